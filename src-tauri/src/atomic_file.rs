@@ -0,0 +1,38 @@
+// Gravação dos arquivos de estado/cache: escreve num `.tmp` ao lado e renomeia por cima, então
+// um crash no meio deixa o arquivo antigo inteiro em vez de um JSON cortado.
+
+use std::{ffi::OsString, fs, io, path::Path};
+
+/// Cria o diretório pai, grava `<path>.tmp` e renomeia para `path`.
+pub fn write_atomic(path: &Path, data: &[u8]) -> io::Result<()> {
+  if let Some(dir) = path.parent() {
+    fs::create_dir_all(dir)?;
+  }
+  let mut tmp = OsString::from(path.as_os_str());
+  tmp.push(".tmp");
+  fs::write(&tmp, data)?;
+  fs::rename(tmp, path)
+}
+
+/// `write_atomic` do JSON indentado de `value`.
+pub fn write_json_atomic<T: serde::Serialize + ?Sized>(path: &Path, value: &T) -> io::Result<()> {
+  let body = serde_json::to_vec_pretty(value).map_err(io::Error::other)?;
+  write_atomic(path, &body)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn replaces_without_leftovers() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("nested").join("state.json");
+
+    write_json_atomic(&path, &["a", "b"]).unwrap();
+    write_json_atomic(&path, &["c"]).unwrap();
+    let back: Vec<String> = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+    assert_eq!(back, ["c"]);
+    assert!(!dir.path().join("nested").join("state.json.tmp").exists());
+  }
+}
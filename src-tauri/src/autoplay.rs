@@ -0,0 +1,405 @@
+// Autoplay da sessão: fica N segundos em cada mapa e toca o próximo sozinho.
+//
+// Relógio + cópia da fila visível (mapcodes e categoria de cada item), como no mass perm:
+// quando o tempo acaba o `poll` devolve o próximo passo, o `lib.rs` digita o `/np` e avisa a
+// webview só para mover a seleção. O `Instant` entra por parâmetro para os testes não
+// precisarem dormir.
+
+use std::{
+  collections::BTreeMap,
+  fs, io,
+  path::Path,
+  time::{Duration, Instant},
+};
+
+use crate::atomic_file::write_json_atomic;
+
+pub const DEFAULT_SECS: u64 = 60;
+pub const MIN_SECS: u64 = 5;
+pub const MAX_SECS: u64 = 900;
+
+fn clamp_secs(secs: u64) -> u64 {
+  secs.clamp(MIN_SECS, MAX_SECS)
+}
+
+/// Tempo por mapa de cada categoria ("P17" → 120), salvo em disco.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Intervals {
+  pub default_secs: u64,
+  #[serde(default)]
+  pub per_category: BTreeMap<String, u64>,
+}
+
+impl Default for Intervals {
+  fn default() -> Self {
+    Self {
+      default_secs: DEFAULT_SECS,
+      per_category: BTreeMap::new(),
+    }
+  }
+}
+
+impl Intervals {
+  pub fn load_file(path: &Path) -> Self {
+    fs::read(path)
+      .ok()
+      .and_then(|raw| serde_json::from_slice(&raw).ok())
+      .unwrap_or_default()
+  }
+
+  pub fn save_file(&self, path: &Path) -> io::Result<()> {
+    write_json_atomic(path, self)
+  }
+
+  pub fn secs_for(&self, category: Option<&str>) -> u64 {
+    let secs = category
+      .and_then(|c| self.per_category.get(c))
+      .copied()
+      .unwrap_or(self.default_secs);
+    clamp_secs(secs)
+  }
+
+  /// Sem categoria muda o padrão; `secs: None` volta a categoria para o padrão.
+  pub fn set(&mut self, category: Option<&str>, secs: Option<u64>) {
+    match (category, secs) {
+      (None, Some(s)) => self.default_secs = clamp_secs(s),
+      (None, None) => self.default_secs = DEFAULT_SECS,
+      (Some(c), Some(s)) => {
+        self.per_category.insert(c.to_string(), clamp_secs(s));
+      }
+      (Some(c), None) => {
+        self.per_category.remove(c);
+      }
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Status {
+  Off,
+  Running,
+  Paused,
+}
+
+/// Item da fila visível; a categoria (fila mista) escolhe o tempo do mapa.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Entry {
+  pub mapcode: String,
+  #[serde(default)]
+  pub category: Option<String>,
+}
+
+/// Próximo mapa a tocar (índice na fila).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+  pub index: usize,
+  pub mapcode: String,
+  pub category: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Tick {
+  /// Mesmo segundo do último poll (não precisa emitir nada).
+  Quiet,
+  /// Contagem (ou status) mudou.
+  Changed,
+  /// Acabou o tempo do mapa atual: tocar este.
+  Advance(Step),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snapshot {
+  pub status: Status,
+  pub category: Option<String>,
+  pub mapcode: Option<String>,
+  pub interval_secs: u64,
+  pub remaining_ms: u64,
+  pub message: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct Timer {
+  status: Status,
+  category: Option<String>,
+  interval: Duration,
+  mapcode: Option<String>,
+  queue: Vec<Entry>,
+  // posição do mapa atual na fila
+  index: Option<usize>,
+  // tempo já assistido do mapa atual (sem contar o trecho corrente)
+  elapsed: Duration,
+  // início do trecho corrente (só rodando)
+  since: Option<Instant>,
+  last_secs: Option<u64>,
+  message: Option<String>,
+}
+
+impl Default for Timer {
+  fn default() -> Self {
+    Self {
+      status: Status::Off,
+      category: None,
+      interval: Duration::from_secs(DEFAULT_SECS),
+      mapcode: None,
+      queue: vec![],
+      index: None,
+      elapsed: Duration::ZERO,
+      since: None,
+      last_secs: None,
+      message: None,
+    }
+  }
+}
+
+impl Timer {
+  pub fn status(&self) -> Status {
+    self.status
+  }
+
+  fn watched(&self, now: Instant) -> Duration {
+    self.elapsed + self.since.map(|s| now.saturating_duration_since(s)).unwrap_or_default()
+  }
+
+  fn remaining(&self, now: Instant) -> Duration {
+    self.interval.saturating_sub(self.watched(now))
+  }
+
+  fn restart_map(&mut self, now: Instant) {
+    self.elapsed = Duration::ZERO;
+    self.since = (self.status == Status::Running).then_some(now);
+    self.last_secs = None;
+  }
+
+  /// Começa a contar do zero no mapa atual.
+  pub fn start(&mut self, category: Option<String>, secs: u64, mapcode: Option<String>, now: Instant) -> Result<(), String> {
+    if mapcode.is_none() {
+      return Err("Select a map first.".into());
+    }
+    let secs = clamp_secs(secs);
+    self.status = Status::Running;
+    self.category = category;
+    self.interval = Duration::from_secs(secs);
+    self.mapcode = mapcode;
+    self.restart_map(now);
+    self.message = Some(format!("Autoplay: next map every {secs}s."));
+    Ok(())
+  }
+
+  pub fn pause(&mut self, reason: &str, now: Instant) {
+    if self.status != Status::Running {
+      return;
+    }
+    self.elapsed = self.watched(now);
+    self.since = None;
+    self.status = Status::Paused;
+    self.last_secs = None;
+    self.message = Some(format!("Autoplay paused ({reason})."));
+  }
+
+  pub fn resume(&mut self, now: Instant) {
+    if self.status != Status::Paused {
+      return;
+    }
+    self.status = Status::Running;
+    self.since = Some(now);
+    self.message = Some("Autoplay resumed.".into());
+  }
+
+  pub fn stop(&mut self, message: &str) {
+    self.status = Status::Off;
+    self.since = None;
+    self.elapsed = Duration::ZERO;
+    self.last_secs = None;
+    self.message = Some(message.to_string());
+  }
+
+  /// Vale na hora (o tempo já assistido do mapa atual é mantido).
+  pub fn set_interval(&mut self, secs: u64) {
+    self.interval = Duration::from_secs(clamp_secs(secs));
+    self.last_secs = None;
+  }
+
+  /// Nova fila visível; a posição vem no próximo `map_changed`.
+  pub fn set_queue(&mut self, queue: Vec<Entry>) {
+    self.queue = queue;
+  }
+
+  /// Chamado a cada `set_np_context` e depois de tocar um passo. Categoria e tempo sempre
+  /// acompanham o mapa (fila mista); a contagem só reinicia se o mapa mudou.
+  /// Devolve `true` quando o snapshot mudou.
+  pub fn map_changed(
+    &mut self,
+    index: Option<usize>,
+    mapcode: Option<String>,
+    category: Option<String>,
+    secs: u64,
+    now: Instant,
+  ) -> bool {
+    let interval = Duration::from_secs(clamp_secs(secs));
+    let meta_changed = category != self.category || interval != self.interval;
+    self.index = index;
+    self.category = category;
+    self.interval = interval;
+    if meta_changed {
+      self.last_secs = None;
+    }
+    if mapcode == self.mapcode {
+      return meta_changed && self.status != Status::Off;
+    }
+    self.mapcode = mapcode;
+    if self.status == Status::Off {
+      return false;
+    }
+    if self.mapcode.is_none() {
+      self.stop("Autoplay stopped: no map selected.");
+    } else {
+      self.restart_map(now);
+    }
+    true
+  }
+
+  pub fn poll(&mut self, now: Instant) -> Tick {
+    if self.status != Status::Running {
+      return Tick::Quiet;
+    }
+    let remaining = self.remaining(now);
+    if remaining.is_zero() {
+      let next = self.index.map_or(self.queue.len(), |i| i + 1);
+      let Some(Entry { mapcode, category }) = self.queue.get(next).cloned() else {
+        self.stop("Autoplay: end of queue.");
+        return Tick::Changed;
+      };
+      // não dispara de novo enquanto o `lib.rs` envia; o `map_changed` do passo reinicia
+      self.restart_map(now);
+      return Tick::Advance(Step {
+        index: next,
+        mapcode,
+        category,
+      });
+    }
+    let secs = remaining.as_millis().div_ceil(1000) as u64;
+    if self.last_secs == Some(secs) {
+      return Tick::Quiet;
+    }
+    self.last_secs = Some(secs);
+    Tick::Changed
+  }
+
+  pub fn snapshot(&self, now: Instant) -> Snapshot {
+    let remaining = match self.status {
+      Status::Off => Duration::ZERO,
+      _ => self.remaining(now),
+    };
+    Snapshot {
+      status: self.status,
+      category: self.category.clone(),
+      mapcode: self.mapcode.clone(),
+      interval_secs: self.interval.as_secs(),
+      remaining_ms: remaining.as_millis() as u64,
+      message: self.message.clone(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn secs(n: u64) -> Duration {
+    Duration::from_secs(n)
+  }
+
+  #[test]
+  fn counts_down_and_pauses() {
+    let t0 = Instant::now();
+    let mut t = Timer::default();
+    assert!(t.start(Some("P17".into()), 10, None, t0).is_err());
+    t.start(Some("P17".into()), 10, Some("@1".into()), t0).unwrap();
+
+    assert_eq!(t.poll(t0), Tick::Changed);
+    assert_eq!(t.poll(t0 + Duration::from_millis(300)), Tick::Quiet);
+    assert_eq!(t.poll(t0 + secs(1)), Tick::Changed);
+
+    // pausa não consome tempo
+    t.pause("hotkey", t0 + secs(4));
+    assert_eq!(t.poll(t0 + secs(30)), Tick::Quiet);
+    assert_eq!(t.snapshot(t0 + secs(30)).remaining_ms, 6_000);
+    t.resume(t0 + secs(30));
+    assert_eq!(t.poll(t0 + secs(35)), Tick::Changed);
+    // sem fila não há próximo
+    assert_eq!(t.poll(t0 + secs(36)), Tick::Changed);
+    assert_eq!(t.status(), Status::Off);
+
+    t.start(None, 5, Some("@1".into()), t0).unwrap();
+    assert!(t.map_changed(None, None, None, 5, t0 + secs(1)));
+    assert_eq!(t.status(), Status::Off);
+  }
+
+  fn entry(mapcode: &str, category: &str) -> Entry {
+    Entry {
+      mapcode: mapcode.into(),
+      category: Some(category.into()),
+    }
+  }
+
+  #[test]
+  fn walks_the_queue_and_stops_at_the_end() {
+    let t0 = Instant::now();
+    let mut t = Timer::default();
+    t.set_queue(vec![entry("@1", "P17"), entry("@2", "P4"), entry("@3", "P17")]);
+    t.map_changed(Some(0), Some("@1".into()), Some("P17".into()), 10, t0);
+    t.start(Some("P17".into()), 10, Some("@1".into()), t0).unwrap();
+
+    let step = match t.poll(t0 + secs(10)) {
+      Tick::Advance(step) => step,
+      other => panic!("expected advance, got {other:?}"),
+    };
+    assert_eq!(
+      step,
+      Step {
+        index: 1,
+        mapcode: "@2".into(),
+        category: Some("P4".into())
+      }
+    );
+    // o passo tocado traz o tempo da categoria dele
+    assert!(t.map_changed(Some(step.index), Some(step.mapcode), step.category, 30, t0 + secs(10)));
+    let snap = t.snapshot(t0 + secs(10));
+    assert_eq!(snap.category.as_deref(), Some("P4"));
+    assert_eq!((snap.interval_secs, snap.remaining_ms), (30, 30_000));
+
+    // a webview confirma a seleção: mesmo mapa não reinicia
+    assert!(!t.map_changed(Some(1), Some("@2".into()), Some("P4".into()), 30, t0 + secs(20)));
+    assert_eq!(t.snapshot(t0 + secs(20)).remaining_ms, 20_000);
+
+    assert!(matches!(t.poll(t0 + secs(40)), Tick::Advance(Step { index: 2, .. })));
+    t.map_changed(Some(2), Some("@3".into()), Some("P17".into()), 10, t0 + secs(40));
+    assert_eq!(t.poll(t0 + secs(50)), Tick::Changed);
+    assert_eq!(t.status(), Status::Off);
+  }
+
+  #[test]
+  fn intervals_per_category() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("autoplay.json");
+    let mut iv = Intervals::default();
+    iv.set(Some("P17"), Some(120));
+    iv.set(Some("P4"), Some(1));
+    iv.set(None, Some(45));
+    iv.save_file(&path).unwrap();
+
+    let back = Intervals::load_file(&path);
+    assert_eq!(back.secs_for(Some("P17")), 120);
+    assert_eq!(back.secs_for(Some("P4")), MIN_SECS);
+    assert_eq!(back.secs_for(Some("P3")), 45);
+    assert_eq!(back.secs_for(None), 45);
+
+    let mut back = back;
+    back.set(Some("P17"), None);
+    assert_eq!(back.secs_for(Some("P17")), 45);
+  }
+}
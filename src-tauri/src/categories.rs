@@ -0,0 +1,245 @@
+// Metadados das categorias e validação das decisões antes do submit: o backend recusa o
+// payload inteiro se um item estiver errado, então aqui os problemas saem por item.
+//
+// A tabela (e o rótulo de cada decisão) vem de `src/app/categories.json`, o mesmo arquivo que
+// a webview importa.
+
+use std::sync::OnceLock;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Decision {
+  LeftAsIs,
+  P1ed,
+  WillBeDiscussed,
+  Ignored,
+}
+
+impl Decision {
+  pub fn parse(raw: &str) -> Option<Self> {
+    match raw.trim() {
+      "left_as_is" => Some(Decision::LeftAsIs),
+      "p1ed" => Some(Decision::P1ed),
+      "will_be_discussed" => Some(Decision::WillBeDiscussed),
+      "ignored" => Some(Decision::Ignored),
+      _ => None,
+    }
+  }
+
+  pub fn as_str(self) -> &'static str {
+    match self {
+      Decision::LeftAsIs => "left_as_is",
+      Decision::P1ed => "p1ed",
+      Decision::WillBeDiscussed => "will_be_discussed",
+      Decision::Ignored => "ignored",
+    }
+  }
+
+  /// Rótulo da UI (`decisions` no JSON); sem rótulo cai no id.
+  pub fn label(self) -> &'static str {
+    registry()
+      .decisions
+      .iter()
+      .find(|d| d.id == self)
+      .map_or(self.as_str(), |d| d.label.as_str())
+  }
+
+  /// Decisões que precisam de texto no review (o que discutir / por que ignorou).
+  pub fn requires_review(self) -> bool {
+    matches!(self, Decision::WillBeDiscussed | Decision::Ignored)
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryMeta {
+  pub code: String,
+  pub description: String,
+  pub picture: String,
+  /// `#RRGGBB`
+  pub color: String,
+  /// Mapas por submitter (`-1` = sem limite); só em categorias revisadas.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub submission_limit: Option<i32>,
+  pub decisions: Vec<Decision>,
+  pub reviewed: bool,
+}
+
+impl CategoryMeta {
+  pub fn allows(&self, decision: Decision) -> bool {
+    self.decisions.contains(&decision)
+  }
+
+  /// Limite positivo, se houver.
+  pub fn limit_per_user(&self) -> Option<i64> {
+    self.submission_limit.filter(|l| *l > 0).map(i64::from)
+  }
+}
+
+pub const ALL_DECISIONS: &[Decision] = &[
+  Decision::LeftAsIs,
+  Decision::P1ed,
+  Decision::WillBeDiscussed,
+  Decision::Ignored,
+];
+
+#[derive(serde::Deserialize)]
+struct DecisionMeta {
+  id: Decision,
+  label: String,
+}
+
+#[derive(serde::Deserialize)]
+struct Registry {
+  decisions: Vec<DecisionMeta>,
+  categories: Vec<CategoryMeta>,
+}
+
+fn registry() -> &'static Registry {
+  static REGISTRY: OnceLock<Registry> = OnceLock::new();
+  // embutido no build; JSON inválido quebra o teste `registry_lookup`, não o usuário
+  REGISTRY.get_or_init(|| {
+    serde_json::from_str(include_str!("../../src/app/categories.json")).expect("invalid src/app/categories.json")
+  })
+}
+
+/// Todas as categorias, na ordem do JSON.
+pub fn all() -> &'static [CategoryMeta] {
+  &registry().categories
+}
+
+/// Aceita "P17", "p17" e "17".
+pub fn find(code: &str) -> Option<&'static CategoryMeta> {
+  let t = code.trim().to_uppercase();
+  let t = t.strip_prefix('P').unwrap_or(&t);
+  all().iter().find(|c| &c.code[1..] == t)
+}
+
+/// Item do payload, só com o que a validação usa.
+#[derive(Clone, Copy, Debug)]
+pub struct ReviewItem<'a> {
+  pub id: &'a str,
+  pub mapcode: &'a str,
+  /// "pending" | "reviewed"
+  pub status: &'a str,
+  pub decision: Option<&'a str>,
+  pub review: &'a str,
+  pub imported_ignored: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemError {
+  pub id: String,
+  pub mapcode: String,
+  /// "missing_decision" | "unknown_decision" | "decision_not_allowed" | "missing_review"
+  pub error: &'static str,
+  pub message: String,
+}
+
+/// Erros por item, na ordem do payload. Vazio = pode enviar.
+pub fn validate(category: &CategoryMeta, items: &[ReviewItem]) -> Vec<ItemError> {
+  let mut out = Vec::new();
+  for item in items {
+    let mut push = |error: &'static str, message: String| {
+      out.push(ItemError {
+        id: item.id.to_string(),
+        mapcode: item.mapcode.to_string(),
+        error,
+        message,
+      })
+    };
+
+    let raw = item.decision.map(str::trim).filter(|d| !d.is_empty());
+    let Some(raw) = raw else {
+      if item.status == "reviewed" {
+        push("missing_decision", format!("{} is marked as reviewed but has no decision", item.mapcode));
+      }
+      continue;
+    };
+    let Some(decision) = Decision::parse(raw) else {
+      push("unknown_decision", format!("{}: unknown decision \"{raw}\"", item.mapcode));
+      continue;
+    };
+    if !category.allows(decision) {
+      push(
+        "decision_not_allowed",
+        format!("{}: {} is not allowed in {}", item.mapcode, decision.as_str(), category.code),
+      );
+      continue;
+    }
+    // ignorados no import vêm com o motivo do bot (às vezes vazio)
+    if decision.requires_review() && !item.imported_ignored && item.review.trim().is_empty() {
+      push(
+        "missing_review",
+        format!("{}: {} needs a review text", item.mapcode, decision.as_str()),
+      );
+    }
+  }
+  out
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn item<'a>(id: &'a str, status: &'a str, decision: Option<&'a str>, review: &'a str) -> ReviewItem<'a> {
+    ReviewItem {
+      id,
+      mapcode: id,
+      status,
+      decision,
+      review,
+      imported_ignored: false,
+    }
+  }
+
+  #[test]
+  fn registry_lookup() {
+    assert_eq!(find("p17").map(|c| c.code.as_str()), Some("P17"));
+    assert_eq!(find(" 4 ").map(|c| c.description.as_str()), Some("Shaman (P4)"));
+    assert!(find("P99").is_none());
+    assert!(find("P1").is_some_and(|c| !c.reviewed));
+    assert_eq!(find("P66").and_then(|c| c.limit_per_user()), None);
+    assert_eq!(find("P10").and_then(|c| c.limit_per_user()), Some(2));
+    // todas as revisadas aceitam left_as_is
+    assert!(all().iter().filter(|c| c.reviewed).all(|c| c.allows(Decision::LeftAsIs)));
+    assert!(all().iter().all(|c| c.code.starts_with('P') && c.color.starts_with('#')));
+    // um rótulo por decisão, na mesma ordem
+    let ids: Vec<Decision> = registry().decisions.iter().map(|d| d.id).collect();
+    assert_eq!(ids, ALL_DECISIONS);
+    assert_eq!(Decision::P1ed.label(), "p1'ed");
+  }
+
+  #[test]
+  fn validates_items_per_category() {
+    let p10 = find("P10").unwrap();
+    let items = [
+      item("@1", "reviewed", Some("left_as_is"), ""),
+      item("@2", "reviewed", Some("p1ed"), "nice"),
+      item("@3", "reviewed", None, ""),
+      item("@4", "pending", None, ""),
+      item("@5", "reviewed", Some("will_be_discussed"), "  "),
+      item("@6", "reviewed", Some("approved"), ""),
+      ReviewItem {
+        imported_ignored: true,
+        ..item("@7", "reviewed", Some("ignored"), "")
+      },
+      item("@8", "reviewed", Some("ignored"), ""),
+    ];
+    let got: Vec<_> = validate(p10, &items).into_iter().map(|e| (e.id, e.error)).collect();
+    assert_eq!(
+      got,
+      vec![
+        ("@2".to_string(), "decision_not_allowed"),
+        ("@3".to_string(), "missing_decision"),
+        ("@5".to_string(), "missing_review"),
+        ("@6".to_string(), "unknown_decision"),
+        ("@8".to_string(), "missing_review"),
+      ]
+    );
+
+    // p1ed vale em P4
+    assert!(validate(find("P4").unwrap(), &[item("@2", "reviewed", Some("p1ed"), "")]).is_empty());
+  }
+}
@@ -0,0 +1,295 @@
+// Observa o clipboard com um único handle do arboard.
+//
+// Linux: usa notificação de troca de dono do CLIPBOARD (XFixes no X11, `wl-paste --watch`
+// via data-control no Wayland) e só lê o texto quando algo mudou. Nas outras plataformas
+// (ou se nada disso estiver disponível) cai no polling.
+
+use std::{
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    Mutex,
+  },
+  thread,
+  time::{Duration, Instant},
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(600);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChangeSource {
+  X11,
+  Wayland,
+  Poll,
+}
+
+impl ChangeSource {
+  pub fn as_str(self) -> &'static str {
+    match self {
+      ChangeSource::X11 => "xfixes",
+      ChangeSource::Wayland => "wayland",
+      ChangeSource::Poll => "poll",
+    }
+  }
+}
+
+// Janela em que mudanças no clipboard vêm do próprio app (paste do `/np`), não do usuário.
+static SUPPRESS_UNTIL: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Ignora mudanças de clipboard pelos próximos `dur` (usado ao injetar comandos via paste).
+pub fn suppress_for(dur: Duration) {
+  if let Ok(mut g) = SUPPRESS_UNTIL.lock() {
+    *g = Some(Instant::now() + dur);
+  }
+}
+
+fn suppressed() -> bool {
+  SUPPRESS_UNTIL
+    .lock()
+    .ok()
+    .and_then(|g| *g)
+    .map(|until| Instant::now() < until)
+    .unwrap_or(false)
+}
+
+enum Notifier {
+  #[cfg(target_os = "linux")]
+  X11(Box<x11::Notifier>),
+  #[cfg(target_os = "linux")]
+  Wayland(wayland::Notifier),
+  Poll,
+}
+
+impl Notifier {
+  fn detect() -> Self {
+    #[cfg(target_os = "linux")]
+    {
+      let session = std::env::var("XDG_SESSION_TYPE").unwrap_or_default();
+      let wayland = session.eq_ignore_ascii_case("wayland") || std::env::var_os("WAYLAND_DISPLAY").is_some();
+      if wayland {
+        if let Some(n) = wayland::Notifier::new() {
+          return Notifier::Wayland(n);
+        }
+      }
+      // no Wayland o XWayland também serve (quando o app roda via X11)
+      if std::env::var_os("DISPLAY").is_some() {
+        if let Some(n) = x11::Notifier::new() {
+          return Notifier::X11(Box::new(n));
+        }
+      }
+    }
+    Notifier::Poll
+  }
+
+  fn source(&self) -> ChangeSource {
+    match self {
+      #[cfg(target_os = "linux")]
+      Notifier::X11(_) => ChangeSource::X11,
+      #[cfg(target_os = "linux")]
+      Notifier::Wayland(_) => ChangeSource::Wayland,
+      Notifier::Poll => ChangeSource::Poll,
+    }
+  }
+
+  /// Espera até `timeout` por uma mudança. `None` = notificador quebrou (cai pro polling).
+  fn wait(&mut self, timeout: Duration) -> Option<bool> {
+    match self {
+      #[cfg(target_os = "linux")]
+      Notifier::X11(n) => n.wait(timeout),
+      #[cfg(target_os = "linux")]
+      Notifier::Wayland(n) => n.wait(timeout),
+      Notifier::Poll => {
+        thread::sleep(POLL_INTERVAL.max(timeout));
+        Some(true)
+      }
+    }
+  }
+}
+
+fn read_text(clipboard: &mut Option<arboard::Clipboard>) -> Option<String> {
+  if clipboard.is_none() {
+    *clipboard = arboard::Clipboard::new().ok();
+  }
+  match clipboard.as_mut()?.get_text() {
+    Ok(txt) => Some(txt),
+    Err(arboard::Error::ContentNotAvailable) => None,
+    Err(_) => {
+      // handle pode ter morrido (ex.: servidor X reiniciou); recria na próxima
+      *clipboard = None;
+      None
+    }
+  }
+}
+
+/// Roda até `running` virar false, chamando `on_change` com o texto (trim) quando ele muda.
+pub fn watch(running: &AtomicBool, mut on_change: impl FnMut(&str, ChangeSource)) {
+  let mut clipboard: Option<arboard::Clipboard> = None;
+  let mut notifier = Notifier::detect();
+  log::info!("clipboard watch using {}", notifier.source().as_str());
+
+  // estado inicial não conta como captura
+  let mut last: Option<String> = read_text(&mut clipboard).map(|t| t.trim().to_string());
+
+  while running.load(Ordering::SeqCst) {
+    let changed = match notifier.wait(Duration::from_millis(200)) {
+      Some(changed) => changed,
+      None => {
+        log::warn!("clipboard notifier failed, falling back to polling");
+        notifier = Notifier::Poll;
+        continue;
+      }
+    };
+    if !changed {
+      continue;
+    }
+
+    let Some(text) = read_text(&mut clipboard) else { continue };
+    let trimmed = text.trim();
+    if trimmed.is_empty() || last.as_deref() == Some(trimmed) {
+      continue;
+    }
+    last = Some(trimmed.to_string());
+    if suppressed() {
+      continue;
+    }
+    on_change(trimmed, notifier.source());
+  }
+}
+
+#[cfg(target_os = "linux")]
+mod x11 {
+  use std::{thread, time::Duration, time::Instant};
+
+  use x11rb::{
+    connection::Connection,
+    protocol::{
+      xfixes::{ConnectionExt as _, SelectionEventMask},
+      xproto::{ConnectionExt as _, CreateWindowAux, WindowClass},
+      Event,
+    },
+    rust_connection::RustConnection,
+  };
+
+  pub struct Notifier {
+    conn: RustConnection,
+  }
+
+  impl Notifier {
+    pub fn new() -> Option<Self> {
+      let (conn, screen_num) = x11rb::connect(None).ok()?;
+      conn.xfixes_query_version(5, 0).ok()?.reply().ok()?;
+
+      let screen = conn.setup().roots.get(screen_num)?;
+      let (root, visual) = (screen.root, screen.root_visual);
+      let win = conn.generate_id().ok()?;
+      conn
+        .create_window(
+          x11rb::COPY_DEPTH_FROM_PARENT,
+          win,
+          root,
+          0,
+          0,
+          1,
+          1,
+          0,
+          WindowClass::INPUT_OUTPUT,
+          visual,
+          &CreateWindowAux::new(),
+        )
+        .ok()?;
+
+      let clipboard = conn.intern_atom(false, b"CLIPBOARD").ok()?.reply().ok()?.atom;
+      conn
+        .xfixes_select_selection_input(
+          win,
+          clipboard,
+          SelectionEventMask::SET_SELECTION_OWNER
+            | SelectionEventMask::SELECTION_WINDOW_DESTROY
+            | SelectionEventMask::SELECTION_CLIENT_CLOSE,
+        )
+        .ok()?;
+      conn.flush().ok()?;
+      Some(Self { conn })
+    }
+
+    pub fn wait(&mut self, timeout: Duration) -> Option<bool> {
+      // poll_for_event (não bloqueia) para conseguir checar o flag de parada
+      let deadline = Instant::now() + timeout;
+      loop {
+        let mut changed = false;
+        while let Some(ev) = self.conn.poll_for_event().ok()? {
+          if let Event::XfixesSelectionNotify(_) = ev {
+            changed = true;
+          }
+        }
+        if changed {
+          // o novo dono ainda pode estar preparando o conteúdo
+          thread::sleep(Duration::from_millis(50));
+          return Some(true);
+        }
+        if Instant::now() >= deadline {
+          return Some(false);
+        }
+        thread::sleep(Duration::from_millis(25));
+      }
+    }
+  }
+}
+
+#[cfg(target_os = "linux")]
+mod wayland {
+  use std::{
+    io::{BufRead, BufReader},
+    process::{Child, Command, Stdio},
+    sync::mpsc,
+    thread,
+    time::Duration,
+  };
+
+  // `wl-paste --watch` (wl-clipboard) usa o protocolo data-control e roda o comando a cada
+  // troca de seleção; com `echo` cada mudança vira uma linha no stdout.
+  pub struct Notifier {
+    child: Child,
+    rx: mpsc::Receiver<()>,
+  }
+
+  impl Notifier {
+    pub fn new() -> Option<Self> {
+      let mut child = Command::new("wl-paste")
+        .args(["--type", "text", "--watch", "echo"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+      let stdout = child.stdout.take()?;
+      let (tx, rx) = mpsc::channel();
+      thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+          if line.is_err() || tx.send(()).is_err() {
+            break;
+          }
+        }
+      });
+      Some(Self { child, rx })
+    }
+
+    pub fn wait(&mut self, timeout: Duration) -> Option<bool> {
+      match self.rx.recv_timeout(timeout) {
+        Ok(()) => {
+          while self.rx.try_recv().is_ok() {}
+          Some(true)
+        }
+        Err(mpsc::RecvTimeoutError::Timeout) => Some(false),
+        // wl-paste saiu (compositor sem data-control, por exemplo)
+        Err(mpsc::RecvTimeoutError::Disconnected) => None,
+      }
+    }
+  }
+
+  impl Drop for Notifier {
+    fn drop(&mut self) {
+      let _ = self.child.kill();
+      let _ = self.child.wait();
+    }
+  }
+}
@@ -0,0 +1,547 @@
+// API de controle local (HTTP em 127.0.0.1) para stream deck, scripts do OBS e bots.
+//
+// Desligada por padrão. Toda requisição precisa do token da instalação, em
+// `Authorization: Bearer <token>` ou `?token=<token>`. Rotas:
+// - `GET /v1/state`: posição na fila e o `NpContext`.
+// - `POST /v1/action`: `{"action":"next"|"prev"|"replay"}`,
+//   `{"action":"decision","decision":"p1ed"}` ou `{"action":"template","prefix":"/p 17","suffix":null}`.
+// - `GET /v1/events`: SSE com o mesmo JSON do `/v1/state` a cada `Server::notify`.
+// - `GET /overlay`: página para browser source do OBS (escuta o `/v1/events`).
+// Respostas: `{"ok":true,"data":...}` ou `{"ok":false,"error":"...","message":"..."}`.
+//
+// Aqui fica só o servidor e o roteamento; o que cada ação faz é do `Handler` (no `lib.rs`).
+
+use std::{
+  fs,
+  io::{self, BufRead, BufReader, Read, Write},
+  net::{Ipv4Addr, TcpListener, TcpStream},
+  path::Path,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc, Arc, Mutex,
+  },
+  thread,
+  time::Duration,
+};
+
+use crate::atomic_file::write_json_atomic;
+
+pub const DEFAULT_PORT: u16 = 17321;
+
+const MAX_HEADER_BYTES: usize = 16 * 1024;
+const MAX_BODY_BYTES: usize = 64 * 1024;
+const ACCEPT_POLL: Duration = Duration::from_millis(100);
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+// comentário SSE periódico: detecta cliente que saiu e segura proxies
+const EVENTS_KEEPALIVE: Duration = Duration::from_secs(15);
+
+const OVERLAY_HTML: &str = include_str!("overlay.html");
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Config {
+  pub enabled: bool,
+  pub port: u16,
+  pub token: String,
+}
+
+impl Config {
+  /// Sem arquivo (primeira execução) gera o token e salva.
+  pub fn load_file(path: &Path) -> Self {
+    if let Some(cfg) = fs::read(path)
+      .ok()
+      .and_then(|raw| serde_json::from_slice::<Config>(&raw).ok())
+      .filter(|c| !c.token.is_empty())
+    {
+      return cfg;
+    }
+    let cfg = Config {
+      enabled: false,
+      port: DEFAULT_PORT,
+      token: new_token(),
+    };
+    if let Err(e) = cfg.save_file(path) {
+      log::warn!("control api: failed to save config: {e}");
+    }
+    cfg
+  }
+
+  pub fn save_file(&self, path: &Path) -> io::Result<()> {
+    write_json_atomic(path, self)
+  }
+}
+
+/// 128 bits do gerador do SO, em hex.
+pub fn new_token() -> String {
+  let mut buf = [0u8; 16];
+  // sem entropia do SO não dá para gerar token que preste; nunca acontece nas plataformas suportadas
+  getrandom::fill(&mut buf).expect("OS random source unavailable");
+  buf.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum Action {
+  Next,
+  Prev,
+  Replay,
+  Decision { decision: String },
+  Template { prefix: String, suffix: Option<String> },
+}
+
+pub trait Handler: Send + Sync {
+  fn state(&self) -> serde_json::Value;
+  fn action(&self, action: Action) -> Result<serde_json::Value, String>;
+}
+
+#[derive(Debug, Default)]
+pub struct Request {
+  pub method: String,
+  pub path: String,
+  pub query: Vec<(String, String)>,
+  /// Nomes em minúsculas.
+  pub headers: Vec<(String, String)>,
+  pub body: Vec<u8>,
+}
+
+impl Request {
+  pub fn header(&self, name: &str) -> Option<&str> {
+    self
+      .headers
+      .iter()
+      .find(|(k, _)| k == name)
+      .map(|(_, v)| v.as_str())
+  }
+
+  pub fn query_param(&self, name: &str) -> Option<&str> {
+    self.query.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+  }
+
+  fn authorized(&self, token: &str) -> bool {
+    self.token().is_some_and(|t| token_matches(t, token))
+  }
+
+  fn token(&self) -> Option<&str> {
+    self
+      .header("authorization")
+      .and_then(|v| v.strip_prefix("Bearer "))
+      .map(str::trim)
+      .or_else(|| self.query_param("token"))
+  }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Response {
+  pub status: u16,
+  pub content_type: &'static str,
+  pub body: String,
+}
+
+impl Response {
+  pub fn json(status: u16, body: serde_json::Value) -> Self {
+    Self {
+      status,
+      content_type: "application/json",
+      body: body.to_string(),
+    }
+  }
+
+  fn html(body: &str) -> Self {
+    Self {
+      status: 200,
+      content_type: "text/html; charset=utf-8",
+      body: body.to_string(),
+    }
+  }
+
+  fn ok(data: serde_json::Value) -> Self {
+    Self::json(200, serde_json::json!({ "ok": true, "data": data }))
+  }
+
+  fn error(status: u16, error: &str, message: &str) -> Self {
+    Self::json(
+      status,
+      serde_json::json!({ "ok": false, "error": error, "message": message }),
+    )
+  }
+}
+
+// comparação sem sair no primeiro byte diferente
+fn token_matches(given: &str, expected: &str) -> bool {
+  given.len() == expected.len()
+    && given
+      .bytes()
+      .zip(expected.bytes())
+      .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+      == 0
+}
+
+pub fn route(req: &Request, token: &str, handler: &dyn Handler) -> Response {
+  if !req.authorized(token) {
+    return Response::error(401, "unauthorized", "missing or wrong token");
+  }
+  match (req.method.as_str(), req.path.as_str()) {
+    ("GET", "/v1/state") => Response::ok(handler.state()),
+    ("GET", "/overlay") => Response::html(OVERLAY_HTML),
+    ("POST", "/v1/action") => {
+      let action = match serde_json::from_slice::<Action>(&req.body) {
+        Ok(a) => a,
+        Err(e) => return Response::error(400, "invalid_action", &e.to_string()),
+      };
+      match handler.action(action) {
+        Ok(data) => Response::ok(data),
+        Err(e) => Response::error(422, "action_failed", &e),
+      }
+    }
+    (_, "/v1/state" | "/v1/action" | "/v1/events" | "/overlay") => Response::error(405, "method_not_allowed", "wrong method"),
+    _ => Response::error(404, "not_found", "unknown route"),
+  }
+}
+
+fn parse_query(raw: &str) -> Vec<(String, String)> {
+  raw
+    .split('&')
+    .filter(|kv| !kv.is_empty())
+    .map(|kv| match kv.split_once('=') {
+      Some((k, v)) => (k.to_string(), v.to_string()),
+      None => (kv.to_string(), String::new()),
+    })
+    .collect()
+}
+
+pub fn read_request(stream: impl Read) -> io::Result<Request> {
+  let bad = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_string());
+  let mut reader = BufReader::new(stream);
+  // linha de requisição + headers nunca passam disso, nem numa linha só sem `\n`
+  let mut head = reader.by_ref().take(MAX_HEADER_BYTES as u64);
+  let mut line = String::new();
+  let mut next_line = |line: &mut String| -> io::Result<usize> {
+    line.clear();
+    let n = head.read_line(line)?;
+    if n > 0 && !line.ends_with('\n') && head.limit() == 0 {
+      return Err(bad("headers too large"));
+    }
+    Ok(n)
+  };
+
+  next_line(&mut line)?;
+  let mut parts = line.split_whitespace();
+  let method = parts.next().ok_or_else(|| bad("empty request"))?.to_string();
+  let target = parts.next().ok_or_else(|| bad("missing path"))?;
+  let (path, query) = target.split_once('?').unwrap_or((target, ""));
+  let mut req = Request {
+    method,
+    path: path.to_string(),
+    query: parse_query(query),
+    ..Request::default()
+  };
+
+  loop {
+    if next_line(&mut line)? == 0 {
+      break;
+    }
+    let l = line.trim_end();
+    if l.is_empty() {
+      break;
+    }
+    if let Some((k, v)) = l.split_once(':') {
+      req.headers.push((k.trim().to_ascii_lowercase(), v.trim().to_string()));
+    }
+  }
+
+  let len = req
+    .header("content-length")
+    .and_then(|v| v.parse::<usize>().ok())
+    .unwrap_or(0);
+  if len > MAX_BODY_BYTES {
+    return Err(bad("body too large"));
+  }
+  req.body = vec![0; len];
+  reader.read_exact(&mut req.body)?;
+  Ok(req)
+}
+
+fn reason(status: u16) -> &'static str {
+  match status {
+    200 => "OK",
+    400 => "Bad Request",
+    401 => "Unauthorized",
+    404 => "Not Found",
+    405 => "Method Not Allowed",
+    422 => "Unprocessable Entity",
+    _ => "Error",
+  }
+}
+
+pub fn write_response(mut stream: impl Write, res: &Response) -> io::Result<()> {
+  write!(
+    stream,
+    "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+    res.status,
+    reason(res.status),
+    res.content_type,
+    res.body.len()
+  )?;
+  stream.write_all(res.body.as_bytes())?;
+  stream.flush()
+}
+
+// Clientes do `/v1/events`; cada um recebe o JSON já serializado.
+#[derive(Default)]
+struct Subscribers(Mutex<Vec<mpsc::Sender<String>>>);
+
+impl Subscribers {
+  fn subscribe(&self) -> mpsc::Receiver<String> {
+    let (tx, rx) = mpsc::channel();
+    if let Ok(mut list) = self.0.lock() {
+      list.push(tx);
+    }
+    rx
+  }
+
+  fn is_empty(&self) -> bool {
+    self.0.lock().map(|l| l.is_empty()).unwrap_or(true)
+  }
+
+  fn publish(&self, data: &str) {
+    if let Ok(mut list) = self.0.lock() {
+      list.retain(|tx| tx.send(data.to_string()).is_ok());
+    }
+  }
+
+  // derruba os streams abertos (o `recv` deles vê o canal fechado)
+  fn close(&self) {
+    if let Ok(mut list) = self.0.lock() {
+      list.clear();
+    }
+  }
+}
+
+fn stream_events(mut stream: TcpStream, handler: &dyn Handler, subscribers: &Subscribers) {
+  let rx = subscribers.subscribe();
+  let head = "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-store\r\nConnection: keep-alive\r\n\r\n";
+  if stream.write_all(head.as_bytes()).is_err() {
+    return;
+  }
+  let mut next = Some(handler.state().to_string());
+  loop {
+    let chunk = match next.take() {
+      Some(data) => format!("data: {data}\n\n"),
+      None => ": ping\n\n".to_string(),
+    };
+    if stream.write_all(chunk.as_bytes()).and_then(|_| stream.flush()).is_err() {
+      return;
+    }
+    match rx.recv_timeout(EVENTS_KEEPALIVE) {
+      Ok(data) => next = Some(data),
+      Err(mpsc::RecvTimeoutError::Timeout) => {}
+      Err(mpsc::RecvTimeoutError::Disconnected) => return,
+    }
+  }
+}
+
+fn serve(mut stream: TcpStream, token: &str, handler: &dyn Handler, subscribers: &Subscribers) {
+  let _ = stream.set_nonblocking(false);
+  let _ = stream.set_read_timeout(Some(CLIENT_TIMEOUT));
+  let _ = stream.set_write_timeout(Some(CLIENT_TIMEOUT));
+  let res = match read_request(&mut stream) {
+    Ok(req) if req.method == "GET" && req.path == "/v1/events" && req.authorized(token) => {
+      return stream_events(stream, handler, subscribers);
+    }
+    Ok(req) => route(&req, token, handler),
+    Err(e) => Response::error(400, "bad_request", &e.to_string()),
+  };
+  let _ = write_response(&mut stream, &res);
+}
+
+/// Servidor rodando; para no `stop` (ou quando é descartado).
+pub struct Server {
+  running: Arc<AtomicBool>,
+  handle: Option<thread::JoinHandle<()>>,
+  port: u16,
+  handler: Arc<dyn Handler>,
+  subscribers: Arc<Subscribers>,
+}
+
+impl Server {
+  /// Só escuta em 127.0.0.1.
+  pub fn start(port: u16, token: String, handler: Arc<dyn Handler>) -> io::Result<Self> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+    let port = listener.local_addr()?.port();
+    // não bloqueia no accept para conseguir checar o flag de parada
+    listener.set_nonblocking(true)?;
+    let running = Arc::new(AtomicBool::new(true));
+    let subscribers = Arc::new(Subscribers::default());
+    let flag = running.clone();
+    let (h, subs) = (handler.clone(), subscribers.clone());
+    let handle = thread::spawn(move || {
+      while flag.load(Ordering::SeqCst) {
+        match listener.accept() {
+          Ok((stream, _)) => {
+            let token = token.clone();
+            let (handler, subscribers) = (h.clone(), subs.clone());
+            thread::spawn(move || serve(stream, &token, handler.as_ref(), &subscribers));
+          }
+          Err(e) if e.kind() == io::ErrorKind::WouldBlock => thread::sleep(ACCEPT_POLL),
+          Err(e) => {
+            log::warn!("control api: accept failed: {e}");
+            thread::sleep(ACCEPT_POLL);
+          }
+        }
+      }
+    });
+    Ok(Self {
+      running,
+      handle: Some(handle),
+      port,
+      handler,
+      subscribers,
+    })
+  }
+
+  /// Manda o estado atual para quem está no `/v1/events` (overlay).
+  pub fn notify(&self) {
+    if !self.subscribers.is_empty() {
+      self.subscribers.publish(&self.handler.state().to_string());
+    }
+  }
+
+  pub fn port(&self) -> u16 {
+    self.port
+  }
+
+  pub fn stop(&mut self) {
+    self.running.store(false, Ordering::SeqCst);
+    self.subscribers.close();
+    if let Some(h) = self.handle.take() {
+      let _ = h.join();
+    }
+  }
+}
+
+impl Drop for Server {
+  fn drop(&mut self) {
+    self.stop();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Mutex;
+
+  use super::*;
+
+  #[derive(Default)]
+  struct Fake {
+    actions: Mutex<Vec<Action>>,
+  }
+
+  impl Handler for Fake {
+    fn state(&self) -> serde_json::Value {
+      serde_json::json!({ "mapcode": "@1" })
+    }
+
+    fn action(&self, action: Action) -> Result<serde_json::Value, String> {
+      if action == Action::Replay {
+        return Err("no map selected".into());
+      }
+      self.actions.lock().unwrap().push(action);
+      Ok(serde_json::Value::Null)
+    }
+  }
+
+  fn req(raw: &str) -> Request {
+    read_request(raw.as_bytes()).unwrap()
+  }
+
+  #[test]
+  fn routes_with_token() {
+    let fake = Fake::default();
+    let res = route(&req("GET /v1/state HTTP/1.1\r\nHost: x\r\n\r\n"), "abc", &fake);
+    assert_eq!(res.status, 401);
+    let res = route(&req("GET /v1/state?token=abd HTTP/1.1\r\n\r\n"), "abc", &fake);
+    assert_eq!(res.status, 401);
+
+    let res = route(&req("GET /v1/state?token=abc HTTP/1.1\r\n\r\n"), "abc", &fake);
+    assert_eq!(res.status, 200);
+    assert_eq!(res.body, r#"{"data":{"mapcode":"@1"},"ok":true}"#);
+
+    let body = r#"{"action":"decision","decision":"p1ed"}"#;
+    let raw = format!(
+      "POST /v1/action HTTP/1.1\r\nAuthorization: Bearer abc\r\nContent-Length: {}\r\n\r\n{body}",
+      body.len()
+    );
+    assert_eq!(route(&req(&raw), "abc", &fake).status, 200);
+    let raw = "POST /v1/action HTTP/1.1\r\nAuthorization: Bearer abc\r\nContent-Length: 19\r\n\r\n{\"action\":\"replay\"}";
+    let res = route(&req(raw), "abc", &fake);
+    assert_eq!((res.status, res.body.contains("no map selected")), (422, true));
+    let raw = "POST /v1/action HTTP/1.1\r\nAuthorization: Bearer abc\r\nContent-Length: 17\r\n\r\n{\"action\":\"jump\"}";
+    assert_eq!(route(&req(raw), "abc", &fake).status, 400);
+    assert_eq!(route(&req("GET /v1/action?token=abc HTTP/1.1\r\n\r\n"), "abc", &fake).status, 405);
+    assert_eq!(route(&req("GET /nope?token=abc HTTP/1.1\r\n\r\n"), "abc", &fake).status, 404);
+    let res = route(&req("GET /overlay?token=abc HTTP/1.1\r\n\r\n"), "abc", &fake);
+    assert_eq!((res.status, res.content_type), (200, "text/html; charset=utf-8"));
+
+    assert_eq!(
+      *fake.actions.lock().unwrap(),
+      vec![Action::Decision {
+        decision: "p1ed".into()
+      }]
+    );
+  }
+
+  #[test]
+  fn bounds_header_reads() {
+    // linha sem fim não pode ser lida inteira para a memória
+    let err = read_request(io::repeat(b'a')).unwrap_err();
+    assert_eq!(err.to_string(), "headers too large");
+    let many = format!("GET / HTTP/1.1\r\n{}\r\n", "X-Pad: 0123456789\r\n".repeat(1000));
+    assert!(read_request(many.as_bytes()).is_err());
+    // corpo não conta no limite dos headers
+    let body = "x".repeat(MAX_HEADER_BYTES);
+    let raw = format!("POST /v1/action HTTP/1.1\r\nContent-Length: {}\r\n\r\n{body}", body.len());
+    assert_eq!(read_request(raw.as_bytes()).unwrap().body.len(), MAX_HEADER_BYTES);
+
+    let token = new_token();
+    assert_eq!(token.len(), 32);
+    assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+    assert_ne!(token, new_token());
+  }
+
+  #[test]
+  fn serves_over_tcp_and_stops() {
+    let fake: Arc<dyn Handler> = Arc::new(Fake::default());
+    let mut server = Server::start(0, "tok".into(), fake).unwrap();
+    let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, server.port())).unwrap();
+    stream.write_all(b"GET /v1/state HTTP/1.1\r\nAuthorization: Bearer tok\r\n\r\n").unwrap();
+    let mut out = String::new();
+    stream.read_to_string(&mut out).unwrap();
+    assert!(out.starts_with("HTTP/1.1 200 OK\r\n"), "{out}");
+    assert!(out.ends_with(r#"{"data":{"mapcode":"@1"},"ok":true}"#));
+
+    // SSE: estado ao conectar e a cada notify; stop fecha o stream
+    let mut events = TcpStream::connect((Ipv4Addr::LOCALHOST, server.port())).unwrap();
+    events.write_all(b"GET /v1/events?token=tok HTTP/1.1\r\n\r\n").unwrap();
+    let mut events = BufReader::new(events);
+    let mut data = Vec::new();
+    let mut line = String::new();
+    while data.len() < 2 {
+      line.clear();
+      events.read_line(&mut line).unwrap();
+      if line == "Content-Type: text/event-stream\r\n" {
+        server.notify();
+      }
+      if let Some(d) = line.strip_prefix("data: ") {
+        data.push(d.trim_end().to_string());
+      }
+    }
+    assert_eq!(data, vec![r#"{"mapcode":"@1"}"#; 2]);
+    server.stop();
+    let mut rest = String::new();
+    events.read_to_string(&mut rest).unwrap();
+
+    let token = new_token();
+    assert_eq!(token.len(), 32);
+    assert_ne!(token, new_token());
+  }
+}
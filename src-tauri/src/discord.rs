@@ -0,0 +1,449 @@
+// Resumo da sessão num webhook do Discord (opcional), depois do submit.
+//
+// Mensagens com embed na cor da categoria: contagem por decisão, quem revisou e a lista dos
+// mapas p1'ed, quebrada em várias mensagens quando passa do limite do Discord. 429 espera o
+// `retry_after`; 5xx e falha ao conectar tentam de novo com backoff. Erro depois de a
+// requisição sair (timeout, conexão caída) não repete: a mensagem pode ter sido postada e
+// repetir duplicaria no canal. A URL pode apontar para um stub local (qualquer http://) para
+// testar sem postar no canal.
+
+use std::{fs, io, path::Path, thread, time::Duration};
+
+use crate::{
+  atomic_file::write_json_atomic,
+  categories::{self, Decision},
+};
+
+pub const MAX_ATTEMPTS: usize = 4;
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+// não fica preso se o Discord pedir uma espera absurda
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(30);
+// limite do Discord é 4096 por descrição (e 6000 por mensagem): uma embed por mensagem
+const DESCRIPTION_LIMIT: usize = 4000;
+const DEFAULT_COLOR: u32 = 0x5865F2;
+const USERNAME: &str = "Maps Reviewer";
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Config {
+  /// `None` desliga a postagem.
+  pub webhook_url: Option<String>,
+}
+
+impl Config {
+  pub fn load_file(path: &Path) -> Self {
+    fs::read(path)
+      .ok()
+      .and_then(|raw| serde_json::from_slice(&raw).ok())
+      .unwrap_or_default()
+  }
+
+  pub fn save_file(&self, path: &Path) -> io::Result<()> {
+    write_json_atomic(path, self)
+  }
+}
+
+/// Só http(s); devolve a URL sem espaços.
+pub fn validate_url(raw: &str) -> Result<String, String> {
+  let raw = raw.trim();
+  let url = reqwest::Url::parse(raw).map_err(|e| format!("invalid webhook URL: {e}"))?;
+  if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+    return Err("invalid webhook URL: expected http(s)://".into());
+  }
+  Ok(raw.to_string())
+}
+
+pub struct ReviewedMap<'a> {
+  pub mapcode: &'a str,
+  pub author: Option<&'a str>,
+  pub decision: Option<&'a str>,
+}
+
+// nomes de autor com `_`/`*` viram formatação no Discord
+fn escape(text: &str) -> String {
+  let mut out = String::with_capacity(text.len());
+  for c in text.chars() {
+    if matches!(c, '\\' | '*' | '_' | '~' | '`' | '|' | '>') {
+      out.push('\\');
+    }
+    out.push(c);
+  }
+  out
+}
+
+fn color(category: Option<&categories::CategoryMeta>) -> u32 {
+  category
+    .and_then(|c| u32::from_str_radix(c.color.trim_start_matches('#'), 16).ok())
+    .unwrap_or(DEFAULT_COLOR)
+}
+
+// junta as linhas em blocos de até `limit` caracteres (linha gigante é cortada)
+fn chunk_lines(lines: &[String], limit: usize) -> Vec<String> {
+  let mut chunks: Vec<String> = Vec::new();
+  let mut cur = String::new();
+  for line in lines {
+    let line: String = line.chars().take(limit).collect();
+    if !cur.is_empty() && cur.chars().count() + 1 + line.chars().count() > limit {
+      chunks.push(std::mem::take(&mut cur));
+    }
+    if !cur.is_empty() {
+      cur.push('\n');
+    }
+    cur.push_str(&line);
+  }
+  if !cur.is_empty() {
+    chunks.push(cur);
+  }
+  chunks
+}
+
+/// Corpos JSON prontos para o webhook, na ordem de envio.
+pub fn summary_messages(category: &str, reviewer: Option<&str>, maps: &[ReviewedMap]) -> Vec<serde_json::Value> {
+  let meta = categories::find(category);
+  let color = color(meta);
+  let title = meta.map(|m| m.description.as_str()).unwrap_or(category);
+
+  let decision_of = |m: &ReviewedMap| m.decision.and_then(Decision::parse);
+  let mut fields: Vec<serde_json::Value> = categories::ALL_DECISIONS
+    .iter()
+    .map(|d| (d, maps.iter().filter(|m| decision_of(m) == Some(*d)).count()))
+    .filter(|(d, n)| *n > 0 || meta.is_some_and(|m| m.allows(**d)))
+    .map(|(d, n)| serde_json::json!({ "name": d.label(), "value": n.to_string(), "inline": true }))
+    .collect();
+  let undecided = maps.iter().filter(|m| decision_of(m).is_none()).count();
+  if undecided > 0 {
+    fields.push(serde_json::json!({ "name": "no decision", "value": undecided.to_string(), "inline": true }));
+  }
+
+  let p1ed: Vec<String> = maps
+    .iter()
+    .filter(|m| decision_of(m) == Some(Decision::P1ed))
+    .map(|m| {
+      let code = format!("@{}", m.mapcode.trim_start_matches('@'));
+      match m.author.map(str::trim).filter(|a| !a.is_empty()) {
+        Some(author) => format!("`{code}` by {}", escape(author)),
+        None => format!("`{code}`"),
+      }
+    })
+    .collect();
+  let mut chunks = chunk_lines(&p1ed, DESCRIPTION_LIMIT).into_iter();
+
+  let mut first = serde_json::json!({
+    "title": format!("{title} review: {} map(s)", maps.len()),
+    "color": color,
+    "description": chunks.next().unwrap_or_else(|| "No maps p1'ed.".to_string()),
+    "fields": fields,
+  });
+  if let Some(reviewer) = reviewer.map(str::trim).filter(|r| !r.is_empty()) {
+    first["footer"] = serde_json::json!({ "text": format!("Reviewer: {reviewer}") });
+  }
+
+  let rest: Vec<String> = chunks.collect();
+  let parts = rest.len() + 1;
+  let message = |embed: serde_json::Value| {
+    serde_json::json!({
+      "username": USERNAME,
+      // nomes de mapa/autor nunca pingam ninguém
+      "allowed_mentions": { "parse": [] },
+      "embeds": [embed],
+    })
+  };
+  let mut out = vec![message(first)];
+  for (i, chunk) in rest.into_iter().enumerate() {
+    out.push(message(serde_json::json!({
+      "title": format!("p1'ed maps ({}/{parts})", i + 2),
+      "color": color,
+      "description": chunk,
+    })));
+  }
+  out
+}
+
+#[derive(Debug)]
+enum SendError {
+  /// Vale tentar de novo; `Some` quando o Discord disse quanto esperar.
+  Transient(String, Option<Duration>),
+  Fatal(String),
+}
+
+pub struct Webhook {
+  client: reqwest::blocking::Client,
+  url: String,
+  retry_delay: Duration,
+}
+
+impl Webhook {
+  pub fn new(url: &str) -> Result<Self, String> {
+    let client = reqwest::blocking::Client::builder()
+      .timeout(Duration::from_secs(10))
+      .build()
+      .map_err(|e| e.to_string())?;
+    Ok(Self {
+      client,
+      url: validate_url(url)?,
+      retry_delay: RETRY_BASE_DELAY,
+    })
+  }
+
+  fn post_once(&self, body: &serde_json::Value) -> Result<(), SendError> {
+    let resp = self
+      .client
+      .post(&self.url)
+      .json(body)
+      .send()
+      .map_err(|e| {
+        // a URL do webhook é o segredo: não pode ir pro log nem pro evento de status
+        let e = e.without_url();
+        if e.is_connect() {
+          SendError::Transient(e.to_string(), None)
+        } else {
+          SendError::Fatal(format!("{e} (not retried: it may have been posted)"))
+        }
+      })?;
+    let status = resp.status();
+    if status.is_success() {
+      return Ok(());
+    }
+    let header_wait = resp
+      .headers()
+      .get("retry-after")
+      .and_then(|v| v.to_str().ok())
+      .and_then(|v| v.parse::<f64>().ok());
+    let text = resp.text().unwrap_or_default();
+    let message = format!("HTTP {}: {}", status.as_u16(), text.chars().take(200).collect::<String>());
+    if status.as_u16() == 429 {
+      // o corpo traz `retry_after` em segundos (com fração)
+      let wait = serde_json::from_str::<serde_json::Value>(&text)
+        .ok()
+        .and_then(|v| v.get("retry_after").and_then(|r| r.as_f64()))
+        .or(header_wait)
+        .map(|s| Duration::from_secs_f64(s.max(0.0).min(MAX_RETRY_AFTER.as_secs_f64())));
+      return Err(SendError::Transient(message, wait));
+    }
+    if status.is_server_error() {
+      return Err(SendError::Transient(message, None));
+    }
+    Err(SendError::Fatal(message))
+  }
+
+  fn post(&self, body: &serde_json::Value) -> Result<(), String> {
+    let mut attempt = 1;
+    loop {
+      match self.post_once(body) {
+        Ok(()) => return Ok(()),
+        Err(SendError::Transient(e, wait)) if attempt < MAX_ATTEMPTS => {
+          log::warn!("discord webhook failed (attempt {attempt}/{MAX_ATTEMPTS}): {e}");
+          thread::sleep(wait.unwrap_or(self.retry_delay * (1 << (attempt - 1))));
+          attempt += 1;
+        }
+        Err(SendError::Transient(e, _) | SendError::Fatal(e)) => return Err(e),
+      }
+    }
+  }
+
+  /// Envia em ordem e para na primeira mensagem que falhar.
+  pub fn post_all(&self, messages: &[serde_json::Value]) -> PostReport {
+    let total = messages.len();
+    for (i, msg) in messages.iter().enumerate() {
+      if let Err(e) = self.post(msg) {
+        return PostReport {
+          posted: i,
+          total,
+          error: Some(format!("message {}/{total} not posted: {e}", i + 1)),
+        };
+      }
+    }
+    PostReport {
+      posted: total,
+      total,
+      error: None,
+    }
+  }
+}
+
+/// Resultado do `post_all`: as `posted` primeiras de `total` mensagens chegaram no canal.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostReport {
+  pub posted: usize,
+  pub total: usize,
+  /// `None` = todas postadas.
+  pub error: Option<String>,
+}
+
+impl PostReport {
+  /// Nada postado (ex.: URL inválida).
+  pub fn failed(total: usize, error: String) -> Self {
+    Self {
+      posted: 0,
+      total,
+      error: Some(error),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::net::{Ipv4Addr, TcpListener};
+
+  use super::*;
+  use crate::control_api::{read_request, write_response, Response};
+
+  // stub local do webhook: responde na ordem dada e devolve os corpos recebidos
+  fn stub(replies: Vec<(u16, serde_json::Value)>) -> (String, thread::JoinHandle<Vec<serde_json::Value>>) {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let url = format!("http://127.0.0.1:{}/api/webhooks/1/abc", listener.local_addr().unwrap().port());
+    let handle = thread::spawn(move || {
+      let mut bodies = Vec::new();
+      for (status, reply) in replies {
+        let (mut stream, _) = listener.accept().unwrap();
+        let req = read_request(&mut stream).unwrap();
+        bodies.push(serde_json::from_slice(&req.body).unwrap());
+        write_response(&mut stream, &Response::json(status, reply)).unwrap();
+      }
+      bodies
+    });
+    (url, handle)
+  }
+
+  fn maps(n: usize) -> Vec<(String, String)> {
+    (0..n).map(|i| (format!("@{}", 7_000_000 + i), format!("Author_{i}#0000"))).collect()
+  }
+
+  #[test]
+  fn summary_counts_colour_and_chunks() {
+    let data = maps(300);
+    let mut reviewed: Vec<ReviewedMap> = data
+      .iter()
+      .map(|(mc, author)| ReviewedMap {
+        mapcode: mc,
+        author: Some(author),
+        decision: Some("p1ed"),
+      })
+      .collect();
+    reviewed.push(ReviewedMap {
+      mapcode: "@1",
+      author: None,
+      decision: Some("ignored"),
+    });
+    reviewed.push(ReviewedMap {
+      mapcode: "@2",
+      author: None,
+      decision: None,
+    });
+
+    let msgs = summary_messages("p17", Some("Reviewer"), &reviewed);
+    assert_eq!(msgs.len(), 3);
+    let first = &msgs[0]["embeds"][0];
+    assert_eq!(first["title"], "Racing (P17) review: 302 map(s)");
+    assert_eq!(first["color"], 0xC32C12);
+    assert_eq!(first["footer"]["text"], "Reviewer: Reviewer");
+    let fields: Vec<(String, String)> = first["fields"]
+      .as_array()
+      .unwrap()
+      .iter()
+      .map(|f| (f["name"].as_str().unwrap().into(), f["value"].as_str().unwrap().into()))
+      .collect();
+    assert_eq!(
+      fields,
+      [("left as is", "0"), ("p1'ed", "300"), ("will be discussed", "0"), ("ignored", "1"), ("no decision", "1")]
+        .map(|(a, b)| (a.to_string(), b.to_string()))
+    );
+    assert!(first["description"].as_str().unwrap().starts_with("`@7000000` by Author\\_0#0000\n"));
+    assert_eq!(msgs[2]["embeds"][0]["title"], "p1'ed maps (3/3)");
+    let lines: usize = msgs
+      .iter()
+      .map(|m| {
+        let d = m["embeds"][0]["description"].as_str().unwrap();
+        assert!(d.chars().count() <= DESCRIPTION_LIMIT);
+        d.lines().count()
+      })
+      .sum();
+    assert_eq!(lines, 300);
+
+    // sem p1'ed: uma mensagem só
+    let msgs = summary_messages("P3", None, &reviewed[300..]);
+    assert_eq!(msgs.len(), 1);
+    assert_eq!(msgs[0]["embeds"][0]["description"], "No maps p1'ed.");
+    assert!(msgs[0]["embeds"][0].get("footer").is_none());
+  }
+
+  #[test]
+  fn retries_rate_limits_and_server_errors() {
+    let msgs = vec![serde_json::json!({ "content": "a" }), serde_json::json!({ "content": "b" })];
+    let (url, stub_handle) = stub(vec![
+      (429, serde_json::json!({ "retry_after": 0.01 })),
+      (204, serde_json::Value::Null),
+      (502, serde_json::Value::Null),
+      (204, serde_json::Value::Null),
+    ]);
+    let mut hook = Webhook::new(&url).unwrap();
+    hook.retry_delay = Duration::from_millis(1);
+    assert_eq!(
+      hook.post_all(&msgs),
+      PostReport {
+        posted: 2,
+        total: 2,
+        error: None,
+      }
+    );
+    let bodies = stub_handle.join().unwrap();
+    let sent: Vec<&str> = bodies.iter().map(|b| b["content"].as_str().unwrap()).collect();
+    assert_eq!(sent, ["a", "a", "b", "b"]);
+
+    // 4xx não adianta repetir
+    let (url, stub_handle) = stub(vec![(404, serde_json::json!({ "message": "Unknown Webhook" }))]);
+    let report = Webhook::new(&url).unwrap().post_all(&msgs);
+    assert_eq!(report.posted, 0);
+    let err = report.error.unwrap();
+    assert!(err.starts_with("message 1/2 not posted: HTTP 404"), "{err}");
+    assert_eq!(stub_handle.join().unwrap().len(), 1);
+
+    // parou no meio: o relatório diz quantas chegaram
+    let (url, stub_handle) = stub(vec![(204, serde_json::Value::Null), (403, serde_json::Value::Null)]);
+    let report = Webhook::new(&url).unwrap().post_all(&msgs);
+    assert_eq!((report.posted, report.total), (1, 2));
+    assert!(report.error.unwrap().starts_with("message 2/2 not posted: HTTP 403"));
+    assert_eq!(stub_handle.join().unwrap().len(), 2);
+
+    // conexão recusada: nada saiu, pode repetir
+    let port = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap().local_addr().unwrap().port();
+    let mut hook = Webhook::new(&format!("http://127.0.0.1:{port}/api/webhooks/1/s3cr3t")).unwrap();
+    hook.retry_delay = Duration::from_millis(1);
+    let err = hook.post_all(&msgs).error.unwrap();
+    assert!(err.starts_with("message 1/2 not posted"), "{err}");
+    assert!(!err.contains("s3cr3t"), "{err}");
+
+    assert!(validate_url("ftp://example.com/hook").is_err());
+    assert!(validate_url("not a url").is_err());
+  }
+
+  #[test]
+  fn does_not_repeat_a_request_that_may_have_been_posted() {
+    // o stub lê a requisição e fecha sem responder
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let url = format!("http://127.0.0.1:{}/hook", listener.local_addr().unwrap().port());
+    let handle = thread::spawn(move || {
+      listener.set_nonblocking(true).unwrap();
+      let mut seen = 0;
+      let until = std::time::Instant::now() + Duration::from_millis(500);
+      while std::time::Instant::now() < until {
+        match listener.accept() {
+          Ok((mut stream, _)) => {
+            stream.set_nonblocking(false).unwrap();
+            read_request(&mut stream).unwrap();
+            seen += 1;
+          }
+          Err(_) => thread::sleep(Duration::from_millis(5)),
+        }
+      }
+      seen
+    });
+    let mut hook = Webhook::new(&url).unwrap();
+    hook.retry_delay = Duration::from_millis(1);
+    let report = hook.post_all(&[serde_json::json!({ "content": "a" })]);
+    assert_eq!(report.posted, 0);
+    assert!(report.error.unwrap().contains("not retried"));
+    assert_eq!(handle.join().unwrap(), 1);
+  }
+}
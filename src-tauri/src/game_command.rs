@@ -0,0 +1,189 @@
+// Texto dos comandos digitados no chat do jogo (`!np @1`, `/p 17 @1`, prefixo livre).
+//
+// `np`/`perm`/`custom` só montam a string. Os `send_*` validam o mapcode, conferem a janela
+// em foco (`check`; no app é o `target_window::ensure_allowed`) e digitam pelo `Injector`.
+
+use std::fmt;
+
+use crate::{
+  inject::Injector,
+  mapcode::{self, MapCode},
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum CommandMode {
+  #[default]
+  #[serde(rename = "!np")]
+  BangNp,
+  #[serde(rename = "/np")]
+  SlashNp,
+  #[serde(rename = "/npp")]
+  SlashNpp,
+}
+
+impl fmt::Display for CommandMode {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      CommandMode::BangNp => write!(f, "!np"),
+      CommandMode::SlashNp => write!(f, "/np"),
+      CommandMode::SlashNpp => write!(f, "/npp"),
+    }
+  }
+}
+
+pub fn np(mode: CommandMode, mapcode: MapCode) -> String {
+  format!("{mode} {mapcode}")
+}
+
+pub fn perm(category_number: i32, mapcode: MapCode) -> Result<String, String> {
+  if !(0..=999).contains(&category_number) {
+    return Err("invalid categoryNumber".into());
+  }
+  Ok(format!("/p {category_number} {mapcode}"))
+}
+
+pub fn custom(prefix: &str, mapcode: MapCode, suffix: Option<&str>) -> Result<String, String> {
+  let prefix = prefix.trim();
+  if prefix.is_empty() {
+    return Err("empty prefix".into());
+  }
+  Ok(match suffix.map(str::trim).filter(|s| !s.is_empty()) {
+    Some(suffix) => format!("{prefix} {mapcode} {suffix}"),
+    None => format!("{prefix} {mapcode}"),
+  })
+}
+
+/// Digita `cmd` + Enter se `check` deixar (não digita no Discord/no próprio app por engano).
+pub fn send(injector: &Injector, check: impl Fn() -> Result<(), String>, cmd: &str) -> Result<(), String> {
+  check()?;
+  injector.type_and_enter(cmd)
+}
+
+fn parse(raw_mapcode: &str) -> Result<MapCode, String> {
+  mapcode::parse_map_code(raw_mapcode).map_err(|e| e.to_string())
+}
+
+pub fn send_np(
+  injector: &Injector,
+  check: impl Fn() -> Result<(), String>,
+  mode: CommandMode,
+  raw_mapcode: &str,
+) -> Result<String, String> {
+  let cmd = np(mode, parse(raw_mapcode)?);
+  send(injector, check, &cmd)?;
+  Ok(cmd)
+}
+
+pub fn send_perm(
+  injector: &Injector,
+  check: impl Fn() -> Result<(), String>,
+  category_number: i32,
+  raw_mapcode: &str,
+) -> Result<String, String> {
+  let cmd = perm(category_number, parse(raw_mapcode)?)?;
+  send(injector, check, &cmd)?;
+  Ok(cmd)
+}
+
+pub fn send_custom(
+  injector: &Injector,
+  check: impl Fn() -> Result<(), String>,
+  prefix: &str,
+  raw_mapcode: &str,
+  suffix: Option<&str>,
+) -> Result<String, String> {
+  let cmd = custom(prefix, parse(raw_mapcode)?, suffix)?;
+  send(injector, check, &cmd)?;
+  Ok(cmd)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    inject::{Key, KeyEvent, Recording},
+    mapcode::parse_map_code,
+  };
+
+  fn code(raw: &str) -> MapCode {
+    parse_map_code(raw).unwrap()
+  }
+
+  // o que chegou no chat: o texto colado e quantos Enter foram apertados
+  fn sent(rec: &Recording) -> (Vec<String>, usize) {
+    let enters = rec
+      .events()
+      .iter()
+      .filter(|e| **e == KeyEvent::Down(Key::Enter))
+      .count();
+    (rec.pasted(), enters)
+  }
+
+  #[test]
+  fn np_modes() {
+    let rec = Recording::with_clipboard("old");
+    let injector = rec.injector();
+    for mode in [CommandMode::BangNp, CommandMode::SlashNp, CommandMode::SlashNpp] {
+      injector.type_and_enter(&np(mode, code("@7654321"))).unwrap();
+    }
+    assert_eq!(
+      sent(&rec),
+      (vec!["!np @7654321".into(), "/np @7654321".into(), "/npp @7654321".into()], 6)
+    );
+    assert_eq!(rec.clipboard().as_deref(), Some("old"));
+    assert_eq!(serde_json::to_string(&CommandMode::SlashNpp).unwrap(), "\"/npp\"");
+  }
+
+  #[test]
+  fn perm_validates_category() {
+    let rec = Recording::default();
+    let injector = rec.injector();
+    injector.type_and_enter(&perm(17, code("123")).unwrap()).unwrap();
+    injector.type_and_enter(&perm(0, code("@9")).unwrap()).unwrap();
+    assert!(perm(1000, code("@9")).is_err());
+    assert!(perm(-1, code("@9")).is_err());
+    assert_eq!(sent(&rec), (vec!["/p 17 @123".into(), "/p 0 @9".into()], 4));
+  }
+
+  #[test]
+  fn custom_prefix_and_suffix() {
+    let rec = Recording::default();
+    let injector = rec.injector();
+    injector.type_and_enter(&custom(" /lsmap ", code("@5"), None).unwrap()).unwrap();
+    injector.type_and_enter(&custom("/info", code("@5"), Some("  ")).unwrap()).unwrap();
+    injector
+      .type_and_enter(&custom("!review", code("@5"), Some(" P17 ")).unwrap())
+      .unwrap();
+    assert!(custom("   ", code("@5"), None).is_err());
+    assert_eq!(
+      sent(&rec),
+      (vec!["/lsmap @5".into(), "/info @5".into(), "!review @5 P17".into()], 6)
+    );
+  }
+
+  #[test]
+  fn send_checks_the_target_before_typing() {
+    let rec = Recording::with_clipboard("old");
+    let injector = rec.injector();
+    let allow = || Ok(());
+    let deny = || Err("wrong_target_window: the focused window is \"Discord\"".to_string());
+
+    assert_eq!(send_np(&injector, allow, CommandMode::SlashNp, "7654321").unwrap(), "/np @7654321");
+    assert_eq!(send_perm(&injector, allow, 17, "@9").unwrap(), "/p 17 @9");
+    assert_eq!(send_custom(&injector, allow, "/info", "@9", Some("x")).unwrap(), "/info @9 x");
+
+    let err = send_np(&injector, deny, CommandMode::BangNp, "@9").unwrap_err();
+    assert!(err.starts_with("wrong_target_window"), "{err}");
+    assert!(send_perm(&injector, deny, 17, "@9").is_err());
+    assert!(send_custom(&injector, deny, "/info", "@9", None).is_err());
+    // mapcode/categoria inválidos falham antes de olhar a janela
+    assert!(send_np(&injector, allow, CommandMode::BangNp, "abc").is_err());
+    assert!(send_perm(&injector, allow, 1000, "@9").is_err());
+
+    assert_eq!(
+      sent(&rec),
+      (vec!["/np @7654321".into(), "/p 17 @9".into(), "/info @9 x".into()], 6)
+    );
+    assert_eq!(rec.clipboard().as_deref(), Some("old"));
+  }
+}
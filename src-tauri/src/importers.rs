@@ -0,0 +1,672 @@
+// Importadores de submissões: export do DiscordChatExporter (JSON/HTML) e logs do chat do jogo.
+//
+// Cada mensagem vira uma linha por mapcode encontrado, com quem mandou, a categoria que a
+// pessoa pediu (ex.: "p17") e o horário da mensagem quando o formato tem isso.
+//
+// Os dois exports do Discord dão o mesmo resultado: submitter é o nome da conta (não o apelido
+// do servidor) e o horário vira RFC 3339 em UTC, para a fila ordenar igual.
+
+use serde::Deserialize;
+
+use crate::mapcode;
+
+const MESSAGE_SNIPPET_MAX_CHARS: usize = 200;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportFormat {
+  DiscordJson,
+  DiscordHtml,
+  ChatLog,
+}
+
+impl std::str::FromStr for ImportFormat {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.trim().to_ascii_lowercase().as_str() {
+      "discordjson" | "discord_json" | "json" => Ok(ImportFormat::DiscordJson),
+      "discordhtml" | "discord_html" | "html" => Ok(ImportFormat::DiscordHtml),
+      "chatlog" | "chat_log" | "chat" | "txt" => Ok(ImportFormat::ChatLog),
+      other => Err(format!("unknown import format: {other}")),
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedRow {
+  /// Sem o `@` (mesmo formato da fila).
+  pub mapcode: String,
+  pub submitter: Option<String>,
+  /// Categoria pedida na mensagem, normalizada ("P17").
+  pub category: Option<String>,
+  /// Exports do Discord: RFC 3339 em UTC. Log do chat: só o relógio (`12:34`), como veio.
+  pub timestamp: Option<String>,
+  pub message: String,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportResult {
+  pub format: ImportFormat,
+  pub rows: Vec<ImportedRow>,
+  /// Mensagens lidas (com ou sem mapcode).
+  pub messages: usize,
+}
+
+struct Message {
+  author: Option<String>,
+  timestamp: Option<String>,
+  content: String,
+}
+
+pub fn detect_format(text: &str) -> ImportFormat {
+  let t = text.trim_start();
+  if t.starts_with('{') && t.contains("\"messages\"") {
+    ImportFormat::DiscordJson
+  } else if t.contains("chatlog__") {
+    ImportFormat::DiscordHtml
+  } else {
+    ImportFormat::ChatLog
+  }
+}
+
+pub fn import_text(text: &str, format: Option<ImportFormat>) -> Result<ImportResult, String> {
+  let format = format.unwrap_or_else(|| detect_format(text));
+  let messages = match format {
+    ImportFormat::DiscordJson => parse_discord_json(text)?,
+    ImportFormat::DiscordHtml => parse_discord_html(text),
+    ImportFormat::ChatLog => parse_chat_log(text),
+  };
+
+  let count = messages.len();
+  let rows = messages.into_iter().flat_map(rows_from_message).collect();
+  Ok(ImportResult {
+    format,
+    rows,
+    messages: count,
+  })
+}
+
+fn rows_from_message(msg: Message) -> Vec<ImportedRow> {
+  // só códigos reais do jogo: "p17"/"pls" também passam no parser tolerante
+  let codes: Vec<String> = mapcode::uniq_preserve_order(mapcode::parse_mapcodes_from_text(&msg.content))
+    .into_iter()
+    .filter(|mc| mapcode::parse_map_code(mc).is_ok())
+    .collect();
+  if codes.is_empty() {
+    return vec![];
+  }
+
+  let category = claimed_category(&msg.content);
+  let message: String = msg.content.trim().chars().take(MESSAGE_SNIPPET_MAX_CHARS).collect();
+  codes
+    .into_iter()
+    .map(|mapcode| ImportedRow {
+      mapcode,
+      submitter: msg.author.clone(),
+      category: category.clone(),
+      timestamp: msg.timestamp.clone(),
+      message: message.clone(),
+    })
+    .collect()
+}
+
+/// Primeiro token do tipo "p17" / "P4" / "p 17" na mensagem.
+pub fn claimed_category(content: &str) -> Option<String> {
+  let tokens: Vec<&str> = content
+    .split(|c: char| c.is_whitespace() || matches!(c, ',' | ';' | '(' | ')' | '[' | ']' | '!' | '?' | '.'))
+    .filter(|t| !t.is_empty())
+    .collect();
+
+  for (i, tok) in tokens.iter().enumerate() {
+    let Some(rest) = tok.strip_prefix(['p', 'P']) else { continue };
+    let digits = if rest.is_empty() {
+      // "p 17"
+      tokens.get(i + 1).copied().unwrap_or("")
+    } else {
+      rest
+    };
+    if (1..=2).contains(&digits.len()) && digits.bytes().all(|b| b.is_ascii_digit()) {
+      let n: u32 = digits.parse().ok()?;
+      return Some(format!("P{n}"));
+    }
+  }
+  None
+}
+
+// -------------------------
+// DiscordChatExporter (JSON)
+// -------------------------
+#[derive(Deserialize)]
+struct DiscordExport {
+  messages: Vec<DiscordMessage>,
+}
+
+#[derive(Deserialize)]
+struct DiscordMessage {
+  #[serde(default)]
+  timestamp: Option<String>,
+  #[serde(default)]
+  content: String,
+  #[serde(default)]
+  author: Option<DiscordAuthor>,
+  #[serde(default)]
+  embeds: Vec<DiscordEmbed>,
+}
+
+#[derive(Deserialize)]
+struct DiscordAuthor {
+  #[serde(default)]
+  name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct DiscordEmbed {
+  #[serde(default)]
+  title: Option<String>,
+  #[serde(default)]
+  description: Option<String>,
+}
+
+fn parse_discord_json(text: &str) -> Result<Vec<Message>, String> {
+  let export: DiscordExport = serde_json::from_str(text).map_err(|e| format!("invalid Discord export: {e}"))?;
+  Ok(
+    export
+      .messages
+      .into_iter()
+      .map(|m| {
+        // bots de submissão costumam mandar o mapcode dentro de um embed
+        let mut content = m.content;
+        for e in m.embeds {
+          for part in [e.title, e.description].into_iter().flatten() {
+            content.push('\n');
+            content.push_str(&part);
+          }
+        }
+        Message {
+          // `name` é a conta; `nickname` muda por servidor e o HTML não traz
+          author: m
+            .author
+            .and_then(|a| a.name)
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty()),
+          timestamp: m.timestamp.as_deref().and_then(iso_to_utc),
+          content,
+        }
+      })
+      .collect(),
+  )
+}
+
+// -------------------------
+// DiscordChatExporter (HTML)
+// -------------------------
+// Sem parser HTML completo: o export tem estrutura fixa, então basta achar os blocos por classe.
+// Mensagens agrupadas (mesmo autor em sequência) só trazem o autor no primeiro bloco.
+// O `title` do autor é o nome da conta (o texto é o apelido); o do horário é uma data por
+// extenso sem fuso, lida como UTC.
+fn parse_discord_html(text: &str) -> Vec<Message> {
+  const CONTAINER: &str = "chatlog__message-container";
+
+  let mut out = Vec::new();
+  let mut author: Option<String> = None;
+  let mut timestamp: Option<String> = None;
+
+  let mut blocks = text.split(CONTAINER);
+  blocks.next();
+  for block in blocks {
+    if let Some(a) = element_title_or_text(block, "chatlog__author") {
+      author = Some(a);
+    }
+    if let Some(ts) = element_title_or_text(block, "chatlog__timestamp")
+      .or_else(|| element_title_or_text(block, "chatlog__short-timestamp"))
+    {
+      timestamp = long_date_to_utc(&ts);
+    }
+    let mut content = element_inner_text(block, "chatlog__content").unwrap_or_default();
+    if let Some(embed) = element_inner_text(block, "chatlog__embed") {
+      content.push('\n');
+      content.push_str(&embed);
+    }
+    if content.trim().is_empty() {
+      continue;
+    }
+    out.push(Message {
+      author: author.clone(),
+      timestamp: timestamp.clone(),
+      content,
+    });
+  }
+  out
+}
+
+/// Abre a tag que tem `class` e devolve (atributos da tag, resto do documento após `>`).
+fn find_element<'a>(html: &'a str, class: &str) -> Option<(&'a str, &'a str)> {
+  let mut search_from = 0;
+  loop {
+    let rel = html[search_from..].find(class)?;
+    let at = search_from + rel;
+    search_from = at + class.len();
+    // a classe precisa terminar aqui (evita chatlog__author-name casar com chatlog__author)
+    let next = html[search_from..].chars().next();
+    if !matches!(next, Some('"') | Some(' ') | Some('\'')) {
+      continue;
+    }
+    let tag_start = html[..at].rfind('<')?;
+    let tag_end = at + html[at..].find('>')?;
+    return Some((&html[tag_start..tag_end], &html[tag_end + 1..]));
+  }
+}
+
+fn attribute(tag: &str, name: &str) -> Option<String> {
+  let needle = format!("{name}=\"");
+  let start = tag.find(&needle)? + needle.len();
+  let end = start + tag[start..].find('"')?;
+  Some(decode_entities(&tag[start..end]))
+}
+
+fn element_title_or_text(html: &str, class: &str) -> Option<String> {
+  let (tag, rest) = find_element(html, class)?;
+  attribute(tag, "title")
+    .or_else(|| Some(strip_tags(&rest[..rest.find("</").unwrap_or(rest.len())])))
+    .map(|s| s.trim().to_string())
+    .filter(|s| !s.is_empty())
+}
+
+fn element_inner_text(html: &str, class: &str) -> Option<String> {
+  let (tag, rest) = find_element(html, class)?;
+  let tag_name: String = tag[1..].chars().take_while(|c| c.is_ascii_alphanumeric()).collect();
+  let inner = &rest[..matching_close(rest, &tag_name).unwrap_or(rest.len())];
+  Some(strip_tags(inner).trim().to_string())
+}
+
+/// Posição do `</tag>` que fecha o elemento (conta aninhamento de tags com o mesmo nome).
+fn matching_close(rest: &str, tag_name: &str) -> Option<usize> {
+  let open = format!("<{tag_name}");
+  let close = format!("</{tag_name}>");
+  let mut depth = 1usize;
+  let mut i = 0;
+  while i < rest.len() {
+    let next_open = rest[i..].find(&open).map(|p| p + i);
+    let next_close = rest[i..].find(&close).map(|p| p + i)?;
+    match next_open {
+      Some(o) if o < next_close => {
+        depth += 1;
+        i = o + open.len();
+      }
+      _ => {
+        depth -= 1;
+        if depth == 0 {
+          return Some(next_close);
+        }
+        i = next_close + close.len();
+      }
+    }
+  }
+  None
+}
+
+fn strip_tags(html: &str) -> String {
+  let mut out = String::with_capacity(html.len());
+  let mut rest = html;
+  while let Some(lt) = rest.find('<') {
+    out.push_str(&rest[..lt]);
+    let Some(gt) = rest[lt..].find('>') else {
+      rest = &rest[lt..];
+      break;
+    };
+    let name: String = rest[lt + 1..lt + gt]
+      .trim_start_matches('/')
+      .chars()
+      .take_while(|c| c.is_ascii_alphanumeric())
+      .collect::<String>()
+      .to_ascii_lowercase();
+    if matches!(name.as_str(), "br" | "div" | "p") {
+      out.push('\n');
+    }
+    rest = &rest[lt + gt + 1..];
+  }
+  out.push_str(rest);
+  decode_entities(&out)
+}
+
+fn decode_entities(s: &str) -> String {
+  if !s.contains('&') {
+    return s.to_string();
+  }
+  let mut out = String::with_capacity(s.len());
+  let mut rest = s;
+  while let Some(amp) = rest.find('&') {
+    out.push_str(&rest[..amp]);
+    rest = &rest[amp..];
+    let Some(semi) = rest.find(';').filter(|p| *p <= 10) else {
+      out.push('&');
+      rest = &rest[1..];
+      continue;
+    };
+    let entity = &rest[1..semi];
+    let decoded = match entity {
+      "amp" => Some('&'),
+      "lt" => Some('<'),
+      "gt" => Some('>'),
+      "quot" => Some('"'),
+      "apos" => Some('\''),
+      "nbsp" => Some(' '),
+      _ => entity
+        .strip_prefix("#x")
+        .or_else(|| entity.strip_prefix("#X"))
+        .and_then(|h| u32::from_str_radix(h, 16).ok())
+        .or_else(|| entity.strip_prefix('#').and_then(|d| d.parse().ok()))
+        .and_then(char::from_u32),
+    };
+    match decoded {
+      Some(ch) => {
+        out.push(ch);
+        rest = &rest[semi + 1..];
+      }
+      None => {
+        out.push('&');
+        rest = &rest[1..];
+      }
+    }
+  }
+  out.push_str(rest);
+  out
+}
+
+// -------------------------
+// Horários
+// -------------------------
+const MONTHS: [&str; 12] = [
+  "january", "february", "march", "april", "may", "june", "july", "august", "september", "october", "november",
+  "december",
+];
+
+// dias desde 1970-01-01 (algoritmo "days from civil" do Howard Hinnant)
+fn days_from_civil(y: i64, m: u32, d: u32) -> i64 {
+  let y = if m <= 2 { y - 1 } else { y };
+  let era = y.div_euclid(400);
+  let yoe = y - era * 400;
+  let mp = (i64::from(m) + 9) % 12;
+  let doy = (153 * mp + 2) / 5 + i64::from(d) - 1;
+  let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+  era * 146_097 + doe - 719_468
+}
+
+fn civil_from_days(z: i64) -> (i64, u32, u32) {
+  let z = z + 719_468;
+  let era = z.div_euclid(146_097);
+  let doe = z - era * 146_097;
+  let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+  let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+  let mp = (5 * doy + 2) / 153;
+  let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+  let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+  (if m <= 2 { yoe + era * 400 + 1 } else { yoe + era * 400 }, m, d)
+}
+
+fn valid_date(y: i64, m: u32, d: u32) -> bool {
+  (1..=12).contains(&m) && d >= 1 && civil_from_days(days_from_civil(y, m, d)) == (y, m, d)
+}
+
+/// Data/hora (já em UTC) → `2026-01-02T10:00:00Z`.
+fn format_utc(secs: i64) -> String {
+  let (y, m, d) = civil_from_days(secs.div_euclid(86_400));
+  let t = secs.rem_euclid(86_400);
+  format!("{y:04}-{m:02}-{d:02}T{:02}:{:02}:{:02}Z", t / 3600, t / 60 % 60, t % 60)
+}
+
+fn utc_secs(y: i64, m: u32, d: u32, h: u32, min: u32, sec: u32) -> Option<i64> {
+  (valid_date(y, m, d) && h < 24 && min < 60 && sec < 60)
+    .then(|| days_from_civil(y, m, d) * 86_400 + i64::from(h * 3600 + min * 60 + sec))
+}
+
+/// `h:mm` ou `h:mm:ss`.
+fn parse_clock(s: &str) -> Option<(u32, u32, u32)> {
+  if !is_clock(s) {
+    return None;
+  }
+  let mut parts = s.split(':').map(|p| p.parse::<u32>().ok());
+  Some((parts.next()??, parts.next()??, parts.next().flatten().unwrap_or(0)))
+}
+
+/// ISO 8601 do export JSON (`2026-01-02T10:00:00.123-03:00`) → RFC 3339 em UTC.
+fn iso_to_utc(raw: &str) -> Option<String> {
+  let raw = raw.trim();
+  let (date, time) = raw.split_once(['T', 't', ' '])?;
+  let mut ymd = date.splitn(3, '-');
+  let (y, m, d) = (ymd.next()?.parse().ok()?, ymd.next()?.parse().ok()?, ymd.next()?.parse().ok()?);
+
+  let (clock, offset_secs) = if let Some(clock) = time.strip_suffix(['Z', 'z']) {
+    (clock, 0)
+  } else {
+    let at = time.rfind(['+', '-'])?;
+    let (sign, off) = (if &time[at..at + 1] == "-" { -1 } else { 1 }, &time[at + 1..]);
+    let (oh, om) = off.split_once(':').unwrap_or((off, "0"));
+    (&time[..at], sign * (oh.parse::<i64>().ok()? * 3600 + om.parse::<i64>().ok()? * 60))
+  };
+  // fração de segundo fora
+  let (h, min, sec) = parse_clock(clock.split('.').next()?)?;
+  Some(format_utc(utc_secs(y, m, d, h, min, sec)? - offset_secs))
+}
+
+/// `title` do horário no export HTML: "Friday, 2 January 2026 10:00" ou
+/// "Friday, January 2, 2026 10:00 AM". Sem fuso no texto: lido como UTC.
+fn long_date_to_utc(raw: &str) -> Option<String> {
+  let (mut day, mut month, mut year, mut clock, mut pm) = (None, None, None, None, None);
+  for tok in raw.split([' ', ',']).filter(|t| !t.is_empty()) {
+    let lower = tok.to_ascii_lowercase();
+    if let Some(i) = MONTHS.iter().position(|m| *m == lower) {
+      month = Some(i as u32 + 1);
+    } else if lower == "am" || lower == "pm" {
+      pm = Some(lower == "pm");
+    } else if let Some(c) = parse_clock(tok) {
+      clock = Some(c);
+    } else if let Ok(n) = tok.parse::<u32>() {
+      if tok.len() == 4 {
+        year = Some(i64::from(n));
+      } else {
+        day = Some(n);
+      }
+    }
+  }
+  let (h, min, sec) = clock?;
+  let h = match pm {
+    Some(pm) if (1..=12).contains(&h) => h % 12 + if pm { 12 } else { 0 },
+    Some(_) => return None,
+    None => h,
+  };
+  Some(format_utc(utc_secs(year?, month?, day?, h, min, sec)?))
+}
+
+// -------------------------
+// Chat do jogo
+// -------------------------
+// Linhas como `[Author] @1234567 p17 pls`, opcionalmente com horário (`[12:34] [Author] ...`)
+// e marcador de canal (`• [Author] ...`). Linhas sem `[Author]` contam sem submitter.
+fn parse_chat_log(text: &str) -> Vec<Message> {
+  let normalized = text.replace("\r\n", "\n").replace('\r', "\n");
+  normalized
+    .split('\n')
+    .filter_map(|line| {
+      let mut rest = line.trim().trim_start_matches(['•', '*', '-', '>']).trim_start();
+      if rest.is_empty() {
+        return None;
+      }
+
+      let mut timestamp = None;
+      let mut author = None;
+      while let Some(inner_end) = rest.strip_prefix('[').and_then(|r| r.find(']')) {
+        let inner = rest[1..inner_end + 1].trim();
+        if is_clock(inner) && timestamp.is_none() && author.is_none() {
+          timestamp = Some(inner.to_string());
+        } else if author.is_none() && !inner.is_empty() && !inner.contains(' ') {
+          author = Some(inner.to_string());
+        } else {
+          break;
+        }
+        rest = rest[inner_end + 2..].trim_start();
+      }
+      // `Author: mensagem` (whisper/tribo em alguns logs)
+      if author.is_none() {
+        if let Some((name, msg)) = rest.split_once(": ") {
+          if !name.is_empty() && !name.contains(' ') && !name.contains('@') {
+            author = Some(name.to_string());
+            rest = msg;
+          }
+        }
+      }
+
+      Some(Message {
+        author,
+        timestamp,
+        content: rest.to_string(),
+      })
+    })
+    .collect()
+}
+
+fn is_clock(s: &str) -> bool {
+  let parts: Vec<&str> = s.split(':').collect();
+  (2..=3).contains(&parts.len())
+    && parts
+      .iter()
+      .all(|p| (1..=2).contains(&p.len()) && p.bytes().all(|b| b.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  type Row = (String, Option<String>, Option<String>, Option<String>);
+
+  fn row(mapcode: &str, submitter: Option<&str>, category: Option<&str>, timestamp: Option<&str>) -> Row {
+    (
+      mapcode.to_string(),
+      submitter.map(str::to_string),
+      category.map(str::to_string),
+      timestamp.map(str::to_string),
+    )
+  }
+
+  fn rows(text: &str) -> (ImportFormat, Vec<Row>) {
+    let res = import_text(text, None).unwrap();
+    let rows = res
+      .rows
+      .into_iter()
+      .map(|r| (r.mapcode, r.submitter, r.category, r.timestamp))
+      .collect();
+    (res.format, rows)
+  }
+
+  #[test]
+  fn chat_log_lines() {
+    let text = "[Author] @1234567 p17 pls\n\
+                [12:34] [Other#0001] @7654321 @7654322 P4\n\
+                • [Third] hello there\n\
+                Fourth: @111111 (p 9)\n\
+                @222222";
+    let (format, got) = rows(text);
+    assert_eq!(format, ImportFormat::ChatLog);
+    assert_eq!(
+      got,
+      vec![
+        row("1234567", Some("Author"), Some("P17"), None),
+        row("7654321", Some("Other#0001"), Some("P4"), Some("12:34")),
+        row("7654322", Some("Other#0001"), Some("P4"), Some("12:34")),
+        row("111111", Some("Fourth"), Some("P9"), None),
+        row("222222", None, None, None),
+      ]
+    );
+  }
+
+  #[test]
+  fn discord_json_export() {
+    let text = r#"{
+      "guild": { "name": "Tribe" },
+      "channel": { "name": "submissions" },
+      "messages": [
+        { "timestamp": "2026-01-02T10:00:00+00:00", "content": "@1234567 p17",
+          "author": { "name": "someone", "nickname": "Some One" } },
+        { "timestamp": "2026-01-02T10:01:00+00:00", "content": "no code here",
+          "author": { "name": "chatty" } },
+        { "timestamp": "2026-01-02T07:02:00.517-03:00", "content": "",
+          "author": { "name": "SubmitBot", "nickname": "" },
+          "embeds": [ { "title": "New map (P18)", "description": "Map: @7654321" } ] }
+      ]
+    }"#;
+    let res = import_text(text, None).unwrap();
+    assert_eq!(res.format, ImportFormat::DiscordJson);
+    assert_eq!(res.messages, 3);
+    let got: Vec<_> = res
+      .rows
+      .into_iter()
+      .map(|r| (r.mapcode, r.submitter, r.category, r.timestamp))
+      .collect();
+    assert_eq!(
+      got,
+      vec![
+        row("1234567", Some("someone"), Some("P17"), Some("2026-01-02T10:00:00Z")),
+        row("7654321", Some("SubmitBot"), Some("P18"), Some("2026-01-02T10:02:00Z")),
+      ]
+    );
+  }
+
+  #[test]
+  fn discord_html_export() {
+    let text = r##"<div class="chatlog">
+      <div class="chatlog__message-group">
+        <div class="chatlog__message-container" data-message-id="1">
+          <div class="chatlog__message">
+            <div class="chatlog__header">
+              <span class="chatlog__author" title="someone" data-user-id="10">Some One</span>
+              <span class="chatlog__timestamp" title="Friday, 2 January 2026 10:00"><a href="#">02/01/2026 10:00</a></span>
+            </div>
+            <div class="chatlog__content chatlog__markdown"><span class="chatlog__markdown-preserve">@1234567 p17 &amp; thanks</span></div>
+          </div>
+        </div>
+        <div class="chatlog__message-container" data-message-id="2">
+          <div class="chatlog__message">
+            <div class="chatlog__short-timestamp" title="Friday, January 2, 2026 10:01 AM">10:01</div>
+            <div class="chatlog__content chatlog__markdown"><span class="chatlog__markdown-preserve">also @7654321<br>p4</span></div>
+          </div>
+        </div>
+      </div>
+    </div>"##;
+    let (format, got) = rows(text);
+    assert_eq!(format, ImportFormat::DiscordHtml);
+    assert_eq!(
+      got,
+      vec![
+        row("1234567", Some("someone"), Some("P17"), Some("2026-01-02T10:00:00Z")),
+        row("7654321", Some("someone"), Some("P4"), Some("2026-01-02T10:01:00Z")),
+      ]
+    );
+  }
+
+  #[test]
+  fn timestamps() {
+    assert_eq!(iso_to_utc("2026-01-02T10:00:00Z").as_deref(), Some("2026-01-02T10:00:00Z"));
+    // vira o dia (e o ano) ao passar para UTC
+    assert_eq!(iso_to_utc("2025-12-31T22:30:15.5-03:00").as_deref(), Some("2026-01-01T01:30:15Z"));
+    assert_eq!(iso_to_utc("2024-03-01T00:10:00+01:00").as_deref(), Some("2024-02-29T23:10:00Z"));
+    assert_eq!(iso_to_utc("2026-02-30T10:00:00Z"), None);
+    assert_eq!(iso_to_utc("yesterday"), None);
+
+    assert_eq!(long_date_to_utc("Friday, 2 January 2026 10:00").as_deref(), Some("2026-01-02T10:00:00Z"));
+    assert_eq!(long_date_to_utc("Friday, January 2, 2026 12:05 AM").as_deref(), Some("2026-01-02T00:05:00Z"));
+    assert_eq!(long_date_to_utc("Friday, January 2, 2026 1:05:09 PM").as_deref(), Some("2026-01-02T13:05:09Z"));
+    assert_eq!(long_date_to_utc("10:01"), None);
+  }
+
+  #[test]
+  fn entities_and_categories() {
+    assert_eq!(decode_entities("a &lt;b&gt; &#64;1 &#x41; &bogus; &"), "a <b> @1 A &bogus; &");
+    assert_eq!(claimed_category("@123 p17 pls"), Some("P17".into()));
+    assert_eq!(claimed_category("(P4)"), Some("P4".into()));
+    assert_eq!(claimed_category("p 9"), Some("P9".into()));
+    assert_eq!(claimed_category("please p123 pls"), None);
+  }
+}
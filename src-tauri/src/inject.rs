@@ -0,0 +1,482 @@
+// Digita um comando na janela em foco: Enter (abre o chat) → colar → Enter (envia).
+//
+// O fluxo é um só (`Injector::type_and_enter`); quem aperta as teclas e mexe no clipboard
+// são os traits, guardados no estado do Tauri:
+// - X11, Windows e macOS: enigo + arboard.
+// - Sessão Wayland (GNOME/KDE): o compositor não deixa eventos sintéticos do X11 chegarem no
+//   jogo, então as teclas vão pelo `ydotool` (uinput, precisa do `ydotoold` rodando) e o
+//   clipboard pelo `wl-copy`/`wl-paste` (wl-clipboard). Sem essas ferramentas, ou com o
+//   `ydotoold` parado, cai no X11 (via XWayland).
+// Nos testes entra o `Recording`, que só anota o que seria feito.
+
+use std::{
+  env,
+  io::Write as _,
+  path::{Path, PathBuf},
+  process::{Command, Stdio},
+  sync::OnceLock,
+  thread,
+  time::Duration,
+};
+
+use crate::clipboard_watch;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Key {
+  Enter,
+  /// Ctrl (Cmd no macOS).
+  PasteModifier,
+  V,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyEvent {
+  Down(Key),
+  Up(Key),
+  /// Pausa entre teclas (o jogo perde eventos muito colados).
+  Wait(Duration),
+}
+
+pub trait KeyboardInjector: Send + Sync {
+  fn send(&self, events: &[KeyEvent]) -> Result<(), String>;
+}
+
+pub trait ClipboardAccess: Send + Sync {
+  /// `None`: clipboard vazio ou sem texto.
+  fn get_text(&self) -> Result<Option<String>, String>;
+  fn set_text(&self, text: &str) -> Result<(), String>;
+}
+
+fn click(key: Key) -> [KeyEvent; 2] {
+  [KeyEvent::Down(key), KeyEvent::Up(key)]
+}
+
+// Enter abre o chat, Ctrl+V cola, Enter envia.
+fn paste_and_enter_events() -> Vec<KeyEvent> {
+  let mut events = Vec::new();
+  events.extend(click(Key::Enter));
+  events.push(KeyEvent::Down(Key::PasteModifier));
+  events.push(KeyEvent::Wait(Duration::from_millis(20)));
+  events.extend(click(Key::V));
+  events.push(KeyEvent::Wait(Duration::from_millis(20)));
+  events.push(KeyEvent::Up(Key::PasteModifier));
+  events.push(KeyEvent::Wait(Duration::from_millis(250)));
+  events.extend(click(Key::Enter));
+  events
+}
+
+pub struct Injector {
+  keyboard: Box<dyn KeyboardInjector>,
+  clipboard: Box<dyn ClipboardAccess>,
+}
+
+impl Injector {
+  pub fn new(keyboard: impl KeyboardInjector + 'static, clipboard: impl ClipboardAccess + 'static) -> Self {
+    Self {
+      keyboard: Box::new(keyboard),
+      clipboard: Box::new(clipboard),
+    }
+  }
+
+  /// Backend da sessão atual (ver `backend`).
+  pub fn system() -> Self {
+    match backend() {
+      Backend::Enigo => Self::new(EnigoKeyboard, ArboardClipboard),
+      Backend::Ydotool => Self::new(YdotoolKeyboard, WlClipboard),
+    }
+  }
+
+  pub fn clipboard(&self) -> &dyn ClipboardAccess {
+    self.clipboard.as_ref()
+  }
+
+  pub fn type_and_enter(&self, text: &str) -> Result<(), String> {
+    // o watcher não deve capturar o próprio comando (nem o restore do clipboard)
+    clipboard_watch::suppress_for(Duration::from_millis(1500));
+
+    // Clipboard + paste is faster than typing for long commands.
+    let prev_clip = self.clipboard.get_text().ok().flatten();
+    self.clipboard.set_text(text)?;
+
+    self.keyboard.send(&paste_and_enter_events())?;
+
+    // best-effort clipboard restore (only if it was text)
+    if let Some(prev) = prev_clip {
+      let _ = self.clipboard.set_text(&prev);
+    }
+    Ok(())
+  }
+}
+
+// -------------------------
+// Escolha do backend
+// -------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend {
+  Enigo,
+  Ydotool,
+}
+
+impl Backend {
+  pub fn as_str(self) -> &'static str {
+    match self {
+      Backend::Enigo => "enigo",
+      Backend::Ydotool => "ydotool",
+    }
+  }
+
+  fn select(wayland: bool, has_tools: bool, daemon_up: bool) -> Self {
+    if wayland && has_tools && daemon_up {
+      Backend::Ydotool
+    } else {
+      Backend::Enigo
+    }
+  }
+}
+
+pub fn wayland_session() -> bool {
+  let session = env::var("XDG_SESSION_TYPE").unwrap_or_default();
+  session.eq_ignore_ascii_case("wayland") || env::var_os("WAYLAND_DISPLAY").is_some()
+}
+
+fn in_path(bin: &str) -> bool {
+  env::var_os("PATH")
+    .map(|paths| env::split_paths(&paths).any(|dir| Path::new(&dir).join(bin).is_file()))
+    .unwrap_or(false)
+}
+
+// sockets onde o `ydotoold` escuta, na ordem que o próprio `ydotool` procura
+#[cfg(unix)]
+fn ydotool_sockets() -> Vec<PathBuf> {
+  let mut out = Vec::new();
+  if let Some(p) = env::var_os("YDOTOOL_SOCKET") {
+    out.push(PathBuf::from(p));
+  }
+  if let Some(dir) = env::var_os("XDG_RUNTIME_DIR") {
+    out.push(Path::new(&dir).join(".ydotool_socket"));
+  }
+  out.push(PathBuf::from("/tmp/.ydotool_socket"));
+  out
+}
+
+/// Alguém escuta no socket (o de um `ydotoold` que morreu sobra no disco e dá ECONNREFUSED).
+#[cfg(unix)]
+fn socket_answers(path: &Path) -> bool {
+  std::os::unix::net::UnixDatagram::unbound()
+    .and_then(|s| s.connect(path))
+    .is_ok()
+}
+
+#[cfg(unix)]
+fn ydotoold_running() -> bool {
+  ydotool_sockets().iter().any(|p| socket_answers(p))
+}
+
+#[cfg(not(unix))]
+fn ydotoold_running() -> bool {
+  false
+}
+
+/// Escolhido uma vez por execução (a sessão não muda com o app aberto).
+pub fn backend() -> Backend {
+  static BACKEND: OnceLock<Backend> = OnceLock::new();
+  *BACKEND.get_or_init(|| {
+    let wayland = wayland_session();
+    let has_tools = ["ydotool", "wl-copy", "wl-paste"].iter().all(|b| in_path(b));
+    let daemon_up = wayland && has_tools && ydotoold_running();
+    let backend = Backend::select(wayland, has_tools, daemon_up);
+    if wayland && !has_tools {
+      log::warn!("wayland session without ydotool/wl-clipboard; typing through XWayland");
+    } else if wayland && !daemon_up {
+      log::warn!("ydotoold is not running (no socket answered); typing through XWayland");
+    }
+    log::info!("input injection using {}", backend.as_str());
+    backend
+  })
+}
+
+fn run(cmd: &mut Command) -> Result<std::process::Output, String> {
+  let name = cmd.get_program().to_string_lossy().into_owned();
+  cmd
+    .stdin(Stdio::null())
+    .output()
+    .map_err(|e| format!("{name}: {e}"))
+}
+
+// -------------------------
+// enigo + arboard
+// -------------------------
+
+struct EnigoKeyboard;
+
+impl KeyboardInjector for EnigoKeyboard {
+  fn send(&self, events: &[KeyEvent]) -> Result<(), String> {
+    use enigo::{Direction, Enigo, Keyboard, Settings};
+
+    let mut enigo = Enigo::new(&Settings::default()).map_err(|e| e.to_string())?;
+    let key = |k: Key| match k {
+      Key::Enter => enigo::Key::Return,
+      Key::PasteModifier if cfg!(target_os = "macos") => enigo::Key::Meta,
+      Key::PasteModifier => enigo::Key::Control,
+      Key::V => enigo::Key::Unicode('v'),
+    };
+    for ev in events {
+      match *ev {
+        KeyEvent::Down(k) => enigo.key(key(k), Direction::Press).map_err(|e| e.to_string())?,
+        KeyEvent::Up(k) => enigo.key(key(k), Direction::Release).map_err(|e| e.to_string())?,
+        KeyEvent::Wait(d) => thread::sleep(d),
+      }
+    }
+    Ok(())
+  }
+}
+
+struct ArboardClipboard;
+
+impl ClipboardAccess for ArboardClipboard {
+  fn get_text(&self) -> Result<Option<String>, String> {
+    let mut clipboard = arboard::Clipboard::new().map_err(|e| e.to_string())?;
+    match clipboard.get_text() {
+      Ok(text) => Ok(Some(text)),
+      Err(arboard::Error::ContentNotAvailable) => Ok(None),
+      Err(e) => Err(e.to_string()),
+    }
+  }
+
+  fn set_text(&self, text: &str) -> Result<(), String> {
+    let mut clipboard = arboard::Clipboard::new().map_err(|e| e.to_string())?;
+    clipboard.set_text(text.to_string()).map_err(|e| e.to_string())
+  }
+}
+
+// -------------------------
+// ydotool + wl-clipboard
+// -------------------------
+
+// códigos do kernel (linux/input-event-codes.h), que é o que o ydotool espera
+fn evdev_code(key: Key) -> u16 {
+  match key {
+    Key::Enter => 28,
+    Key::PasteModifier => 29,
+    Key::V => 47,
+  }
+}
+
+fn ydotool_keys(args: &[String]) -> Result<(), String> {
+  let out = run(Command::new("ydotool").args(["key", "--key-delay", "20"]).args(args))?;
+  if !out.status.success() {
+    let err = String::from_utf8_lossy(&out.stderr);
+    return Err(format!("ydotool failed: {} (is ydotoold running?)", err.trim()));
+  }
+  Ok(())
+}
+
+struct YdotoolKeyboard;
+
+impl KeyboardInjector for YdotoolKeyboard {
+  fn send(&self, events: &[KeyEvent]) -> Result<(), String> {
+    // teclas seguidas vão numa chamada só; as pausas ficam entre chamadas
+    let mut batch: Vec<String> = Vec::new();
+    for ev in events {
+      match *ev {
+        KeyEvent::Down(k) => batch.push(format!("{}:1", evdev_code(k))),
+        KeyEvent::Up(k) => batch.push(format!("{}:0", evdev_code(k))),
+        KeyEvent::Wait(d) => {
+          if !batch.is_empty() {
+            ydotool_keys(&batch)?;
+            batch.clear();
+          }
+          thread::sleep(d);
+        }
+      }
+    }
+    if !batch.is_empty() {
+      ydotool_keys(&batch)?;
+    }
+    Ok(())
+  }
+}
+
+struct WlClipboard;
+
+impl ClipboardAccess for WlClipboard {
+  fn get_text(&self) -> Result<Option<String>, String> {
+    let out = run(Command::new("wl-paste").args(["--no-newline", "--type", "text"]))?;
+    // clipboard vazio ou sem texto: wl-paste sai com erro
+    if !out.status.success() {
+      return Ok(None);
+    }
+    Ok(Some(String::from_utf8_lossy(&out.stdout).into_owned()))
+  }
+
+  fn set_text(&self, text: &str) -> Result<(), String> {
+    // o wl-copy fica em segundo plano servindo a seleção; o processo chamado sai logo
+    let mut child = Command::new("wl-copy")
+      .args(["--type", "text/plain;charset=utf-8"])
+      .stdin(Stdio::piped())
+      .stdout(Stdio::null())
+      .stderr(Stdio::null())
+      .spawn()
+      .map_err(|e| format!("wl-copy: {e}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+      stdin.write_all(text.as_bytes()).map_err(|e| format!("wl-copy: {e}"))?;
+    }
+    let status = child.wait().map_err(|e| format!("wl-copy: {e}"))?;
+    if !status.success() {
+      return Err(format!("wl-copy failed ({status})"));
+    }
+    Ok(())
+  }
+}
+
+// -------------------------
+// Dublê para testes
+// -------------------------
+
+#[cfg(test)]
+pub use recording::Recording;
+
+#[cfg(test)]
+mod recording {
+  use std::sync::{Arc, Mutex};
+
+  use super::{ClipboardAccess, Injector, Key, KeyEvent, KeyboardInjector};
+
+  #[derive(Debug, Default)]
+  struct Log {
+    events: Vec<KeyEvent>,
+    clipboard: Option<String>,
+    clipboard_writes: Vec<String>,
+    pasted: Vec<String>,
+    modifier_down: bool,
+  }
+
+  /// Teclado + clipboard de mentira; clones compartilham o mesmo registro.
+  #[derive(Clone, Debug, Default)]
+  pub struct Recording {
+    log: Arc<Mutex<Log>>,
+  }
+
+  impl Recording {
+    pub fn with_clipboard(text: &str) -> Self {
+      let r = Self::default();
+      r.log.lock().unwrap().clipboard = Some(text.to_string());
+      r
+    }
+
+    pub fn injector(&self) -> Injector {
+      Injector::new(self.clone(), self.clone())
+    }
+
+    pub fn events(&self) -> Vec<KeyEvent> {
+      self.log.lock().unwrap().events.clone()
+    }
+
+    pub fn clipboard(&self) -> Option<String> {
+      self.log.lock().unwrap().clipboard.clone()
+    }
+
+    pub fn clipboard_writes(&self) -> Vec<String> {
+      self.log.lock().unwrap().clipboard_writes.clone()
+    }
+
+    /// O que estava no clipboard a cada Ctrl+V (o que o jogo recebeu).
+    pub fn pasted(&self) -> Vec<String> {
+      self.log.lock().unwrap().pasted.clone()
+    }
+  }
+
+  impl KeyboardInjector for Recording {
+    fn send(&self, events: &[KeyEvent]) -> Result<(), String> {
+      let mut log = self.log.lock().unwrap();
+      for ev in events {
+        match *ev {
+          KeyEvent::Down(Key::PasteModifier) => log.modifier_down = true,
+          KeyEvent::Up(Key::PasteModifier) => log.modifier_down = false,
+          KeyEvent::Down(Key::V) if log.modifier_down => {
+            let text = log.clipboard.clone().unwrap_or_default();
+            log.pasted.push(text);
+          }
+          _ => {}
+        }
+        log.events.push(*ev);
+      }
+      Ok(())
+    }
+  }
+
+  impl ClipboardAccess for Recording {
+    fn get_text(&self) -> Result<Option<String>, String> {
+      Ok(self.log.lock().unwrap().clipboard.clone())
+    }
+
+    fn set_text(&self, text: &str) -> Result<(), String> {
+      let mut log = self.log.lock().unwrap();
+      log.clipboard = Some(text.to_string());
+      log.clipboard_writes.push(text.to_string());
+      Ok(())
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn picks_ydotool_only_on_wayland_with_tools() {
+    assert_eq!(Backend::select(true, true, true), Backend::Ydotool);
+    // daemon parado: ydotool só falharia a cada envio
+    assert_eq!(Backend::select(true, true, false), Backend::Enigo);
+    // sem ydotool/wl-clipboard: XWayland
+    assert_eq!(Backend::select(true, false, false), Backend::Enigo);
+    assert_eq!(Backend::select(false, true, true), Backend::Enigo);
+    assert_eq!(Backend::select(false, false, false), Backend::Enigo);
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn stale_ydotool_socket_does_not_count() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("ydotool.sock");
+    assert!(!socket_answers(&path));
+    let daemon = std::os::unix::net::UnixDatagram::bind(&path).unwrap();
+    assert!(socket_answers(&path));
+    drop(daemon);
+    // o arquivo continua lá, mas ninguém responde
+    assert!(path.exists() && !socket_answers(&path));
+  }
+
+  #[test]
+  fn pastes_between_two_enters_and_restores_clipboard() {
+    let rec = Recording::with_clipboard("what the user had");
+    rec.injector().type_and_enter("!np @123").unwrap();
+
+    let ms = Duration::from_millis;
+    assert_eq!(
+      rec.events(),
+      vec![
+        KeyEvent::Down(Key::Enter),
+        KeyEvent::Up(Key::Enter),
+        KeyEvent::Down(Key::PasteModifier),
+        KeyEvent::Wait(ms(20)),
+        KeyEvent::Down(Key::V),
+        KeyEvent::Up(Key::V),
+        KeyEvent::Wait(ms(20)),
+        KeyEvent::Up(Key::PasteModifier),
+        KeyEvent::Wait(ms(250)),
+        KeyEvent::Down(Key::Enter),
+        KeyEvent::Up(Key::Enter),
+      ]
+    );
+    assert_eq!(rec.pasted(), vec!["!np @123"]);
+    assert_eq!(rec.clipboard_writes(), vec!["!np @123", "what the user had"]);
+    assert_eq!(rec.clipboard().as_deref(), Some("what the user had"));
+
+    // clipboard sem texto: nada para restaurar
+    let rec = Recording::default();
+    rec.injector().type_and_enter("/p 4 @1").unwrap();
+    assert_eq!(rec.clipboard_writes(), vec!["/p 4 @1"]);
+  }
+}
@@ -1,56 +1,114 @@
+mod atomic_file;
+mod autoplay;
+mod categories;
+mod clipboard_watch;
+mod control_api;
+mod discord;
+mod game_command;
+mod importers;
+mod inject;
+mod map_cache;
+mod map_diff;
+mod map_fingerprint;
+mod map_info;
+mod map_lint;
+mod map_preview;
+mod map_xml;
+mod mapcode;
+mod massperm;
+mod session_watch;
+mod shortcut;
+mod submission_limit;
+mod target_window;
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
   use std::{
     fs,
-    path::Path,
+    path::{Path, PathBuf},
     time::Duration as StdDuration,
     sync::{
-      atomic::{AtomicBool, Ordering},
+      atomic::{AtomicBool, AtomicU64, Ordering},
       Mutex,
     },
     thread,
-    time::Duration,
+    time::{Duration, Instant},
   };
 
   use tauri::{Emitter, Manager};
   use serde::Deserialize;
 
+  use game_command::CommandMode;
+
   #[derive(Default)]
   struct ClipboardWatcher {
     running: AtomicBool,
     handle: Mutex<Option<thread::JoinHandle<()>>>,
   }
 
-  #[derive(Clone, serde::Serialize, serde::Deserialize, Debug)]
-  enum CommandMode {
-    #[serde(rename = "!np")]
-    BangNp,
-    #[serde(rename = "/np")]
-    SlashNp,
-    #[serde(rename = "/npp")]
-    SlashNpp,
-  }
-
-  impl Default for CommandMode {
-    fn default() -> Self {
-      CommandMode::BangNp
-    }
-  }
-
-  impl std::fmt::Display for CommandMode {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-      match self {
-        CommandMode::BangNp => write!(f, "!np"),
-        CommandMode::SlashNp => write!(f, "/np"),
-        CommandMode::SlashNpp => write!(f, "/npp"),
-      }
-    }
+  #[derive(Clone, serde::Serialize, Debug)]
+  #[serde(rename_all = "camelCase")]
+  struct MapcodesCaptured {
+    mapcodes: Vec<String>,
+    // trecho do clipboard de onde os mapcodes saíram
+    snippet: String,
+    source: &'static str,
   }
 
   #[derive(Default)]
   struct NpContext {
     current_mapcode: Mutex<Option<String>>,
     command_mode: Mutex<CommandMode>,
+    // categoria da sessão ("P17"), escolhe o tempo do autoplay
+    category: Mutex<Option<String>>,
+    // posição na fila visível e autor (para a API de controle/overlay)
+    position: Mutex<QueuePosition>,
+    author: Mutex<Option<String>>,
+  }
+
+  #[derive(Clone, Copy, Default, serde::Serialize, Debug)]
+  #[serde(rename_all = "camelCase")]
+  struct QueuePosition {
+    // 0-based; None sem seleção
+    index: Option<usize>,
+    total: usize,
+  }
+
+  // Categorias vigiadas pelo poller; `generation` muda a cada troca para a thread antiga parar.
+  struct SessionWatchState {
+    watch: Mutex<session_watch::Watch>,
+    path: PathBuf,
+    generation: AtomicU64,
+  }
+
+  // Webhook do Discord para o resumo da sessão (salvo em disco).
+  struct DiscordWebhookState {
+    config: Mutex<discord::Config>,
+    path: PathBuf,
+  }
+
+  // API de controle local; `server` só existe enquanto está ligada.
+  struct ControlApiState {
+    config: Mutex<control_api::Config>,
+    path: PathBuf,
+    server: Mutex<Option<control_api::Server>>,
+  }
+
+  // Autoplay da sessão; `generation` muda a cada start/stop para a thread antiga parar.
+  struct AutoplayState {
+    timer: Mutex<autoplay::Timer>,
+    intervals: Mutex<autoplay::Intervals>,
+    path: PathBuf,
+    generation: AtomicU64,
+  }
+
+  // Runner do mass perm; `generation` muda a cada start/pause para a thread antiga parar.
+  struct MassPermState {
+    runner: Mutex<massperm::Runner>,
+    path: PathBuf,
+    generation: AtomicU64,
+    // um comando por vez na janela (tick automático x hotkey)
+    send_lock: Mutex<()>,
   }
 
   struct HotkeyRegistry {
@@ -64,6 +122,35 @@ pub fn run() {
     mp_prev: Mutex<Option<tauri_plugin_global_shortcut::Shortcut>>,
     mp_enabled: AtomicBool,
     enabled: AtomicBool,
+    // layout do teclado do revisor (resolve `<`, `?` etc. para a tecla certa)
+    layout: Mutex<shortcut::KeyboardLayout>,
+    // Leader-key sequences (leader -> follow-up registered only while armed)
+    chords: Mutex<Vec<ChordBinding>>,
+    armed_chord: Mutex<Option<ArmedChord>>,
+    chord_generation: AtomicU64,
+  }
+
+  #[derive(Clone, Debug)]
+  struct ChordBinding {
+    leader: tauri_plugin_global_shortcut::Shortcut,
+    leader_accel: String,
+    follow_ups: Vec<ChordFollowUp>,
+    timeout: Duration,
+  }
+
+  #[derive(Clone, Debug)]
+  struct ChordFollowUp {
+    shortcut: tauri_plugin_global_shortcut::Shortcut,
+    accel: String,
+    action: String,
+  }
+
+  #[derive(Clone, Debug)]
+  struct ArmedChord {
+    binding: usize,
+    generation: u64,
+    // só as follow-ups que nós registramos (as demais já pertenciam a outra hotkey)
+    registered: Vec<tauri_plugin_global_shortcut::Shortcut>,
   }
 
   impl Default for HotkeyRegistry {
@@ -78,6 +165,10 @@ pub fn run() {
         mp_prev: Mutex::new(None),
         mp_enabled: AtomicBool::new(false),
         enabled: AtomicBool::new(true),
+        layout: Mutex::new(shortcut::KeyboardLayout::default()),
+        chords: Mutex::new(Vec::new()),
+        armed_chord: Mutex::new(None),
+        chord_generation: AtomicU64::new(0),
       }
     }
   }
@@ -87,6 +178,14 @@ pub fn run() {
   struct NpContextUpdate {
     mapcode: Option<String>,
     command_mode: CommandMode,
+    #[serde(default)]
+    category: Option<String>,
+    #[serde(default)]
+    index: Option<usize>,
+    #[serde(default)]
+    total: usize,
+    #[serde(default)]
+    author: Option<String>,
   }
 
   #[derive(Clone, serde::Deserialize, Debug)]
@@ -117,6 +216,28 @@ pub fn run() {
     hotkeys: MassPermHotkeysArgs,
   }
 
+  #[derive(Clone, serde::Deserialize, Debug)]
+  #[serde(rename_all = "camelCase")]
+  struct ChordFollowUpArgs {
+    // ex.: "1" -> action "decision:1"
+    key: String,
+    action: String,
+  }
+
+  #[derive(Clone, serde::Deserialize, Debug)]
+  #[serde(rename_all = "camelCase")]
+  struct ChordArgs {
+    leader: String,
+    follow_ups: Vec<ChordFollowUpArgs>,
+    timeout_ms: Option<u64>,
+  }
+
+  #[derive(Clone, serde::Deserialize, Debug)]
+  #[serde(rename_all = "camelCase")]
+  struct RegisterChordHotkeysArgs {
+    chords: Vec<ChordArgs>,
+  }
+
   #[derive(Clone, serde::Deserialize, Debug)]
   #[serde(rename_all = "camelCase")]
   struct SendNpArgs {
@@ -158,6 +279,11 @@ pub fn run() {
     status: String,
     created_at: String,
     updated_at: String,
+    // fila mista (várias categorias): sessão de onde o item veio
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source_category: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source_thread_id: Option<String>,
   }
 
   #[derive(Clone, serde::Serialize, serde::Deserialize, Debug)]
@@ -453,8 +579,75 @@ pub fn run() {
   }
 
   #[tauri::command]
-  fn fetch_session_api(category_type: String) -> Result<SessionApiEnvelope, String> {
-    let Some(category) = normalize_category_code(&category_type) else {
+  fn fetch_session_api(
+    watch: tauri::State<'_, SessionWatchState>,
+    category_type: String,
+  ) -> Result<SessionApiEnvelope, String> {
+    let env = request_session_api(&category_type)?;
+    // abrir a sessão à mão conta como visto: o poller não avisa desse lote
+    if let Some(data) = env.data.as_ref() {
+      session_watch_observe(&watch, data);
+    }
+    Ok(env)
+  }
+
+  #[derive(Clone, serde::Serialize, Debug)]
+  #[serde(rename_all = "camelCase")]
+  struct SessionApiMultiEnvelope {
+    // chave = código normalizado ("P4"); código inválido fica com o texto recebido
+    results: std::collections::BTreeMap<String, SessionApiEnvelope>,
+  }
+
+  // Várias categorias em paralelo; erro de uma não derruba as outras.
+  #[tauri::command]
+  async fn fetch_sessions_api(app: tauri::AppHandle, category_types: Vec<String>) -> Result<SessionApiMultiEnvelope, String> {
+    let mut codes: Vec<String> = Vec::new();
+    for raw in &category_types {
+      let code = normalize_category_code(raw).unwrap_or_else(|| raw.trim().to_string());
+      if !codes.contains(&code) {
+        codes.push(code);
+      }
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+      let fetched: Vec<(String, SessionApiEnvelope)> = thread::scope(|scope| {
+        let handles: Vec<_> = codes
+          .iter()
+          .map(|code| scope.spawn(move || request_session_api(code)))
+          .collect();
+        codes
+          .iter()
+          .zip(handles)
+          .map(|(code, h)| {
+            // thread que entrou em pânico vira erro da categoria, em vez de sumir do resultado
+            let res = h.join().unwrap_or_else(|_| Err("session request panicked".to_string()));
+            let env = res.unwrap_or_else(|e| SessionApiEnvelope {
+              ok: false,
+              status: 0,
+              data: None,
+              error: Some(SessionApiError {
+                error: e,
+                category: normalize_category_code(code),
+                thread_id: None,
+              }),
+            });
+            (code.clone(), env)
+          })
+          .collect()
+      });
+      let watch = app.state::<SessionWatchState>();
+      for data in fetched.iter().filter_map(|(_, env)| env.data.as_ref()) {
+        session_watch_observe(&watch, data);
+      }
+      SessionApiMultiEnvelope {
+        results: fetched.into_iter().collect(),
+      }
+    })
+    .await
+    .map_err(|e| e.to_string())
+  }
+
+  fn request_session_api(category_type: &str) -> Result<SessionApiEnvelope, String> {
+    let Some(category) = normalize_category_code(category_type) else {
       return Ok(SessionApiEnvelope {
         ok: false,
         status: 400,
@@ -520,22 +713,256 @@ pub fn run() {
     }
   }
 
-  #[derive(Clone, serde::Serialize, serde::Deserialize, Debug)]
+  // -------------------------
+  // Poller da Session API
+  // -------------------------
+  // `true` quando é um lote novo de uma categoria vigiada (e salva o visto).
+  fn session_watch_observe(state: &SessionWatchState, data: &SessionApiSuccess) -> bool {
+    let Some(category) = normalize_category_code(&data.category) else {
+      return false;
+    };
+    let Ok(mut watch) = state.watch.lock() else { return false };
+    let seen = session_watch::Seen {
+      thread_id: data.thread_id.clone(),
+      collected_at: data.collected_at.clone(),
+    };
+    let is_new = watch.observe(&category, seen);
+    if let Err(e) = watch.save_file(&state.path) {
+      log::warn!("session watch not saved: {e}");
+    }
+    is_new
+  }
+
+  fn notify_session_available(app: &tauri::AppHandle, data: &SessionApiSuccess) {
+    use tauri_plugin_notification::NotificationExt;
+
+    let _ = app.emit(
+      "session_available",
+      serde_json::json!({
+        "category": data.category,
+        "threadId": data.thread_id,
+        "collectedAt": data.collected_at,
+        "maps": data.maps.len(),
+      }),
+    );
+    let title = categories::find(&data.category).map(|c| c.description.as_str()).unwrap_or(&data.category);
+    if let Err(e) = app
+      .notification()
+      .builder()
+      .title(format!("New {title} session"))
+      .body(format!("{} map(s) ready to review.", data.maps.len()))
+      .show()
+    {
+      log::warn!("notification failed: {e}");
+    }
+  }
+
+  // Uma volta por intervalo em todas as categorias vigiadas; para quando `generation` muda.
+  fn spawn_session_poller(app: &tauri::AppHandle) {
+    let st = app.state::<SessionWatchState>();
+    let generation = st.generation.fetch_add(1, Ordering::SeqCst) + 1;
+    let app = app.clone();
+    thread::spawn(move || loop {
+      let st = app.state::<SessionWatchState>();
+      let (categories, interval) = match st.watch.lock() {
+        Ok(w) => (w.categories.clone(), w.interval()),
+        Err(_) => return,
+      };
+      if categories.is_empty() {
+        return;
+      }
+      for category in &categories {
+        match request_session_api(category) {
+          Ok(SessionApiEnvelope { data: Some(data), .. }) => {
+            if st.generation.load(Ordering::SeqCst) != generation {
+              return;
+            }
+            if session_watch_observe(&st, &data) {
+              notify_session_available(&app, &data);
+            }
+          }
+          // sem sessão aberta (ou erro da API): tenta de novo na próxima volta
+          Ok(_) => {}
+          Err(e) => log::warn!("session poll {category} failed: {e}"),
+        }
+      }
+      thread::sleep(interval);
+      if st.generation.load(Ordering::SeqCst) != generation {
+        return;
+      }
+    });
+  }
+
+  #[tauri::command]
+  fn get_session_watch(state: tauri::State<'_, SessionWatchState>) -> Result<session_watch::Watch, String> {
+    Ok(state.watch.lock().map_err(|_| "session watch lock poisoned")?.clone())
+  }
+
+  /// Lista vazia desliga o poller; `interval_secs: None` mantém o intervalo.
+  #[tauri::command]
+  fn set_session_watch(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, SessionWatchState>,
+    categories: Vec<String>,
+    interval_secs: Option<u64>,
+  ) -> Result<session_watch::Watch, String> {
+    let categories = categories
+      .iter()
+      .map(|raw| normalize_category_code(raw).ok_or_else(|| format!("invalid category: {raw}")))
+      .collect::<Result<Vec<_>, _>>()?;
+    let watch = {
+      let mut w = state.watch.lock().map_err(|_| "session watch lock poisoned")?;
+      w.set(categories, interval_secs);
+      w.save_file(&state.path).map_err(|e| e.to_string())?;
+      w.clone()
+    };
+    if watch.categories.is_empty() {
+      state.generation.fetch_add(1, Ordering::SeqCst);
+    } else {
+      spawn_session_poller(&app);
+    }
+    Ok(watch)
+  }
+
+  #[derive(Clone, serde::Serialize, Debug)]
   #[serde(rename_all = "camelCase")]
   struct SubmitReviewEnvelope {
     ok: bool,
     status: u16,
     body: Option<String>,
     error: Option<String>,
+    // erros por item quando a validação local barrou o envio (error = "invalid_items")
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    item_errors: Vec<categories::ItemError>,
+  }
+
+  fn validate_review_items(category: &categories::CategoryMeta, items: &[ExportQueueItemV1]) -> Vec<categories::ItemError> {
+    let items: Vec<categories::ReviewItem> = items
+      .iter()
+      .map(|it| categories::ReviewItem {
+        id: &it.id,
+        mapcode: &it.mapcode,
+        status: &it.status,
+        decision: it.decision.as_deref(),
+        review: &it.review,
+        imported_ignored: it.imported_ignored.unwrap_or(false),
+      })
+      .collect();
+    categories::validate(category, &items)
+  }
+
+  #[tauri::command]
+  fn list_categories() -> Vec<categories::CategoryMeta> {
+    categories::all().to_vec()
+  }
+
+  // Mesma validação que o submit faz antes de enviar (para mostrar antes de fechar a sessão).
+  #[tauri::command]
+  fn validate_review_payload(
+    category_type: String,
+    payload: ExportPayloadV1,
+  ) -> Result<Vec<categories::ItemError>, String> {
+    let category = categories::find(&category_type).ok_or_else(|| format!("unknown category: {category_type}"))?;
+    Ok(validate_review_items(category, &payload.items))
+  }
+
+  // -------------------------
+  // Discord webhook
+  // -------------------------
+  fn discord_webhook_url(app: &tauri::AppHandle) -> Option<String> {
+    let st = app.state::<DiscordWebhookState>();
+    let cfg = st.config.lock().ok()?;
+    cfg.webhook_url.clone()
+  }
+
+  fn discord_post_summary(url: &str, category: &str, reviewer: Option<&str>, items: &[ExportQueueItemV1]) -> discord::PostReport {
+    let maps: Vec<discord::ReviewedMap> = items
+      .iter()
+      .map(|it| discord::ReviewedMap {
+        mapcode: &it.mapcode,
+        author: it.author.as_deref(),
+        decision: it.decision.as_deref(),
+      })
+      .collect();
+    let messages = discord::summary_messages(category, reviewer, &maps);
+    match discord::Webhook::new(url) {
+      Ok(hook) => hook.post_all(&messages),
+      Err(e) => discord::PostReport::failed(messages.len(), e),
+    }
+  }
+
+  // Depois de um submit aceito; roda em outra thread para não segurar o resultado do submit.
+  fn post_session_summary(app: &tauri::AppHandle, category: &str, reviewer: Option<String>, items: &[ExportQueueItemV1]) {
+    let Some(url) = discord_webhook_url(app) else { return };
+    let (app, category, items) = (app.clone(), category.to_string(), items.to_vec());
+    thread::spawn(move || {
+      let report = discord_post_summary(&url, &category, reviewer.as_deref(), &items);
+      if let Some(e) = &report.error {
+        log::warn!("discord webhook: {e} ({}/{} posted)", report.posted, report.total);
+      }
+      let _ = app.emit("discord_webhook_status", &report);
+    });
+  }
+
+  #[tauri::command]
+  fn get_discord_webhook(state: tauri::State<'_, DiscordWebhookState>) -> Result<discord::Config, String> {
+    Ok(state.config.lock().map_err(|_| "discord webhook lock poisoned")?.clone())
+  }
+
+  /// `None` (ou vazio) desliga a postagem.
+  #[tauri::command]
+  fn set_discord_webhook(
+    state: tauri::State<'_, DiscordWebhookState>,
+    webhook_url: Option<String>,
+  ) -> Result<discord::Config, String> {
+    let webhook_url = match webhook_url.as_deref().map(str::trim).filter(|u| !u.is_empty()) {
+      Some(raw) => Some(discord::validate_url(raw)?),
+      None => None,
+    };
+    let mut cfg = state.config.lock().map_err(|_| "discord webhook lock poisoned")?;
+    cfg.webhook_url = webhook_url;
+    cfg.save_file(&state.path).map_err(|e| e.to_string())?;
+    Ok(cfg.clone())
+  }
+
+  // Resumo de exemplo; `webhook_url` deixa testar outra URL (ex.: stub local) sem salvar.
+  #[tauri::command]
+  async fn test_discord_webhook(app: tauri::AppHandle, webhook_url: Option<String>) -> Result<discord::PostReport, String> {
+    let url = webhook_url
+      .map(|u| u.trim().to_string())
+      .filter(|u| !u.is_empty())
+      .or_else(|| discord_webhook_url(&app))
+      .ok_or("no webhook URL configured")?;
+    tauri::async_runtime::spawn_blocking(move || {
+      let sample = [
+        ("@7000001", Some("Tigrounette#0001"), "p1ed"),
+        ("@7000002", Some("Melibellule#0001"), "left_as_is"),
+        ("@7000003", None, "ignored"),
+      ];
+      let maps: Vec<discord::ReviewedMap> = sample
+        .iter()
+        .map(|(mapcode, author, decision)| discord::ReviewedMap {
+          mapcode,
+          author: *author,
+          decision: Some(decision),
+        })
+        .collect();
+      let messages = discord::summary_messages("P17", Some("Maps Reviewer (test)"), &maps);
+      Ok(discord::Webhook::new(&url)?.post_all(&messages))
+    })
+    .await
+    .map_err(|e| e.to_string())?
   }
 
   #[tauri::command]
   fn submit_session_review_api(
+    app: tauri::AppHandle,
     category_type: String,
     payload: ExportPayloadV1,
     token: Option<String>,
     votecrew: Option<bool>,
     post_as_private: Option<bool>,
+    reviewer: Option<String>,
   ) -> Result<SubmitReviewEnvelope, String> {
     let Some(category) = normalize_category_code(&category_type) else {
       return Ok(SubmitReviewEnvelope {
@@ -543,6 +970,7 @@ pub fn run() {
         status: 400,
         body: None,
         error: Some("missing_category".to_string()),
+        item_errors: vec![],
       });
     };
 
@@ -553,6 +981,28 @@ pub fn run() {
         status: 400,
         body: None,
         error: Some(format!("invalid schemaVersion: {}", payload.schema_version)),
+        item_errors: vec![],
+      });
+    }
+
+    // decisões/review conferidos aqui: o backend recusaria o payload inteiro
+    let Some(meta) = categories::find(&category).filter(|c| c.reviewed) else {
+      return Ok(SubmitReviewEnvelope {
+        ok: false,
+        status: 400,
+        body: None,
+        error: Some("category_not_reviewed".to_string()),
+        item_errors: vec![],
+      });
+    };
+    let item_errors = validate_review_items(meta, &payload.items);
+    if !item_errors.is_empty() {
+      return Ok(SubmitReviewEnvelope {
+        ok: false,
+        status: 422,
+        body: None,
+        error: Some("invalid_items".to_string()),
+        item_errors,
       });
     }
 
@@ -594,11 +1044,17 @@ pub fn run() {
     let body = resp.text().unwrap_or_default();
 
     if (200..300).contains(&status) {
+      // guarda a impressão digital de cada mapa revisado (para achar reenvios depois)
+      if let Err(e) = record_reviewed_items(&app, &category, &payload.items) {
+        log::warn!("fingerprint store write failed: {e}");
+      }
+      post_session_summary(&app, &category, reviewer, &payload.items);
       Ok(SubmitReviewEnvelope {
         ok: true,
         status,
         body: if body.trim().is_empty() { None } else { Some(body) },
         error: None,
+        item_errors: vec![],
       })
     } else {
       Ok(SubmitReviewEnvelope {
@@ -610,10 +1066,23 @@ pub fn run() {
         } else {
           body
         }),
+        item_errors: vec![],
       })
     }
   }
 
+  // Itens com o mesmo formato do QueueItem (campos extras são ignorados), na ordem da fila.
+  // Sem limitPerUser (sessão sem Session API), usa o limite da categoria.
+  #[tauri::command]
+  fn check_submission_limits(
+    items: Vec<submission_limit::LimitItem>,
+    limit_per_user: Option<i64>,
+    category: Option<String>,
+  ) -> submission_limit::LimitReport {
+    let limit = limit_per_user.or_else(|| category.as_deref().and_then(categories::find)?.limit_per_user());
+    submission_limit::check(&items, limit)
+  }
+
   // -------------------------
   // Auth (validate token)
   // -------------------------
@@ -765,95 +1234,213 @@ pub fn run() {
     }
   }
 
-  fn build_np_command(mode: CommandMode, mapcode: &str) -> String {
-    format!("{mode} @{mapcode}")
+  /// Interpreta o acelerador no layout configurado; devolve o `Shortcut` e a forma canônica.
+  fn parse_shortcut(
+    reg: &HotkeyRegistry,
+    raw: &str,
+  ) -> Result<(tauri_plugin_global_shortcut::Shortcut, String), String> {
+    let layout = *reg.layout.lock().map_err(|_| "hotkey lock poisoned")?;
+    let accel = shortcut::parse_accelerator(raw, layout, shortcut::Platform::current()).map_err(|e| e.to_string())?;
+    let sc = accel
+      .to_accelerator_string()
+      .parse::<tauri_plugin_global_shortcut::Shortcut>()
+      .map_err(|e| e.to_string())?;
+    Ok((sc, accel.display()))
   }
 
-  fn build_perm_command(category_number: i32, mapcode: &str) -> String {
-    format!("/p {} @{}", category_number, mapcode)
-  }
+  // -------------------------
+  // Mass perm runner
+  // -------------------------
+  // tempo para o mapa carregar entre o /np e o /p
+  const MASSPERM_NP_SETTLE: Duration = Duration::from_millis(800);
 
-  fn is_macos() -> bool {
-    cfg!(target_os = "macos")
+  fn massperm_emit(app: &tauri::AppHandle, runner: &massperm::Runner) -> massperm::Snapshot {
+    let st = app.state::<MassPermState>();
+    if let Err(e) = runner.save_file(&st.path) {
+      log::warn!("mass perm progress not saved: {e}");
+    }
+    let snap = runner.snapshot();
+    let _ = app.emit("massperm_status", &snap);
+    snap
+  }
+
+  fn massperm_send(app: &tauri::AppHandle, step: &massperm::Step, category: i32, play_first: bool) -> Result<String, String> {
+    let st = app.state::<MassPermState>();
+    let _guard = st.send_lock.lock().map_err(|_| "mass perm lock poisoned")?;
+    let injector = app.state::<inject::Injector>();
+    if play_first {
+      let mode = app
+        .state::<NpContext>()
+        .command_mode
+        .lock()
+        .map(|m| *m)
+        .unwrap_or_default();
+      game_command::send_np(&injector, target_window::ensure_allowed, mode, &step.mapcode)?;
+      thread::sleep(MASSPERM_NP_SETTLE);
+    }
+    game_command::send_perm(&injector, target_window::ensure_allowed, category, &step.mapcode)
   }
 
-  fn normalize_shortcut_string(raw: &str) -> String {
-    let parts: Vec<String> = raw
-      .split('+')
-      .map(|part| part.trim())
-      .filter(|part| !part.is_empty())
-      .map(|part| part.to_string())
-      .collect();
-
-    if parts.is_empty() {
-      return String::new();
-    }
+  // Envia o passo fora do lock do runner e grava o resultado.
+  fn massperm_run_step(app: &tauri::AppHandle, step: massperm::Step) -> Result<massperm::Snapshot, String> {
+    let st = app.state::<MassPermState>();
+    let (category, play_first) = {
+      let r = st.runner.lock().map_err(|_| "mass perm lock poisoned")?;
+      (r.category(), r.play_first())
+    };
+    let result = massperm_send(app, &step, category, play_first);
+    let mut r = st.runner.lock().map_err(|_| "mass perm lock poisoned")?;
+    r.record(&step, result);
+    Ok(massperm_emit(app, &r))
+  }
 
-    let last_idx = parts.len().saturating_sub(1);
-    let mut normalized = Vec::with_capacity(parts.len() + 1);
-    let mut shift_present = false;
-    for (idx, part) in parts.iter().enumerate() {
-      if idx == last_idx {
-        continue;
-      }
-      if part.eq_ignore_ascii_case("shift") {
-        shift_present = true;
+  fn spawn_massperm_driver(app: &tauri::AppHandle) {
+    let st = app.state::<MassPermState>();
+    let generation = st.generation.fetch_add(1, Ordering::SeqCst) + 1;
+    let app = app.clone();
+    thread::spawn(move || loop {
+      let st = app.state::<MassPermState>();
+      let interval = match st.runner.lock() {
+        Ok(r) => r.interval_ms(),
+        Err(_) => return,
+      };
+      thread::sleep(Duration::from_millis(interval));
+      if st.generation.load(Ordering::SeqCst) != generation {
+        return;
       }
-      normalized.push(part.clone());
-    }
-
-    let base = parts.get(last_idx).cloned().unwrap_or_default();
-    let needs_shift = matches!(base.as_str(), "<" | ">" | "?");
-    if needs_shift && !shift_present {
-      normalized.push("Shift".to_string());
-    }
+      let step = match st.runner.lock() {
+        Ok(mut r) => {
+          let step = r.tick();
+          if step.is_none() {
+            massperm_emit(&app, &r);
+          }
+          step
+        }
+        Err(_) => return,
+      };
+      let Some(step) = step else { return };
+      let _ = massperm_run_step(&app, step);
+    });
+  }
 
-    let mapped = match base.as_str() {
-      "<" | "," => "Comma",
-      ">" | "." => "Period",
-      "?" | "/" => "Slash",
-      _ => base.as_str(),
+  // Caminho comum de botões e hotkeys.
+  fn massperm_apply(app: &tauri::AppHandle, action: massperm::Action) -> Result<massperm::Snapshot, String> {
+    let st = app.state::<MassPermState>();
+    let step = {
+      let mut r = st.runner.lock().map_err(|_| "mass perm lock poisoned")?;
+      let was_running = r.status() == massperm::RunStatus::Running;
+      match r.apply(action) {
+        Err(e) => {
+          r.set_message(e);
+          return Ok(massperm_emit(app, &r));
+        }
+        Ok(step) => {
+          let running = r.status() == massperm::RunStatus::Running;
+          if running != was_running {
+            if running {
+              spawn_massperm_driver(app);
+            } else {
+              st.generation.fetch_add(1, Ordering::SeqCst);
+            }
+          }
+          match step {
+            Some(step) => step,
+            None => return Ok(massperm_emit(app, &r)),
+          }
+        }
+      }
     };
-    normalized.push(mapped.to_string());
+    massperm_run_step(app, step)
+  }
+
+  const AUTOPLAY_POLL: Duration = Duration::from_millis(200);
 
-    normalized.join("+")
+  fn autoplay_emit(app: &tauri::AppHandle, timer: &autoplay::Timer) -> autoplay::Snapshot {
+    let snap = timer.snapshot(Instant::now());
+    let _ = app.emit("autoplay_status", &snap);
+    snap
   }
 
-  fn paste_modifier_key() -> enigo::Key {
-    if is_macos() {
-      enigo::Key::Meta
-    } else {
-      enigo::Key::Control
+  // Digita o `/np` do próximo fora do lock do timer; a webview só acompanha a seleção
+  // (`autoplay_next`). Envio que falha pausa o autoplay com o erro.
+  fn autoplay_play_step(app: &tauri::AppHandle, generation: u64, step: autoplay::Step) {
+    let mode = app
+      .state::<NpContext>()
+      .command_mode
+      .lock()
+      .map(|m| *m)
+      .unwrap_or_default();
+    let injector = app.state::<inject::Injector>();
+    let result = game_command::send_np(&injector, target_window::ensure_allowed, mode, &step.mapcode);
+
+    let st = app.state::<AutoplayState>();
+    if st.generation.load(Ordering::SeqCst) != generation {
+      return;
+    }
+    let Ok(secs) = st.intervals.lock().map(|iv| iv.secs_for(step.category.as_deref())) else { return };
+    let Ok(mut timer) = st.timer.lock() else { return };
+    let now = Instant::now();
+    match result {
+      Ok(_) => {
+        timer.map_changed(Some(step.index), Some(step.mapcode.clone()), step.category.clone(), secs, now);
+        let _ = app.emit(
+          "autoplay_next",
+          serde_json::json!({ "index": step.index, "mapcode": step.mapcode }),
+        );
+      }
+      Err(e) => timer.pause(&format!("send failed: {e}"), now),
     }
+    autoplay_emit(app, &timer);
   }
 
-  fn type_in_active_window_and_enter(text: &str) -> Result<(), String> {
-    use enigo::{Direction, Enigo, Key, Keyboard, Settings};
-    use std::{thread, time::Duration};
-
-    let mut enigo = Enigo::new(&Settings::default()).map_err(|e| e.to_string())?;
+  fn spawn_autoplay_driver(app: &tauri::AppHandle) {
+    let st = app.state::<AutoplayState>();
+    let generation = st.generation.fetch_add(1, Ordering::SeqCst) + 1;
+    let app = app.clone();
+    thread::spawn(move || loop {
+      thread::sleep(AUTOPLAY_POLL);
+      let st = app.state::<AutoplayState>();
+      if st.generation.load(Ordering::SeqCst) != generation {
+        return;
+      }
+      let Ok(mut timer) = st.timer.lock() else { return };
+      let step = match timer.poll(Instant::now()) {
+        autoplay::Tick::Quiet => None,
+        autoplay::Tick::Changed => {
+          autoplay_emit(&app, &timer);
+          None
+        }
+        autoplay::Tick::Advance(step) => Some(step),
+      };
+      if timer.status() == autoplay::Status::Off {
+        return;
+      }
+      drop(timer);
+      if let Some(step) = step {
+        autoplay_play_step(&app, generation, step);
+      }
+    });
+  }
 
-    // Clipboard + paste is faster than text() for long commands.
-    let mut clipboard = arboard::Clipboard::new().map_err(|e| e.to_string())?;
-    let prev_clip = clipboard.get_text().ok();
-    clipboard.set_text(text.to_string()).map_err(|e| e.to_string())?;
+  // Hotkey de review pressionada: o revisor assumiu, o autoplay espera.
+  fn autoplay_pause_for_hotkey(app: &tauri::AppHandle) {
+    let st = app.state::<AutoplayState>();
+    let Ok(mut timer) = st.timer.lock() else { return };
+    if timer.status() == autoplay::Status::Running {
+      timer.pause("review hotkey", Instant::now());
+      autoplay_emit(app, &timer);
+    }
+  }
 
-    // Flow: Enter (open chat) -> paste -> Enter (submit)
-    enigo.key(Key::Return, Direction::Click).map_err(|e| e.to_string())?;
-    let cmd = paste_modifier_key();
-    enigo.key(cmd, Direction::Press).map_err(|e| e.to_string())?;
-    thread::sleep(Duration::from_millis(20));
-    enigo.key(Key::Unicode('v'), Direction::Click).map_err(|e| e.to_string())?;
-    thread::sleep(Duration::from_millis(20));
-    enigo.key(cmd, Direction::Release).map_err(|e| e.to_string())?;
-    thread::sleep(Duration::from_millis(250));
-    enigo.key(Key::Return, Direction::Click).map_err(|e| e.to_string())?;
+  // Próximo/anterior e replay: mesmo caminho para as hotkeys globais e a API de controle.
+  fn review_nav(app: &tauri::AppHandle, delta: i32) {
+    autoplay_pause_for_hotkey(app);
+    let _ = app.emit("hotkey_nav_play", serde_json::json!({ "delta": delta }));
+  }
 
-    // best-effort clipboard restore (only if it was text)
-    if let Some(prev) = prev_clip {
-      let _ = clipboard.set_text(prev);
-    }
-    Ok(())
+  fn review_replay(app: &tauri::AppHandle) {
+    autoplay_pause_for_hotkey(app);
+    let _ = app.emit("hotkey_replay_current", ());
   }
 
   fn unregister_shortcut(app: &tauri::AppHandle, sc: &Option<tauri_plugin_global_shortcut::Shortcut>) {
@@ -927,18 +1514,14 @@ pub fn run() {
       return Err("empty mass perm hotkey".to_string());
     }
 
-    let mp_play = normalize_shortcut_string(toggle_accel)
-      .parse::<tauri_plugin_global_shortcut::Shortcut>()
-      .map_err(|_| format!("invalid hotkey (toggle): {toggle_accel}"))?;
-    let mp_pause = normalize_shortcut_string(play_current_accel)
-      .parse::<tauri_plugin_global_shortcut::Shortcut>()
-      .map_err(|_| format!("invalid hotkey (playCurrent): {play_current_accel}"))?;
-    let mp_next = normalize_shortcut_string(next_accel)
-      .parse::<tauri_plugin_global_shortcut::Shortcut>()
-      .map_err(|_| format!("invalid hotkey (next): {next_accel}"))?;
-    let mp_prev = normalize_shortcut_string(prev_accel)
-      .parse::<tauri_plugin_global_shortcut::Shortcut>()
-      .map_err(|_| format!("invalid hotkey (prev): {prev_accel}"))?;
+    let (mp_play, _) = parse_shortcut(reg, toggle_accel)
+      .map_err(|e| format!("invalid hotkey (toggle): {toggle_accel} ({e})"))?;
+    let (mp_pause, _) = parse_shortcut(reg, play_current_accel)
+      .map_err(|e| format!("invalid hotkey (playCurrent): {play_current_accel} ({e})"))?;
+    let (mp_next, _) = parse_shortcut(reg, next_accel)
+      .map_err(|e| format!("invalid hotkey (next): {next_accel} ({e})"))?;
+    let (mp_prev, _) = parse_shortcut(reg, prev_accel)
+      .map_err(|e| format!("invalid hotkey (prev): {prev_accel} ({e})"))?;
 
     *reg.mp_play.lock().map_err(|_| "hotkey lock poisoned")? = Some(mp_play.clone());
     *reg.mp_pause.lock().map_err(|_| "hotkey lock poisoned")? = Some(mp_pause.clone());
@@ -1006,6 +1589,14 @@ pub fn run() {
       .map_err(|_| "hotkey lock poisoned")?
       .clone();
 
+    let leaders: Vec<_> = reg
+      .chords
+      .lock()
+      .map_err(|_| "hotkey lock poisoned")?
+      .iter()
+      .map(|c| c.leader.clone())
+      .collect();
+
     if enabled {
       if let Some(sc) = prev {
         let _ = app.global_shortcut().register(sc);
@@ -1016,142 +1607,1331 @@ pub fn run() {
       if let Some(sc) = replay {
         let _ = app.global_shortcut().register(sc);
       }
+      for sc in leaders {
+        let _ = app.global_shortcut().register(sc);
+      }
     } else {
+      disarm_chord(app, reg);
       unregister_shortcut(app, &prev);
       unregister_shortcut(app, &next);
       unregister_shortcut(app, &replay);
+      for sc in leaders {
+        unregister_shortcut(app, &Some(sc));
+      }
     }
 
     let _ = app.emit("hotkeys_status", serde_json::json!({ "enabled": enabled }));
     Ok(())
   }
 
-  #[tauri::command]
-  fn set_review_hotkeys_enabled_cmd(
-    app: tauri::AppHandle,
-    reg: tauri::State<'_, HotkeyRegistry>,
-    enabled: bool,
-  ) -> Result<(), String> {
-    set_hotkeys_enabled(&app, &reg, enabled)
-  }
-
-  #[tauri::command]
-  fn read_clipboard_text() -> Result<Option<String>, String> {
-    let mut clipboard = arboard::Clipboard::new().map_err(|e| e.to_string())?;
-    match clipboard.get_text() {
-      Ok(text) => Ok(Some(text)),
-      Err(arboard::Error::ContentNotAvailable) => Ok(None),
-      Err(e) => Err(e.to_string()),
-    }
-  }
-
-  #[tauri::command]
-  fn write_clipboard_text(text: String) -> Result<(), String> {
-    let mut clipboard = arboard::Clipboard::new().map_err(|e| e.to_string())?;
-    clipboard.set_text(text).map_err(|e| e.to_string())?;
-    Ok(())
-  }
-
-  #[tauri::command]
-  fn read_text_file(path: String) -> Result<String, String> {
-    fs::read_to_string(path).map_err(|e| e.to_string())
-  }
-
   // -------------------------
-  // Cypher801 mapInfo
+  // Chord / leader-key hotkeys
   // -------------------------
-  #[derive(Clone, serde::Serialize, serde::Deserialize, Debug)]
-  struct CypherMapInfoEntry {
-    id: i64,
-    author: String,
-    xml: String,
-    p: i64,
+  // O handler do global-shortcut roda com o lock interno do plugin; registrar/desregistrar
+  // de dentro dele trava. Por isso arm/disarm a partir do handler vão para outra thread.
+  const CHORD_DEFAULT_TIMEOUT_MS: u64 = 1500;
+
+  fn disarm_chord(app: &tauri::AppHandle, reg: &HotkeyRegistry) -> Option<ArmedChord> {
+    let armed = reg.armed_chord.lock().ok()?.take()?;
+    for sc in &armed.registered {
+      unregister_shortcut(app, &Some(sc.clone()));
+    }
+    Some(armed)
   }
 
-  #[derive(Clone, serde::Serialize, serde::Deserialize, Debug)]
-  struct CypherMapInfoResponse {
-    error: bool,
-    data: Vec<CypherMapInfoEntry>,
-  }
+  fn arm_chord(app: &tauri::AppHandle, reg: &HotkeyRegistry, binding_idx: usize) -> Result<(), String> {
+    use tauri_plugin_global_shortcut::GlobalShortcutExt;
 
-  #[tauri::command]
-  fn fetch_map_info(map_ids: Vec<i64>) -> Result<CypherMapInfoResponse, String> {
-    const KEY: &str = "e3b0c44298fc1c149af9-934ca495991b7852b855";
+    disarm_chord(app, reg);
 
-    let ids: Vec<i64> = map_ids
+    let Some(binding) = reg
+      .chords
+      .lock()
+      .map_err(|_| "hotkey lock poisoned")?
+      .get(binding_idx)
+      .cloned()
+    else {
+      return Ok(());
+    };
+
+    // registra temporariamente as follow-ups; as que já estão registradas por outra
+    // hotkey (ex.: PageDown) continuam chegando no handler e não são desregistradas depois
+    let mut registered = Vec::new();
+    for f in &binding.follow_ups {
+      if f.shortcut == binding.leader || registered.contains(&f.shortcut) {
+        continue;
+      }
+      if app.global_shortcut().is_registered(f.shortcut.clone()) {
+        continue;
+      }
+      if app.global_shortcut().register(f.shortcut.clone()).is_ok() {
+        registered.push(f.shortcut.clone());
+      }
+    }
+
+    let generation = reg.chord_generation.fetch_add(1, Ordering::SeqCst) + 1;
+    *reg.armed_chord.lock().map_err(|_| "hotkey lock poisoned")? = Some(ArmedChord {
+      binding: binding_idx,
+      generation,
+      registered,
+    });
+
+    let _ = app.emit(
+      "hotkey_chord_armed",
+      serde_json::json!({
+        "leader": binding.leader_accel,
+        "timeoutMs": binding.timeout.as_millis() as u64
+      }),
+    );
+
+    let app = app.clone();
+    thread::spawn(move || {
+      thread::sleep(binding.timeout);
+      let reg = app.state::<HotkeyRegistry>();
+      let expired = reg
+        .armed_chord
+        .lock()
+        .ok()
+        .map(|g| g.as_ref().map(|a| a.generation) == Some(generation))
+        .unwrap_or(false);
+      if expired && disarm_chord(&app, &reg).is_some() {
+        let _ = app.emit("hotkey_chord_timeout", serde_json::json!({ "leader": binding.leader_accel }));
+      }
+    });
+
+    Ok(())
+  }
+
+  /// Resolve uma follow-up da sequência armada (chamado de dentro do handler).
+  fn take_chord_follow_up(
+    reg: &HotkeyRegistry,
+    shortcut: &tauri_plugin_global_shortcut::Shortcut,
+  ) -> Option<(ArmedChord, ChordBinding, ChordFollowUp)> {
+    let mut armed_guard = reg.armed_chord.lock().ok()?;
+    let armed = armed_guard.as_ref()?;
+    let binding = reg.chords.lock().ok()?.get(armed.binding).cloned()?;
+    let follow_up = binding.follow_ups.iter().find(|f| &f.shortcut == shortcut).cloned()?;
+    let armed = armed_guard.take()?;
+    Some((armed, binding, follow_up))
+  }
+
+  fn find_chord_leader(reg: &HotkeyRegistry, shortcut: &tauri_plugin_global_shortcut::Shortcut) -> Option<usize> {
+    reg.chords.lock().ok()?.iter().position(|c| &c.leader == shortcut)
+  }
+
+  #[tauri::command]
+  fn register_chord_hotkeys(
+    app: tauri::AppHandle,
+    reg: tauri::State<'_, HotkeyRegistry>,
+    args: RegisterChordHotkeysArgs,
+  ) -> Result<(), String> {
+    use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+    let mut bindings = Vec::with_capacity(args.chords.len());
+    for chord in &args.chords {
+      let leader_accel = chord.leader.trim();
+      if leader_accel.is_empty() {
+        return Err("empty chord leader".to_string());
+      }
+      let (leader, _) = parse_shortcut(&reg, leader_accel)
+        .map_err(|e| format!("invalid hotkey (chord leader): {leader_accel} ({e})"))?;
+      if bindings.iter().any(|b: &ChordBinding| b.leader == leader) {
+        return Err(format!("duplicate chord leader: {leader_accel}"));
+      }
+
+      let mut follow_ups = Vec::with_capacity(chord.follow_ups.len());
+      for f in &chord.follow_ups {
+        let key = f.key.trim();
+        let action = f.action.trim();
+        if key.is_empty() || action.is_empty() {
+          return Err(format!("empty chord follow-up ({leader_accel})"));
+        }
+        let (shortcut, _) = parse_shortcut(&reg, key)
+          .map_err(|e| format!("invalid hotkey (chord follow-up): {leader_accel} {key} ({e})"))?;
+        follow_ups.push(ChordFollowUp {
+          shortcut,
+          accel: key.to_string(),
+          action: action.to_string(),
+        });
+      }
+      if follow_ups.is_empty() {
+        return Err(format!("chord without follow-ups: {leader_accel}"));
+      }
+
+      let timeout_ms = chord.timeout_ms.unwrap_or(CHORD_DEFAULT_TIMEOUT_MS).clamp(200, 10_000);
+      bindings.push(ChordBinding {
+        leader,
+        leader_accel: leader_accel.to_string(),
+        follow_ups,
+        timeout: Duration::from_millis(timeout_ms),
+      });
+    }
+
+    // registra os líderes novos antes de mexer no estado: se um falhar, desfaz os que
+    // entraram e os antigos continuam valendo (e desregistráveis)
+    let old: Vec<_> = reg
+      .chords
+      .lock()
+      .map_err(|_| "hotkey lock poisoned")?
+      .iter()
+      .map(|c| c.leader.clone())
+      .collect();
+    let enabled = reg.enabled.load(Ordering::SeqCst);
+    if enabled {
+      let mut added = Vec::new();
+      for b in &bindings {
+        // líder que já era nosso continua registrado
+        if old.contains(&b.leader) {
+          continue;
+        }
+        if let Err(e) = app.global_shortcut().register(b.leader.clone()) {
+          for sc in added {
+            unregister_shortcut(&app, &Some(sc));
+          }
+          return Err(format!("{}: {e}", b.leader_accel));
+        }
+        added.push(b.leader.clone());
+      }
+    }
+
+    disarm_chord(&app, &reg);
+    for sc in old {
+      if !bindings.iter().any(|b| b.leader == sc) {
+        unregister_shortcut(&app, &Some(sc));
+      }
+    }
+
+    let leaders: Vec<String> = bindings.iter().map(|b| b.leader_accel.clone()).collect();
+    *reg.chords.lock().map_err(|_| "hotkey lock poisoned")? = bindings;
+
+    let _ = app.emit(
+      "chord_hotkeys_registered",
+      serde_json::json!({ "enabled": enabled, "leaders": leaders }),
+    );
+    Ok(())
+  }
+
+  #[tauri::command]
+  fn set_review_hotkeys_enabled_cmd(
+    app: tauri::AppHandle,
+    reg: tauri::State<'_, HotkeyRegistry>,
+    enabled: bool,
+  ) -> Result<(), String> {
+    set_hotkeys_enabled(&app, &reg, enabled)
+  }
+
+  #[tauri::command]
+  fn read_clipboard_text(injector: tauri::State<'_, inject::Injector>) -> Result<Option<String>, String> {
+    injector.clipboard().get_text()
+  }
+
+  #[tauri::command]
+  fn write_clipboard_text(injector: tauri::State<'_, inject::Injector>, text: String) -> Result<(), String> {
+    injector.clipboard().set_text(&text)
+  }
+
+  #[tauri::command]
+  fn read_text_file(path: String) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| e.to_string())
+  }
+
+  #[tauri::command]
+  fn import_submissions(path: String, format: Option<String>) -> Result<importers::ImportResult, String> {
+    // sem format: detecta pelo conteúdo (JSON/HTML do DiscordChatExporter ou log do chat)
+    let format = format
+      .as_deref()
+      .map(str::parse::<importers::ImportFormat>)
+      .transpose()?;
+    let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    importers::import_text(&text, format)
+  }
+
+  // -------------------------
+  // Cypher801 mapInfo
+  // -------------------------
+  struct MapInfoSource {
+    provider: Mutex<std::sync::Arc<dyn map_info::MapInfoProvider>>,
+    // escolha feita nas configurações (`map_info_provider.json`)
+    path: PathBuf,
+  }
+
+  // Mesma prioridade da Session API: env em runtime → .env vizinho → env no build.
+  //   MAPINFO_LOCAL_DIR     diretório com `<id>.xml` (ignora o Cypher801)
+  //   CYPHER801_BASE_URL    default https://cypher801.app/
+  //   CYPHER801_KEY         sem ela (e sem key salva nas configurações) o Cypher801 não responde
+  fn env_map_info_config() -> map_info::ProviderConfig {
+    let file_map = read_sibling_dotenv().unwrap_or_default();
+    let setting = |name: &str, build: Option<&'static str>| -> String {
+      std::env::var(name)
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+        .or_else(|| file_map.get(name).cloned().filter(|v| !v.trim().is_empty()))
+        .or_else(|| build.map(str::to_string))
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+    };
+
+    let local_dir = setting("MAPINFO_LOCAL_DIR", option_env!("MAPINFO_LOCAL_DIR"));
+    if !local_dir.is_empty() {
+      if Path::new(&local_dir).is_dir() {
+        return map_info::ProviderConfig::LocalDir { dir: local_dir };
+      }
+      log::warn!("MAPINFO_LOCAL_DIR ignored: not a directory: {local_dir}");
+    }
+
+    let base_url = setting("CYPHER801_BASE_URL", option_env!("CYPHER801_BASE_URL"));
+    let key = setting("CYPHER801_KEY", option_env!("CYPHER801_KEY"));
+    map_info::ProviderConfig::Cypher801 {
+      base_url: (!base_url.is_empty()).then(|| normalize_base_url(&base_url)),
+      key: (!key.is_empty()).then_some(key),
+    }
+  }
+
+  // configuração salva tem prioridade; inválida (ex.: diretório que sumiu) cai no env
+  fn load_map_info_provider(path: &Path) -> Result<std::sync::Arc<dyn map_info::MapInfoProvider>, String> {
+    let env = env_map_info_config();
+    if let Some(saved) = map_info::ProviderConfig::load_file(path) {
+      match saved.with_defaults(&env).build() {
+        Ok(p) => return Ok(p),
+        Err(e) => log::warn!("saved map info provider ignored: {e}"),
+      }
+    }
+    env.build()
+  }
+
+  #[derive(Clone, serde::Serialize, Debug)]
+  #[serde(rename_all = "camelCase")]
+  struct MapInfoProviderInfo {
+    kind: &'static str,
+    // URL base ou diretório (a key nunca sai daqui)
+    location: String,
+  }
+
+  #[derive(Clone, serde::Deserialize, Debug)]
+  #[serde(rename_all = "camelCase")]
+  struct SetMapInfoProviderArgs {
+    // "cypher801" | "localDir" | "default" (volta para o configurado no .env/env)
+    kind: String,
+    base_url: Option<String>,
+    key: Option<String>,
+    dir: Option<String>,
+  }
+
+  fn provider_info(p: &dyn map_info::MapInfoProvider) -> MapInfoProviderInfo {
+    MapInfoProviderInfo {
+      kind: p.kind(),
+      location: p.describe(),
+    }
+  }
+
+  #[tauri::command]
+  fn get_map_info_provider(source: tauri::State<'_, MapInfoSource>) -> Result<MapInfoProviderInfo, String> {
+    let provider = source.provider.lock().map_err(|_| "map info provider lock poisoned")?;
+    Ok(provider_info(provider.as_ref()))
+  }
+
+  #[tauri::command]
+  fn set_map_info_provider(
+    source: tauri::State<'_, MapInfoSource>,
+    cache: tauri::State<'_, map_cache::MapInfoCache>,
+    args: SetMapInfoProviderArgs,
+  ) -> Result<MapInfoProviderInfo, String> {
+    let trimmed = |v: &Option<String>| v.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(str::to_string);
+    let config = match args.kind.trim() {
+      "cypher801" => Some(map_info::ProviderConfig::Cypher801 {
+        base_url: trimmed(&args.base_url).map(|u| normalize_base_url(&u)),
+        key: trimmed(&args.key),
+      }),
+      "localDir" => Some(map_info::ProviderConfig::LocalDir {
+        dir: trimmed(&args.dir).ok_or("missing dir")?,
+      }),
+      "default" => None,
+      other => return Err(format!("unknown provider: {other}")),
+    };
+    let env = env_map_info_config();
+    let next = match &config {
+      Some(cfg) => cfg.clone().with_defaults(&env).build()?,
+      None => env.build()?,
+    };
+    // só salva o que o usuário digitou; o resto continua vindo do env/.env
+    match &config {
+      Some(cfg) => cfg.save_file(&source.path).map_err(|e| e.to_string())?,
+      None => fs::remove_file(&source.path)
+        .or_else(|e| if e.kind() == std::io::ErrorKind::NotFound { Ok(()) } else { Err(e) })
+        .map_err(|e| e.to_string())?,
+    }
+    let info = provider_info(next.as_ref());
+    cache.set_namespace(&next.cache_namespace());
+    *source.provider.lock().map_err(|_| "map info provider lock poisoned")? = next;
+    Ok(info)
+  }
+
+  #[derive(Clone, serde::Serialize, Debug)]
+  #[serde(rename_all = "camelCase")]
+  struct MapInfoResult {
+    id: i64,
+    // found | notFound | failed
+    status: &'static str,
+    cached: bool,
+    // veio do cache vencido porque o fetch falhou
+    stale: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+  }
+
+  #[derive(Clone, serde::Serialize, Debug)]
+  struct MapInfoResponse {
+    // true só quando nenhum id pôde ser resolvido
+    error: bool,
+    data: Vec<map_info::MapInfoEntry>,
+    // quantos itens de `data` vieram do cache local
+    cached: usize,
+    results: Vec<MapInfoResult>,
+  }
+
+  fn fetch_map_info_blocking(
+    app: &tauri::AppHandle,
+    map_ids: Vec<i64>,
+    force_refresh: bool,
+  ) -> Result<MapInfoResponse, String> {
+    let cache = app.state::<map_cache::MapInfoCache>();
+    let provider = app
+      .state::<MapInfoSource>()
+      .provider
+      .lock()
+      .map_err(|_| "map info provider lock poisoned")?
+      .clone();
+
+    let mut ids: Vec<i64> = map_ids
       .into_iter()
       .filter(|id| *id > 0)
       .collect();
+    let mut seen = std::collections::HashSet::new();
+    ids.retain(|id| seen.insert(*id));
 
     if ids.is_empty() {
-      return Ok(CypherMapInfoResponse {
+      return Ok(MapInfoResponse {
         error: false,
         data: vec![],
+        cached: 0,
+        results: vec![],
       });
     }
 
-    // mantém URLs razoáveis
-    let mut out = Vec::<CypherMapInfoEntry>::new();
-    let client = reqwest::blocking::Client::builder()
-      .timeout(StdDuration::from_secs(8))
-      .build()
-      .map_err(|e| e.to_string())?;
+    let now = map_cache::now_secs();
+    let mut lookup = if provider.cacheable() {
+      cache.lookup(&ids, now)
+    } else {
+      map_cache::Lookup {
+        missing: ids.clone(),
+        ..Default::default()
+      }
+    };
+    if force_refresh {
+      lookup.stale.append(&mut lookup.fresh);
+    }
 
-    for chunk in ids.chunks(80) {
-      let joined = chunk
-        .iter()
-        .map(|x| x.to_string())
-        .collect::<Vec<_>>()
-        .join(",");
-      let url = format!("https://cypher801.app/mapInfo/?maps={joined}&key={KEY}");
-      let resp = client.get(url).send().map_err(|e| e.to_string())?;
-      let body = resp.text().map_err(|e| e.to_string())?;
-      let parsed: CypherMapInfoResponse = serde_json::from_str(&body).map_err(|e| e.to_string())?;
-      if parsed.error {
-        return Ok(parsed);
+    let mut results: std::collections::HashMap<i64, (MapInfoResult, Option<map_info::MapInfoEntry>)> = lookup
+      .fresh
+      .iter()
+      .cloned()
+      .map(|e| {
+        let result = MapInfoResult {
+          id: e.id,
+          status: "found",
+          cached: true,
+          stale: false,
+          error: None,
+        };
+        (e.id, (result, Some(e.into())))
+      })
+      .collect();
+
+    let hits = results.len();
+    let total = ids.len();
+    let emit_progress = |p: map_info::Progress| {
+      let _ = app.emit(
+        "map_info_progress",
+        serde_json::json!({
+          "done": hits + p.done,
+          "total": total,
+          "found": hits + p.found,
+          "notFound": p.not_found,
+          "failed": p.failed,
+          "cached": hits,
+        }),
+      );
+    };
+    emit_progress(map_info::Progress::default());
+
+    let to_fetch = lookup.to_fetch();
+    if !to_fetch.is_empty() {
+      let mut stale: std::collections::HashMap<i64, map_cache::CachedMapInfo> =
+        lookup.stale.into_iter().map(|e| (e.id, e)).collect();
+
+      let fetched = map_info::fetch_concurrent(
+        &to_fetch,
+        provider.options(),
+        |chunk| provider.fetch_chunk(chunk),
+        emit_progress,
+      );
+
+      for (id, outcome) in fetched {
+        let result = |status, cached, stale, error| MapInfoResult {
+          id,
+          status,
+          cached,
+          stale,
+          error,
+        };
+        let row = match outcome {
+          map_info::Fetched::Found(entry) => {
+            if provider.cacheable() {
+              let cached = map_cache::CachedMapInfo {
+                id: entry.id,
+                author: entry.author.clone(),
+                xml: entry.xml.clone(),
+                p: entry.p,
+                fetched_at: now,
+                previous_xml: None,
+              };
+              if let Err(e) = cache.put(&cached) {
+                log::warn!("mapInfo cache write failed for {id}: {e}");
+              }
+            }
+            (result("found", false, false, None), Some(entry))
+          }
+          map_info::Fetched::NotFound => (result("notFound", false, false, None), None),
+          // sem rede (ou erro da API): o cache vencido ainda vale mais que nada
+          map_info::Fetched::Failed(e) => match stale.remove(&id) {
+            Some(old) => (result("found", true, true, Some(e)), Some(old.into())),
+            None => (result("failed", false, false, Some(e)), None),
+          },
+        };
+        results.insert(id, row);
+      }
+    }
+
+    let mut out = MapInfoResponse {
+      error: false,
+      data: vec![],
+      cached: 0,
+      results: vec![],
+    };
+    for id in &ids {
+      let Some((result, entry)) = results.remove(id) else { continue };
+      if let Some(entry) = entry {
+        out.cached += usize::from(result.cached);
+        out.data.push(entry);
       }
-      out.extend(parsed.data.into_iter());
+      out.results.push(result);
+    }
+    out.error = out.results.iter().all(|r| r.status == "failed");
+    Ok(out)
+  }
+
+  #[tauri::command]
+  async fn fetch_map_info(
+    app: tauri::AppHandle,
+    map_ids: Vec<i64>,
+    force_refresh: Option<bool>,
+  ) -> Result<MapInfoResponse, String> {
+    // fora da thread principal: uma sessão grande leva vários segundos
+    tauri::async_runtime::spawn_blocking(move || {
+      fetch_map_info_blocking(&app, map_ids, force_refresh.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+  }
+
+  #[tauri::command]
+  fn map_info_cache_stats(cache: tauri::State<'_, map_cache::MapInfoCache>) -> map_cache::CacheStats {
+    cache.stats(map_cache::now_secs())
+  }
+
+  #[tauri::command]
+  fn clear_map_info_cache(
+    cache: tauri::State<'_, map_cache::MapInfoCache>,
+    expired_only: Option<bool>,
+  ) -> Result<usize, String> {
+    cache
+      .clear(expired_only.unwrap_or(false), map_cache::now_secs())
+      .map_err(|e| e.to_string())
+  }
+
+  #[derive(Clone, serde::Serialize, Debug)]
+  #[serde(rename_all = "camelCase")]
+  struct ParsedMapXml {
+    summary: map_xml::MapSummary,
+    // modelo completo só quando pedido (grounds/objetos podem ser milhares)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    map: Option<map_xml::MapXml>,
+  }
+
+  #[tauri::command]
+  fn parse_map_xml(xml: String, full: Option<bool>) -> Result<ParsedMapXml, String> {
+    let map = map_xml::parse_map_xml(&xml).map_err(|e| e.to_string())?;
+    Ok(ParsedMapXml {
+      summary: map.summary(),
+      map: full.unwrap_or(false).then_some(map),
+    })
+  }
+
+  #[derive(Clone, serde::Deserialize, Debug)]
+  #[serde(rename_all = "camelCase")]
+  struct DiffMapXmlArgs {
+    // sem `before`: usa a versão anterior desse mapa no cache de mapInfo
+    map_id: Option<i64>,
+    before: Option<String>,
+    after: String,
+  }
+
+  // Versão anterior no cache: o XML salvo se for diferente de `after` (cache ainda não
+  // atualizado), senão o `previous_xml` guardado no último refetch que mudou o mapa.
+  fn cached_previous_xml(cache: &map_cache::MapInfoCache, map_id: i64, after: &str) -> Result<String, String> {
+    let entry = cache.get(map_id).ok_or_else(|| format!("map {map_id} is not cached"))?;
+    if entry.xml.trim() != after.trim() {
+      return Ok(entry.xml);
+    }
+    entry
+      .previous_xml
+      .ok_or_else(|| format!("no previous version of map {map_id} in cache"))
+  }
+
+  #[tauri::command]
+  fn diff_map_xml(
+    cache: tauri::State<'_, map_cache::MapInfoCache>,
+    args: DiffMapXmlArgs,
+  ) -> Result<map_diff::MapDiff, String> {
+    let before = match (args.before.filter(|x| !x.trim().is_empty()), args.map_id) {
+      (Some(xml), _) => xml,
+      (None, Some(id)) => cached_previous_xml(&cache, id, &args.after)?,
+      (None, None) => return Err("missing previous xml".into()),
+    };
+    let before = map_xml::parse_map_xml(&before).map_err(|e| format!("previous version: {e}"))?;
+    let after = map_xml::parse_map_xml(&args.after).map_err(|e| e.to_string())?;
+    Ok(map_diff::diff(&before, &after))
+  }
+
+  #[derive(Clone, serde::Deserialize, Debug)]
+  #[serde(rename_all = "camelCase")]
+  struct LintMapItem {
+    map_id: i64,
+    // "P17" / "17"; sem categoria usa o `p` atual do mapa (cache de mapInfo)
+    category: Option<String>,
+    // sem xml: usa o cache de mapInfo
+    xml: Option<String>,
+  }
+
+  #[derive(Clone, serde::Serialize, Debug)]
+  #[serde(rename_all = "camelCase")]
+  struct MapLintReport {
+    map_id: i64,
+    category: Option<String>,
+    warnings: Vec<map_lint::LintWarning>,
+    // mapa que não deu pra checar (sem XML, XML inválido, categoria inválida)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+  }
+
+  fn lint_map_item(cache: &map_cache::MapInfoCache, item: LintMapItem) -> MapLintReport {
+    let cached = cache.get(item.map_id);
+    let category = match item.category.as_deref() {
+      Some(raw) => normalize_category_code(raw),
+      None => cached.as_ref().and_then(|e| e.p).map(|p| format!("P{p}")),
+    };
+    let mut report = MapLintReport {
+      map_id: item.map_id,
+      category: category.clone(),
+      warnings: vec![],
+      error: None,
+    };
+
+    let number = category.as_deref().and_then(|c| c[1..].parse::<u32>().ok());
+    let xml = item.xml.filter(|x| !x.trim().is_empty()).or(cached.map(|e| e.xml));
+    let result = match (number, xml) {
+      (None, _) => Err(format!("invalid category: {}", item.category.unwrap_or_default())),
+      (_, None) => Err(format!("no xml for map {}", item.map_id)),
+      (Some(n), Some(xml)) => map_xml::parse_map_xml(&xml)
+        .map(|map| map_lint::lint(&map, n))
+        .map_err(|e| e.to_string()),
+    };
+    match result {
+      Ok(warnings) => report.warnings = warnings,
+      Err(e) => report.error = Some(e),
+    }
+    report
+  }
+
+  // Roda na sessão inteira (depois do fetch_map_info), na ordem recebida.
+  #[tauri::command]
+  fn lint_maps(cache: tauri::State<'_, map_cache::MapInfoCache>, items: Vec<LintMapItem>) -> Vec<MapLintReport> {
+    items.into_iter().map(|item| lint_map_item(&cache, item)).collect()
+  }
+
+  // -------------------------
+  // Fingerprints (reenvios / cópias)
+  // -------------------------
+  fn reviewed_map(
+    map_id: i64,
+    xml: &str,
+    category: Option<String>,
+    decision: Option<String>,
+    author: Option<String>,
+  ) -> Result<map_fingerprint::ReviewedMap, String> {
+    let map = map_xml::parse_map_xml(xml).map_err(|e| e.to_string())?;
+    let fp = map_fingerprint::fingerprint(&map);
+    Ok(map_fingerprint::ReviewedMap {
+      map_id,
+      hash: fp.hash,
+      features: fp.features,
+      category,
+      decision,
+      author,
+      reviewed_at: map_cache::now_secs(),
+    })
+  }
+
+  // Só itens revisados e com decisão (os pendentes não têm o que lembrar). Itens sem XML (nem no
+  // payload nem no cache) ou com XML inválido ficam de fora.
+  fn record_reviewed_items(app: &tauri::AppHandle, category: &str, items: &[ExportQueueItemV1]) -> Result<usize, String> {
+    let cache = app.state::<map_cache::MapInfoCache>();
+    let reviewed: Vec<_> = items
+      .iter()
+      .filter(|item| item.status == "reviewed")
+      .filter_map(|item| {
+        let decision = categories::Decision::parse(item.decision.as_deref()?)?;
+        let map_id = mapcode::parse_map_code(&item.mapcode).ok()?.id() as i64;
+        let xml = item
+          .xml
+          .clone()
+          .filter(|x| !x.trim().is_empty())
+          .or_else(|| cache.get(map_id).map(|e| e.xml))?;
+        reviewed_map(map_id, &xml, Some(category.to_string()), Some(decision.as_str().to_string()), item.author.clone()).ok()
+      })
+      .collect();
+    if reviewed.is_empty() {
+      return Ok(0);
+    }
+    app
+      .state::<map_fingerprint::FingerprintStore>()
+      .record(reviewed)
+      .map_err(|e| e.to_string())
+  }
+
+  #[derive(Clone, serde::Deserialize, Debug)]
+  #[serde(rename_all = "camelCase")]
+  struct RecordReviewedMapItem {
+    map_id: i64,
+    // sem xml: usa o cache de mapInfo
+    xml: Option<String>,
+    category: Option<String>,
+    decision: Option<String>,
+    author: Option<String>,
+  }
+
+  // Para reviews feitos fora do submit (ex.: sessão importada de arquivo).
+  #[tauri::command]
+  fn record_reviewed_maps(
+    cache: tauri::State<'_, map_cache::MapInfoCache>,
+    store: tauri::State<'_, map_fingerprint::FingerprintStore>,
+    items: Vec<RecordReviewedMapItem>,
+  ) -> Result<usize, String> {
+    let mut reviewed = Vec::with_capacity(items.len());
+    for item in items {
+      let xml = item
+        .xml
+        .filter(|x| !x.trim().is_empty())
+        .or_else(|| cache.get(item.map_id).map(|e| e.xml))
+        .ok_or_else(|| format!("no xml for map {}", item.map_id))?;
+      let category = item.category.as_deref().and_then(normalize_category_code);
+      reviewed.push(reviewed_map(item.map_id, &xml, category, item.decision, item.author)?);
     }
+    store.record(reviewed).map_err(|e| e.to_string())
+  }
 
-    Ok(CypherMapInfoResponse { error: false, data: out })
+  #[derive(Clone, serde::Deserialize, Debug)]
+  #[serde(rename_all = "camelCase")]
+  struct ResubmissionCheckItem {
+    map_id: i64,
+    // sem xml: usa o cache de mapInfo
+    xml: Option<String>,
   }
 
+  #[derive(Clone, serde::Serialize, Debug)]
+  #[serde(rename_all = "camelCase")]
+  struct ResubmissionReport {
+    map_id: i64,
+    // exatos primeiro, depois por similaridade
+    matches: Vec<map_fingerprint::FingerprintMatch>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+  }
+
+  // O próprio código não é excluído: mapa já revisado que voltou pra fila também conta.
   #[tauri::command]
-  fn set_np_context(ctx: NpContextUpdate, state: tauri::State<'_, NpContext>) -> Result<(), String> {
+  fn find_resubmissions(
+    cache: tauri::State<'_, map_cache::MapInfoCache>,
+    store: tauri::State<'_, map_fingerprint::FingerprintStore>,
+    items: Vec<ResubmissionCheckItem>,
+    threshold: Option<f32>,
+  ) -> Vec<ResubmissionReport> {
+    let threshold = threshold.unwrap_or(map_fingerprint::DEFAULT_THRESHOLD).clamp(0.0, 1.0);
+    items
+      .into_iter()
+      .map(|item| {
+        let xml = item
+          .xml
+          .filter(|x| !x.trim().is_empty())
+          .or_else(|| cache.get(item.map_id).map(|e| e.xml));
+        let result = match xml {
+          None => Err(format!("no xml for map {}", item.map_id)),
+          Some(xml) => map_xml::parse_map_xml(&xml)
+            .map(|map| store.matches(&map_fingerprint::fingerprint(&map), threshold, None))
+            .map_err(|e| e.to_string()),
+        };
+        let (matches, error) = match result {
+          Ok(m) => (m, None),
+          Err(e) => (vec![], Some(e)),
+        };
+        ResubmissionReport {
+          map_id: item.map_id,
+          matches,
+          error,
+        }
+      })
+      .collect()
+  }
+
+  #[derive(Clone, serde::Deserialize, Debug)]
+  #[serde(rename_all = "camelCase")]
+  struct RenderMapPreviewArgs {
+    map_id: i64,
+    // sem xml: usa o que estiver no cache de mapInfo
+    xml: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+    show_invisible: Option<bool>,
+    // devolve também o PNG como data URL (evita configurar o asset protocol)
+    inline: Option<bool>,
+  }
+
+  #[derive(Clone, serde::Serialize, Debug)]
+  #[serde(rename_all = "camelCase")]
+  struct MapPreview {
+    path: String,
+    cached: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data_url: Option<String>,
+  }
+
+  fn render_map_preview_blocking(app: &tauri::AppHandle, args: RenderMapPreviewArgs) -> Result<MapPreview, String> {
+    use base64::Engine as _;
+
+    let xml = match args.xml.filter(|x| !x.trim().is_empty()) {
+      Some(xml) => xml,
+      None => app
+        .state::<map_cache::MapInfoCache>()
+        .get(args.map_id)
+        .map(|e| e.xml)
+        .ok_or_else(|| format!("no xml for map {}", args.map_id))?,
+    };
+    let opts = map_preview::PreviewOptions {
+      width: args.width.unwrap_or(map_preview::DEFAULT_WIDTH),
+      height: args.height,
+      show_invisible: args.show_invisible.unwrap_or(false),
+    };
+
+    let previews = app.state::<map_preview::PreviewCache>();
+    let (path, cached) = previews.get_or_render(
+      args.map_id,
+      &xml,
+      || map_xml::parse_map_xml(&xml).map_err(|e| e.to_string()),
+      &opts,
+    )?;
+    let data_url = if args.inline.unwrap_or(false) {
+      let png = fs::read(&path).map_err(|e| e.to_string())?;
+      Some(format!(
+        "data:image/png;base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(png)
+      ))
+    } else {
+      None
+    };
+
+    Ok(MapPreview {
+      path: path.display().to_string(),
+      cached,
+      data_url,
+    })
+  }
+
+  #[tauri::command]
+  async fn render_map_preview(app: tauri::AppHandle, args: RenderMapPreviewArgs) -> Result<MapPreview, String> {
+    tauri::async_runtime::spawn_blocking(move || render_map_preview_blocking(&app, args))
+      .await
+      .map_err(|e| e.to_string())?
+  }
+
+  #[derive(Clone, serde::Serialize, Debug)]
+  #[serde(rename_all = "camelCase")]
+  struct ParsedMapcodes {
+    mapcodes: Vec<String>,
+    // tokens que passaram no parser tolerante mas não são códigos reais (@123456)
+    rejected: Vec<String>,
+  }
+
+  #[tauri::command]
+  fn parse_mapcodes(text: String, strict: Option<bool>) -> ParsedMapcodes {
+    let parsed = mapcode::parse_mapcodes_from_text(&text);
+    if !strict.unwrap_or(false) {
+      return ParsedMapcodes {
+        mapcodes: parsed,
+        rejected: vec![],
+      };
+    }
+    let (valid, rejected): (Vec<String>, Vec<String>) =
+      parsed.into_iter().partition(|mc| mapcode::parse_map_code(mc).is_ok());
+    ParsedMapcodes {
+      mapcodes: valid,
+      rejected,
+    }
+  }
+
+  #[tauri::command]
+  fn set_np_context(
+    app: tauri::AppHandle,
+    ctx: NpContextUpdate,
+    state: tauri::State<'_, NpContext>,
+    autoplay_state: tauri::State<'_, AutoplayState>,
+    control_api: tauri::State<'_, ControlApiState>,
+  ) -> Result<(), String> {
+    let mapcode = ctx.mapcode.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
     *state
       .current_mapcode
       .lock()
-      .map_err(|_| "np context lock poisoned")? = ctx.mapcode.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+      .map_err(|_| "np context lock poisoned")? = mapcode.clone();
     *state.command_mode.lock().map_err(|_| "np context lock poisoned")? = ctx.command_mode;
+    let category = ctx.category.as_deref().and_then(normalize_category_code);
+    *state.category.lock().map_err(|_| "np context lock poisoned")? = category.clone();
+    let index = ctx.index.filter(|i| *i < ctx.total);
+    *state.position.lock().map_err(|_| "np context lock poisoned")? = QueuePosition {
+      index,
+      total: ctx.total,
+    };
+    *state.author.lock().map_err(|_| "np context lock poisoned")? =
+      ctx.author.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+
+    // overlay do stream acompanha a seleção
+    if let Some(server) = control_api.server.lock().map_err(|_| "control api lock poisoned")?.as_ref() {
+      server.notify();
+    }
+
+    // mapa novo reinicia a contagem do autoplay (tempo da categoria do item)
+    let secs = autoplay_state
+      .intervals
+      .lock()
+      .map_err(|_| "autoplay lock poisoned")?
+      .secs_for(category.as_deref());
+    let mut timer = autoplay_state.timer.lock().map_err(|_| "autoplay lock poisoned")?;
+    if timer.map_changed(index, mapcode, category, secs, Instant::now()) {
+      autoplay_emit(&app, &timer);
+    }
+    Ok(())
+  }
+
+  // Fila visível da webview (mesma ordem do `index` do `set_np_context`).
+  #[tauri::command]
+  fn autoplay_set_queue(state: tauri::State<'_, AutoplayState>, queue: Vec<autoplay::Entry>) -> Result<(), String> {
+    let queue = queue
+      .into_iter()
+      .map(|e| autoplay::Entry {
+        mapcode: e.mapcode.trim().to_string(),
+        category: e.category.as_deref().and_then(normalize_category_code),
+      })
+      .collect();
+    state.timer.lock().map_err(|_| "autoplay lock poisoned")?.set_queue(queue);
     Ok(())
   }
 
+  #[derive(Clone, Copy, serde::Deserialize, Debug)]
+  #[serde(rename_all = "camelCase")]
+  enum AutoplayAction {
+    Start,
+    Pause,
+    Resume,
+    /// Desligado → start, rodando → pause, pausado → resume.
+    Toggle,
+    Stop,
+  }
+
   #[tauri::command]
-  fn send_np_to_active_window(args: SendNpArgs) -> Result<String, String> {
-    let mc = args.mapcode.trim();
-    if mc.is_empty() {
-      return Err("empty mapcode".into());
+  fn autoplay_control(
+    app: tauri::AppHandle,
+    np: tauri::State<'_, NpContext>,
+    state: tauri::State<'_, AutoplayState>,
+    action: AutoplayAction,
+  ) -> Result<autoplay::Snapshot, String> {
+    let now = Instant::now();
+    let mut timer = state.timer.lock().map_err(|_| "autoplay lock poisoned")?;
+    let action = match (action, timer.status()) {
+      (AutoplayAction::Toggle, autoplay::Status::Off) => AutoplayAction::Start,
+      (AutoplayAction::Toggle, autoplay::Status::Running) => AutoplayAction::Pause,
+      (AutoplayAction::Toggle, autoplay::Status::Paused) => AutoplayAction::Resume,
+      (a, _) => a,
+    };
+    match action {
+      AutoplayAction::Start => {
+        let category = np.category.lock().map_err(|_| "np context lock poisoned")?.clone();
+        let mapcode = np.current_mapcode.lock().map_err(|_| "np context lock poisoned")?.clone();
+        let secs = state
+          .intervals
+          .lock()
+          .map_err(|_| "autoplay lock poisoned")?
+          .secs_for(category.as_deref());
+        timer.start(category, secs, mapcode, now)?;
+        spawn_autoplay_driver(&app);
+      }
+      AutoplayAction::Pause => timer.pause("manual", now),
+      AutoplayAction::Resume => timer.resume(now),
+      AutoplayAction::Toggle => {}
+      AutoplayAction::Stop => {
+        state.generation.fetch_add(1, Ordering::SeqCst);
+        timer.stop("Autoplay stopped.");
+      }
     }
-    let mc = mc.strip_prefix('@').unwrap_or(mc);
-    let cmd = build_np_command(args.command_mode, mc);
-    type_in_active_window_and_enter(&cmd)?;
-    Ok(cmd)
+    Ok(autoplay_emit(&app, &timer))
+  }
+
+  #[tauri::command]
+  fn autoplay_state(state: tauri::State<'_, AutoplayState>) -> Result<autoplay::Snapshot, String> {
+    let timer = state.timer.lock().map_err(|_| "autoplay lock poisoned")?;
+    Ok(timer.snapshot(Instant::now()))
   }
 
   #[tauri::command]
-  fn send_perm_to_active_window(args: SendPermArgs) -> Result<String, String> {
-    let mc = args.mapcode.trim();
-    if mc.is_empty() {
-      return Err("empty mapcode".into());
+  fn autoplay_intervals(state: tauri::State<'_, AutoplayState>) -> Result<autoplay::Intervals, String> {
+    Ok(state.intervals.lock().map_err(|_| "autoplay lock poisoned")?.clone())
+  }
+
+  /// `category: None` muda o padrão; `secs: None` volta a categoria para o padrão.
+  #[tauri::command]
+  fn autoplay_set_interval(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AutoplayState>,
+    category: Option<String>,
+    secs: Option<u64>,
+  ) -> Result<autoplay::Intervals, String> {
+    let category = match category.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+      Some(raw) => Some(normalize_category_code(raw).ok_or_else(|| format!("invalid category: {raw}"))?),
+      None => None,
+    };
+    let intervals = {
+      let mut iv = state.intervals.lock().map_err(|_| "autoplay lock poisoned")?;
+      iv.set(category.as_deref(), secs);
+      iv.save_file(&state.path).map_err(|e| e.to_string())?;
+      iv.clone()
+    };
+
+    // rodando na mesma categoria: o tempo novo vale para o mapa atual
+    let mut timer = state.timer.lock().map_err(|_| "autoplay lock poisoned")?;
+    let snap = timer.snapshot(Instant::now());
+    if snap.status != autoplay::Status::Off && (category.is_none() || category == snap.category) {
+      timer.set_interval(intervals.secs_for(snap.category.as_deref()));
+      autoplay_emit(&app, &timer);
     }
-    if args.category_number < 0 || args.category_number > 999 {
+    Ok(intervals)
+  }
+
+  #[tauri::command]
+  fn send_np_to_active_window(injector: tauri::State<'_, inject::Injector>, args: SendNpArgs) -> Result<String, String> {
+    game_command::send_np(&injector, target_window::ensure_allowed, args.command_mode, &args.mapcode)
+  }
+
+  #[tauri::command]
+  fn send_perm_to_active_window(injector: tauri::State<'_, inject::Injector>, args: SendPermArgs) -> Result<String, String> {
+    game_command::send_perm(&injector, target_window::ensure_allowed, args.category_number, &args.mapcode)
+  }
+
+  #[derive(Clone, serde::Serialize, Debug)]
+  #[serde(rename_all = "camelCase")]
+  struct TargetWindowConfig {
+    allow_list: Vec<String>,
+    defaults: Vec<String>,
+    strict: bool,
+    /// Se dá para ler a janela em foco aqui (X11); sem isso só o modo estrito faz diferença.
+    checkable: bool,
+  }
+
+  fn target_window_config() -> TargetWindowConfig {
+    TargetWindowConfig {
+      allow_list: target_window::allow_list(),
+      defaults: target_window::default_allow_list(),
+      strict: target_window::strict(),
+      checkable: target_window::active_window().is_some(),
+    }
+  }
+
+  fn save_target_window_config(app: &tauri::AppHandle) -> Result<(), String> {
+    let path = app.path().app_data_dir().map_err(|e| e.to_string())?.join("target_windows.json");
+    target_window::save_file(&path, &target_window::config()).map_err(|e| e.to_string())
+  }
+
+  #[tauri::command]
+  fn get_target_window_config() -> TargetWindowConfig {
+    target_window_config()
+  }
+
+  /// `None` volta para a lista padrão; lista vazia desliga a checagem.
+  #[tauri::command]
+  fn set_target_window_allow_list(
+    app: tauri::AppHandle,
+    patterns: Option<Vec<String>>,
+  ) -> Result<TargetWindowConfig, String> {
+    target_window::set_allow_list(patterns.unwrap_or_else(target_window::default_allow_list));
+    save_target_window_config(&app)?;
+    Ok(target_window_config())
+  }
+
+  /// Estrito: recusa digitar quando não dá para ler a janela em foco (ex.: Wayland).
+  #[tauri::command]
+  fn set_target_window_strict(app: tauri::AppHandle, strict: bool) -> Result<TargetWindowConfig, String> {
+    target_window::set_strict(strict);
+    save_target_window_config(&app)?;
+    Ok(target_window_config())
+  }
+
+  // -------------------------
+  // API de controle local
+  // -------------------------
+  struct ControlHandler {
+    app: tauri::AppHandle,
+  }
+
+  impl ControlHandler {
+    fn current_mapcode(&self) -> Result<String, String> {
+      self
+        .app
+        .state::<NpContext>()
+        .current_mapcode
+        .lock()
+        .map_err(|_| "np context lock poisoned")?
+        .clone()
+        .ok_or_else(|| "no map selected".to_string())
+    }
+  }
+
+  impl control_api::Handler for ControlHandler {
+    fn state(&self) -> serde_json::Value {
+      let np = self.app.state::<NpContext>();
+      let mapcode = np.current_mapcode.lock().ok().and_then(|g| g.clone());
+      let command_mode = np.command_mode.lock().map(|m| *m).unwrap_or_default();
+      let category = np.category.lock().ok().and_then(|g| g.clone());
+      let category_color = category.as_deref().and_then(categories::find).map(|c| c.color.as_str());
+      let position = np.position.lock().map(|p| *p).unwrap_or_default();
+      let author = np.author.lock().ok().and_then(|g| g.clone());
+      serde_json::json!({
+        "mapcode": mapcode,
+        "author": author,
+        "commandMode": command_mode,
+        "category": category,
+        "categoryColor": category_color,
+        "position": position,
+      })
+    }
+
+    fn action(&self, action: control_api::Action) -> Result<serde_json::Value, String> {
+      match action {
+        control_api::Action::Next => review_nav(&self.app, 1),
+        control_api::Action::Prev => review_nav(&self.app, -1),
+        control_api::Action::Replay => review_replay(&self.app),
+        control_api::Action::Decision { decision } => {
+          let parsed = categories::Decision::parse(&decision).ok_or_else(|| format!("unknown decision: {decision}"))?;
+          let mapcode = self.current_mapcode()?;
+          let category = self.app.state::<NpContext>().category.lock().ok().and_then(|g| g.clone());
+          if let Some(meta) = category.as_deref().and_then(categories::find) {
+            if !meta.allows(parsed) {
+              return Err(format!("{} does not allow {}", meta.code, parsed.as_str()));
+            }
+          }
+          // a fila está na webview; ela só aplica se o mapa selecionado ainda for este
+          let _ = self.app.emit(
+            "control_decision",
+            serde_json::json!({ "mapcode": mapcode, "decision": parsed.as_str() }),
+          );
+        }
+        control_api::Action::Template { prefix, suffix } => {
+          let cmd = game_command::send_custom(
+            &self.app.state::<inject::Injector>(),
+            target_window::ensure_allowed,
+            &prefix,
+            &self.current_mapcode()?,
+            suffix.as_deref(),
+          )?;
+          return Ok(serde_json::json!({ "command": cmd }));
+        }
+      }
+      Ok(serde_json::Value::Null)
+    }
+  }
+
+  #[derive(Clone, serde::Serialize, Debug)]
+  #[serde(rename_all = "camelCase")]
+  struct ControlApiInfo {
+    enabled: bool,
+    port: u16,
+    token: String,
+    running: bool,
+  }
+
+  fn control_api_info(state: &ControlApiState) -> Result<ControlApiInfo, String> {
+    let cfg = state.config.lock().map_err(|_| "control api lock poisoned")?.clone();
+    let running = state.server.lock().map_err(|_| "control api lock poisoned")?.is_some();
+    Ok(ControlApiInfo {
+      enabled: cfg.enabled,
+      port: cfg.port,
+      token: cfg.token,
+      running,
+    })
+  }
+
+  // (Re)inicia ou para o servidor conforme a config atual.
+  fn control_api_apply(app: &tauri::AppHandle, state: &ControlApiState) -> Result<(), String> {
+    let cfg = state.config.lock().map_err(|_| "control api lock poisoned")?.clone();
+    let mut server = state.server.lock().map_err(|_| "control api lock poisoned")?;
+    if let Some(mut old) = server.take() {
+      old.stop();
+    }
+    if cfg.enabled {
+      let handler = std::sync::Arc::new(ControlHandler { app: app.clone() });
+      let started = control_api::Server::start(cfg.port, cfg.token, handler)
+        .map_err(|e| format!("control API could not listen on 127.0.0.1:{}: {e}", cfg.port))?;
+      log::info!("control API listening on 127.0.0.1:{}", started.port());
+      *server = Some(started);
+    }
+    Ok(())
+  }
+
+  #[tauri::command]
+  fn get_control_api(state: tauri::State<'_, ControlApiState>) -> Result<ControlApiInfo, String> {
+    control_api_info(&state)
+  }
+
+  #[tauri::command]
+  fn set_control_api(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, ControlApiState>,
+    enabled: bool,
+    port: Option<u16>,
+  ) -> Result<ControlApiInfo, String> {
+    if port == Some(0) {
+      return Err("invalid port".into());
+    }
+    {
+      let mut cfg = state.config.lock().map_err(|_| "control api lock poisoned")?;
+      cfg.enabled = enabled;
+      if let Some(port) = port {
+        cfg.port = port;
+      }
+      cfg.save_file(&state.path).map_err(|e| e.to_string())?;
+    }
+    control_api_apply(&app, &state)?;
+    control_api_info(&state)
+  }
+
+  // Invalida o token antigo (clientes precisam do novo).
+  #[tauri::command]
+  fn regenerate_control_api_token(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, ControlApiState>,
+  ) -> Result<ControlApiInfo, String> {
+    {
+      let mut cfg = state.config.lock().map_err(|_| "control api lock poisoned")?;
+      cfg.token = control_api::new_token();
+      cfg.save_file(&state.path).map_err(|e| e.to_string())?;
+    }
+    control_api_apply(&app, &state)?;
+    control_api_info(&state)
+  }
+
+  #[derive(Clone, serde::Deserialize, Debug)]
+  #[serde(rename_all = "camelCase")]
+  struct MassPermConfigArgs {
+    category_number: Option<i32>,
+    interval_ms: Option<u64>,
+    play_first: Option<bool>,
+  }
+
+  fn massperm_configure_runner(runner: &mut massperm::Runner, args: MassPermConfigArgs) -> Result<(), String> {
+    if args.category_number.is_some_and(|n| !(0..=999).contains(&n)) {
       return Err("invalid categoryNumber".into());
     }
+    runner.configure(args.category_number, args.interval_ms, args.play_first);
+    Ok(())
+  }
+
+  // Nova lista (zera o progresso e para se estiver rodando).
+  #[tauri::command]
+  fn massperm_load(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, MassPermState>,
+    mapcodes: Vec<String>,
+    config: MassPermConfigArgs,
+  ) -> Result<massperm::Snapshot, String> {
+    let list = mapcode::uniq_preserve_order(mapcodes.iter().filter_map(|m| mapcode::normalize_mapcode(m)).collect());
+    let mut r = state.runner.lock().map_err(|_| "mass perm lock poisoned")?;
+    massperm_configure_runner(&mut r, config)?;
+    state.generation.fetch_add(1, Ordering::SeqCst);
+    r.load(list);
+    Ok(massperm_emit(&app, &r))
+  }
+
+  // Categoria/intervalo/`/np` antes; vale no próximo envio, mesmo rodando.
+  #[tauri::command]
+  fn massperm_configure(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, MassPermState>,
+    config: MassPermConfigArgs,
+  ) -> Result<massperm::Snapshot, String> {
+    let mut r = state.runner.lock().map_err(|_| "mass perm lock poisoned")?;
+    massperm_configure_runner(&mut r, config)?;
+    Ok(massperm_emit(&app, &r))
+  }
 
-    let mc = mc.strip_prefix('@').unwrap_or(mc);
-    let cmd = build_perm_command(args.category_number, mc);
-    type_in_active_window_and_enter(&cmd)?;
-    Ok(cmd)
+  // Bloqueia enquanto digita na janela: roda fora da thread principal.
+  #[tauri::command]
+  async fn massperm_action(app: tauri::AppHandle, action: massperm::Action) -> Result<massperm::Snapshot, String> {
+    tauri::async_runtime::spawn_blocking(move || massperm_apply(&app, action))
+      .await
+      .map_err(|e| e.to_string())?
+  }
+
+  #[tauri::command]
+  fn massperm_seek(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, MassPermState>,
+    index: usize,
+  ) -> Result<massperm::Snapshot, String> {
+    let mut r = state.runner.lock().map_err(|_| "mass perm lock poisoned")?;
+    if let Err(e) = r.seek(index) {
+      r.set_message(e);
+    }
+    Ok(massperm_emit(&app, &r))
+  }
+
+  #[tauri::command]
+  fn massperm_clear(app: tauri::AppHandle, state: tauri::State<'_, MassPermState>) -> Result<massperm::Snapshot, String> {
+    let mut r = state.runner.lock().map_err(|_| "mass perm lock poisoned")?;
+    state.generation.fetch_add(1, Ordering::SeqCst);
+    r.clear();
+    Ok(massperm_emit(&app, &r))
+  }
+
+  // Inclui progresso retomado do disco (volta sempre pausado).
+  #[tauri::command]
+  fn massperm_state(state: tauri::State<'_, MassPermState>) -> Result<massperm::Snapshot, String> {
+    Ok(state.runner.lock().map_err(|_| "mass perm lock poisoned")?.snapshot())
   }
 
   #[derive(Clone, serde::Deserialize, Debug)]
@@ -1163,29 +2943,25 @@ pub fn run() {
   }
 
   #[tauri::command]
-  fn send_custom_to_active_window(args: SendCustomArgs) -> Result<String, String> {
-    let mc = args.mapcode.trim();
-    if mc.is_empty() {
-      return Err("empty mapcode".into());
-    }
-    let mc = mc.strip_prefix('@').unwrap_or(mc);
-
-    let prefix = args.prefix.trim();
-    if prefix.is_empty() {
-      return Err("empty prefix".into());
-    }
-
-    let suffix = args.suffix.unwrap_or_default();
-    let suffix = suffix.trim();
-
-    let cmd = if suffix.is_empty() {
-      format!("{prefix} @{mc}")
-    } else {
-      format!("{prefix} @{mc} {suffix}")
-    };
+  fn send_custom_to_active_window(
+    injector: tauri::State<'_, inject::Injector>,
+    args: SendCustomArgs,
+  ) -> Result<String, String> {
+    game_command::send_custom(
+      &injector,
+      target_window::ensure_allowed,
+      &args.prefix,
+      &args.mapcode,
+      args.suffix.as_deref(),
+    )
+  }
 
-    type_in_active_window_and_enter(&cmd)?;
-    Ok(cmd)
+  #[tauri::command]
+  fn set_keyboard_layout(reg: tauri::State<'_, HotkeyRegistry>, layout: String) -> Result<String, String> {
+    // vale para os próximos register_*; o frontend re-registra as hotkeys em seguida
+    let parsed = layout.parse::<shortcut::KeyboardLayout>()?;
+    *reg.layout.lock().map_err(|_| "hotkey lock poisoned")? = parsed;
+    Ok(parsed.to_string())
   }
 
   #[tauri::command]
@@ -1203,15 +2979,12 @@ pub fn run() {
     let next_accel = args.next_map.as_deref().unwrap_or("PageDown").trim();
     let replay_accel = args.replay_current.as_deref().unwrap_or("Insert").trim();
 
-    let prev_map = normalize_shortcut_string(prev_accel)
-      .parse::<tauri_plugin_global_shortcut::Shortcut>()
-      .map_err(|_| format!("invalid hotkey (prevMap): {prev_accel}"))?;
-    let next_map = normalize_shortcut_string(next_accel)
-      .parse::<tauri_plugin_global_shortcut::Shortcut>()
-      .map_err(|_| format!("invalid hotkey (nextMap): {next_accel}"))?;
-    let replay_current = normalize_shortcut_string(replay_accel)
-      .parse::<tauri_plugin_global_shortcut::Shortcut>()
-      .map_err(|_| format!("invalid hotkey (replayCurrent): {replay_accel}"))?;
+    let (prev_map, prev_display) = parse_shortcut(&reg, prev_accel)
+      .map_err(|e| format!("invalid hotkey (prevMap): {prev_accel} ({e})"))?;
+    let (next_map, next_display) = parse_shortcut(&reg, next_accel)
+      .map_err(|e| format!("invalid hotkey (nextMap): {next_accel} ({e})"))?;
+    let (replay_current, replay_display) = parse_shortcut(&reg, replay_accel)
+      .map_err(|e| format!("invalid hotkey (replayCurrent): {replay_accel} ({e})"))?;
 
     *reg.prev_map.lock().map_err(|_| "hotkey lock poisoned")? = Some(prev_map.clone());
     *reg.next_map.lock().map_err(|_| "hotkey lock poisoned")? = Some(next_map.clone());
@@ -1248,7 +3021,12 @@ pub fn run() {
         "enabled": reg.enabled.load(Ordering::SeqCst),
         "prevMap": prev_accel,
         "nextMap": next_accel,
-        "replayCurrent": replay_accel
+        "replayCurrent": replay_accel,
+        "display": {
+          "prevMap": prev_display,
+          "nextMap": next_display,
+          "replayCurrent": replay_display
+        }
       }),
     );
 
@@ -1273,35 +3051,26 @@ pub fn run() {
       return Ok(());
     }
 
+    const SNIPPET_MAX_CHARS: usize = 200;
+
     let app = app.clone();
     let handle = thread::spawn(move || {
-      let mut last: Option<String> = None;
-
-      loop {
-        // stop condition
-        if !app.state::<ClipboardWatcher>().running.load(Ordering::SeqCst) {
-          break;
-        }
-
-        let next = (|| -> Option<String> {
-          let mut clipboard = arboard::Clipboard::new().ok()?;
-          let txt = clipboard.get_text().ok()?;
-          let trimmed = txt.trim();
-          if trimmed.is_empty() {
-            return None;
-          }
-          Some(trimmed.to_string())
-        })();
-
-        if let Some(text) = next {
-          if last.as_deref() != Some(text.as_str()) {
-            last = Some(text.clone());
-            let _ = app.emit("clipboard_changed", text);
-          }
+      let watcher = app.state::<ClipboardWatcher>();
+      clipboard_watch::watch(&watcher.running, |text, source| {
+        let mapcodes = mapcode::uniq_preserve_order(mapcode::parse_mapcodes_from_text(text));
+        if mapcodes.is_empty() {
+          return;
         }
-
-        thread::sleep(Duration::from_millis(600));
-      }
+        let snippet: String = text.chars().take(SNIPPET_MAX_CHARS).collect();
+        let _ = app.emit(
+          "mapcodes_captured",
+          MapcodesCaptured {
+            mapcodes,
+            snippet,
+            source: source.as_str(),
+          },
+        );
+      });
     });
 
     *watcher.handle.lock().map_err(|_| "watcher lock poisoned")? = Some(handle);
@@ -1332,26 +3101,65 @@ pub fn run() {
 
           let reg = app.state::<HotkeyRegistry>();
 
-          // mass perm hotkeys (funcionam independentemente do enabled toggle da sessão)
-          if reg.mp_enabled.load(Ordering::SeqCst) {
-            let mp_play = reg.mp_play.lock().ok().and_then(|g| g.clone());
-            if mp_play.as_ref() == Some(shortcut) {
-              let _ = app.emit("hotkey_massperm_toggle", ());
-              return;
-            }
-            let mp_pause = reg.mp_pause.lock().ok().and_then(|g| g.clone());
-            if mp_pause.as_ref() == Some(shortcut) {
-              let _ = app.emit("hotkey_massperm_play_current", ());
-              return;
-            }
-            let mp_prev = reg.mp_prev.lock().ok().and_then(|g| g.clone());
-            if mp_prev.as_ref() == Some(shortcut) {
-              let _ = app.emit("hotkey_massperm_prev", ());
+          // follow-up de uma sequência armada tem prioridade sobre qualquer outra hotkey
+          if let Some((armed, binding, follow_up)) = take_chord_follow_up(&reg, shortcut) {
+            autoplay_pause_for_hotkey(app);
+            let _ = app.emit(
+              "hotkey_chord",
+              serde_json::json!({
+                "leader": binding.leader_accel,
+                "key": follow_up.accel,
+                "action": follow_up.action
+              }),
+            );
+            let app = app.clone();
+            thread::spawn(move || {
+              for sc in armed.registered {
+                unregister_shortcut(&app, &Some(sc));
+              }
+            });
+            return;
+          }
+
+          if reg.enabled.load(Ordering::SeqCst) {
+            if let Some(idx) = find_chord_leader(&reg, shortcut) {
+              let app = app.clone();
+              thread::spawn(move || {
+                let reg = app.state::<HotkeyRegistry>();
+                let _ = arm_chord(&app, &reg, idx);
+              });
               return;
             }
-            let mp_next = reg.mp_next.lock().ok().and_then(|g| g.clone());
-            if mp_next.as_ref() == Some(shortcut) {
-              let _ = app.emit("hotkey_massperm_next", ());
+          }
+
+          // mass perm hotkeys (funcionam independentemente do enabled toggle da sessão)
+          if reg.mp_enabled.load(Ordering::SeqCst) {
+            let bindings = [
+              (&reg.mp_play, massperm::Action::Toggle, "hotkey_massperm_toggle"),
+              (&reg.mp_pause, massperm::Action::PlayCurrent, "hotkey_massperm_play_current"),
+              (&reg.mp_prev, massperm::Action::Prev, "hotkey_massperm_prev"),
+              (&reg.mp_next, massperm::Action::Next, "hotkey_massperm_next"),
+            ];
+            let hit = bindings
+              .into_iter()
+              .find(|(slot, _, _)| slot.lock().ok().and_then(|g| g.clone()).as_ref() == Some(shortcut));
+            if let Some((_, action, event)) = hit {
+              // com lista carregada o runner do Rust responde direto (não depende da webview);
+              // sem lista, quem escuta é o custom command
+              let loaded = app
+                .state::<MassPermState>()
+                .runner
+                .lock()
+                .map(|r| r.is_loaded())
+                .unwrap_or(false);
+              if loaded {
+                let app = app.clone();
+                thread::spawn(move || {
+                  let _ = massperm_apply(&app, action);
+                });
+              } else {
+                let _ = app.emit(event, ());
+              }
               return;
             }
           }
@@ -1362,19 +3170,19 @@ pub fn run() {
 
           let prev = reg.prev_map.lock().ok().and_then(|g| g.clone());
           if prev.as_ref() == Some(shortcut) {
-            let _ = app.emit("hotkey_nav_play", serde_json::json!({ "delta": -1 }));
+            review_nav(app, -1);
             return;
           }
 
           let next = reg.next_map.lock().ok().and_then(|g| g.clone());
           if next.as_ref() == Some(shortcut) {
-            let _ = app.emit("hotkey_nav_play", serde_json::json!({ "delta": 1 }));
+            review_nav(app, 1);
             return;
           }
 
           let replay = reg.replay_current.lock().ok().and_then(|g| g.clone());
           if replay.as_ref() == Some(shortcut) {
-            let _ = app.emit("hotkey_replay_current", ());
+            review_replay(app);
             return;
           }
         })
@@ -1382,8 +3190,66 @@ pub fn run() {
     )
     .plugin(tauri_plugin_dialog::init())
     .plugin(tauri_plugin_process::init())
+    .plugin(tauri_plugin_notification::init())
     .plugin(tauri_plugin_updater::Builder::new().build())
     .setup(|app| {
+      let cache_dir = app.path().app_cache_dir()?.join("mapinfo");
+      app.manage(map_cache::MapInfoCache::new(cache_dir, map_cache::DEFAULT_TTL));
+      let preview_dir = app.path().app_cache_dir()?.join("previews");
+      app.manage(map_preview::PreviewCache::new(preview_dir, map_preview::DEFAULT_CACHE_BYTES));
+      let fingerprints = app.path().app_data_dir()?.join("fingerprints.json");
+      app.manage(map_fingerprint::FingerprintStore::load(fingerprints));
+      let massperm_path = app.path().app_data_dir()?.join("massperm.json");
+      app.manage(MassPermState {
+        runner: Mutex::new(massperm::Runner::load_file(&massperm_path)),
+        path: massperm_path,
+        generation: AtomicU64::new(0),
+        send_lock: Mutex::new(()),
+      });
+      let target_windows = app.path().app_data_dir()?.join("target_windows.json");
+      let target_config = target_window::load_file(&target_windows);
+      target_window::set_allow_list(target_config.allow_list);
+      target_window::set_strict(target_config.strict);
+      let autoplay_path = app.path().app_data_dir()?.join("autoplay.json");
+      app.manage(AutoplayState {
+        timer: Mutex::new(autoplay::Timer::default()),
+        intervals: Mutex::new(autoplay::Intervals::load_file(&autoplay_path)),
+        path: autoplay_path,
+        generation: AtomicU64::new(0),
+      });
+      let provider_path = app.path().app_data_dir()?.join("map_info_provider.json");
+      let provider = load_map_info_provider(&provider_path)?;
+      app.state::<map_cache::MapInfoCache>().set_namespace(&provider.cache_namespace());
+      app.manage(MapInfoSource {
+        provider: Mutex::new(provider),
+        path: provider_path,
+      });
+      let session_watch_path = app.path().app_data_dir()?.join("session_watch.json");
+      let watch = session_watch::Watch::load_file(&session_watch_path);
+      let watching = !watch.categories.is_empty();
+      app.manage(SessionWatchState {
+        watch: Mutex::new(watch),
+        path: session_watch_path,
+        generation: AtomicU64::new(0),
+      });
+      if watching {
+        spawn_session_poller(app.handle());
+      }
+      let discord_path = app.path().app_data_dir()?.join("discord_webhook.json");
+      app.manage(DiscordWebhookState {
+        config: Mutex::new(discord::Config::load_file(&discord_path)),
+        path: discord_path,
+      });
+      let control_api_path = app.path().app_data_dir()?.join("control_api.json");
+      app.manage(ControlApiState {
+        config: Mutex::new(control_api::Config::load_file(&control_api_path)),
+        path: control_api_path,
+        server: Mutex::new(None),
+      });
+      if let Err(e) = control_api_apply(app.handle(), &app.state::<ControlApiState>()) {
+        log::warn!("{e}");
+      }
+
       if cfg!(debug_assertions) {
         app.handle().plugin(
           tauri_plugin_log::Builder::default()
@@ -1395,23 +3261,64 @@ pub fn run() {
     })
     .manage(ClipboardWatcher::default())
     .manage(NpContext::default())
+    .manage(inject::Injector::system())
     .manage(HotkeyRegistry::default())
     .invoke_handler(tauri::generate_handler![
       read_clipboard_text,
       write_clipboard_text,
       read_text_file,
+      import_submissions,
       fetch_map_info,
+      map_info_cache_stats,
+      clear_map_info_cache,
+      get_map_info_provider,
+      set_map_info_provider,
+      parse_mapcodes,
+      parse_map_xml,
+      diff_map_xml,
+      render_map_preview,
+      lint_maps,
+      record_reviewed_maps,
+      find_resubmissions,
       set_np_context,
       send_np_to_active_window,
       send_perm_to_active_window,
       send_custom_to_active_window,
+      get_target_window_config,
+      set_target_window_allow_list,
+      set_target_window_strict,
+      massperm_load,
+      massperm_configure,
+      massperm_action,
+      massperm_seek,
+      massperm_clear,
+      massperm_state,
+      autoplay_control,
+      autoplay_set_queue,
+      autoplay_state,
+      autoplay_intervals,
+      autoplay_set_interval,
+      get_control_api,
+      set_control_api,
+      regenerate_control_api_token,
       export_json,
       validate_auth_token,
       fetch_session_api,
+      fetch_sessions_api,
+      get_session_watch,
+      set_session_watch,
       submit_session_review_api,
+      get_discord_webhook,
+      set_discord_webhook,
+      test_discord_webhook,
+      list_categories,
+      validate_review_payload,
+      check_submission_limits,
       register_hotkeys,
       set_massperm_hotkeys_enabled_cmd,
       set_review_hotkeys_enabled_cmd,
+      register_chord_hotkeys,
+      set_keyboard_layout,
       start_clipboard_watch,
       stop_clipboard_watch
     ])
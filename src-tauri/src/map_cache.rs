@@ -0,0 +1,321 @@
+// Cache em disco dos lookups do Cypher801 (`/mapInfo`).
+//
+// Um arquivo JSON por mapa (`<id>.json`) com author/p/xml e o horário do fetch. Entradas
+// dentro do TTL são servidas direto; as vencidas ainda servem de fallback quando a rede falha.
+// Cada origem (servidor) grava numa subpasta própria, escolhida com `set_namespace`.
+
+use std::{
+  fs, io,
+  path::PathBuf,
+  sync::Mutex,
+  time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::atomic_file::write_json_atomic;
+
+pub const DEFAULT_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedMapInfo {
+  pub id: i64,
+  pub author: String,
+  pub xml: String,
+  pub p: Option<i64>,
+  /// Unix (segundos).
+  pub fetched_at: u64,
+  /// XML anterior, guardado quando um refetch trouxe conteúdo diferente (para diff).
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub previous_xml: Option<String>,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheEntrySummary {
+  pub id: i64,
+  pub author: String,
+  pub p: Option<i64>,
+  pub fetched_at: u64,
+  pub expired: bool,
+  pub bytes: u64,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStats {
+  pub dir: String,
+  pub ttl_secs: u64,
+  pub entries: usize,
+  pub expired: usize,
+  pub bytes: u64,
+  pub oldest_fetched_at: Option<u64>,
+  pub newest_fetched_at: Option<u64>,
+  /// Ordenado do fetch mais recente para o mais antigo.
+  pub items: Vec<CacheEntrySummary>,
+}
+
+/// Resultado de um lookup: o que está fresco, o que está vencido e o que falta buscar.
+#[derive(Debug, Default)]
+pub struct Lookup {
+  pub fresh: Vec<CachedMapInfo>,
+  pub stale: Vec<CachedMapInfo>,
+  pub missing: Vec<i64>,
+}
+
+impl Lookup {
+  /// Ids que precisam ir para a rede (vencidos + ausentes), na ordem do pedido.
+  pub fn to_fetch(&self) -> Vec<i64> {
+    self.stale.iter().map(|e| e.id).chain(self.missing.iter().copied()).collect()
+  }
+}
+
+pub fn now_secs() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_secs())
+    .unwrap_or(0)
+}
+
+pub struct MapInfoCache {
+  root: PathBuf,
+  // `root` ou `root/<namespace>`
+  dir: Mutex<PathBuf>,
+  ttl: Duration,
+}
+
+impl MapInfoCache {
+  pub fn new(root: PathBuf, ttl: Duration) -> Self {
+    Self {
+      dir: Mutex::new(root.clone()),
+      root,
+      ttl,
+    }
+  }
+
+  /// Troca a subpasta em uso (a origem do mapInfo mudou).
+  pub fn set_namespace(&self, namespace: &str) {
+    let mut dir = self.dir.lock().unwrap_or_else(|p| p.into_inner());
+    *dir = self.root.join(namespace);
+  }
+
+  pub fn dir(&self) -> PathBuf {
+    self.dir.lock().unwrap_or_else(|p| p.into_inner()).clone()
+  }
+
+  fn entry_path(&self, id: i64) -> PathBuf {
+    self.dir().join(format!("{id}.json"))
+  }
+
+  fn is_expired(&self, entry: &CachedMapInfo, now: u64) -> bool {
+    now.saturating_sub(entry.fetched_at) >= self.ttl.as_secs()
+  }
+
+  pub fn get(&self, id: i64) -> Option<CachedMapInfo> {
+    let raw = fs::read(self.entry_path(id)).ok()?;
+    let entry: CachedMapInfo = serde_json::from_slice(&raw).ok()?;
+    // arquivo renomeado/corrompido não conta
+    (entry.id == id).then_some(entry)
+  }
+
+  pub fn lookup(&self, ids: &[i64], now: u64) -> Lookup {
+    let mut out = Lookup::default();
+    for &id in ids {
+      match self.get(id) {
+        Some(entry) if !self.is_expired(&entry, now) => out.fresh.push(entry),
+        Some(entry) => out.stale.push(entry),
+        None => out.missing.push(id),
+      }
+    }
+    out
+  }
+
+  /// Grava a entrada; se o XML mudou em relação ao que estava salvo, o antigo vai para
+  /// `previous_xml` (senão o `previous_xml` existente é mantido).
+  pub fn put(&self, entry: &CachedMapInfo) -> io::Result<()> {
+    let mut entry = entry.clone();
+    if let Some(old) = self.get(entry.id) {
+      entry.previous_xml = if old.xml != entry.xml { Some(old.xml) } else { old.previous_xml };
+    }
+    write_json_atomic(&self.entry_path(entry.id), &entry)
+  }
+
+  fn entries(&self) -> Vec<(CachedMapInfo, u64)> {
+    let Ok(dir) = fs::read_dir(self.dir()) else {
+      return vec![];
+    };
+    dir
+      .flatten()
+      .filter_map(|de| {
+        let name = de.file_name();
+        let id: i64 = name.to_str()?.strip_suffix(".json")?.parse().ok()?;
+        let bytes = de.metadata().map(|m| m.len()).unwrap_or(0);
+        Some((self.get(id)?, bytes))
+      })
+      .collect()
+  }
+
+  pub fn stats(&self, now: u64) -> CacheStats {
+    let mut items: Vec<CacheEntrySummary> = self
+      .entries()
+      .into_iter()
+      .map(|(e, bytes)| CacheEntrySummary {
+        expired: self.is_expired(&e, now),
+        id: e.id,
+        author: e.author,
+        p: e.p,
+        fetched_at: e.fetched_at,
+        bytes,
+      })
+      .collect();
+    items.sort_by(|a, b| b.fetched_at.cmp(&a.fetched_at).then(a.id.cmp(&b.id)));
+
+    CacheStats {
+      dir: self.dir().display().to_string(),
+      ttl_secs: self.ttl.as_secs(),
+      entries: items.len(),
+      expired: items.iter().filter(|i| i.expired).count(),
+      bytes: items.iter().map(|i| i.bytes).sum(),
+      oldest_fetched_at: items.iter().map(|i| i.fetched_at).min(),
+      newest_fetched_at: items.iter().map(|i| i.fetched_at).max(),
+      items,
+    }
+  }
+
+  /// Remove tudo (ou só o que venceu). Retorna quantas entradas saíram.
+  pub fn clear(&self, expired_only: bool, now: u64) -> io::Result<usize> {
+    let Ok(dir) = fs::read_dir(self.dir()) else {
+      return Ok(0);
+    };
+    let mut removed = 0;
+    for de in dir.flatten() {
+      let path = de.path();
+      let Some(name) = path.file_name().and_then(|n| n.to_str()) else { continue };
+      let Some(id) = name.strip_suffix(".json").and_then(|s| s.parse::<i64>().ok()) else {
+        // sobra de escrita interrompida
+        if name.ends_with(".json.tmp") {
+          let _ = fs::remove_file(&path);
+        }
+        continue;
+      };
+      // com expired_only, arquivo ilegível também sai
+      if expired_only {
+        if let Some(entry) = self.get(id) {
+          if !self.is_expired(&entry, now) {
+            continue;
+          }
+        }
+      }
+      fs::remove_file(&path)?;
+      removed += 1;
+    }
+    Ok(removed)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // o diretório é apagado quando o `TempDir` sai de escopo (mesmo com assert falhando)
+  fn temp_cache(ttl: Duration) -> (tempfile::TempDir, MapInfoCache) {
+    let dir = tempfile::tempdir().unwrap();
+    let cache = MapInfoCache::new(dir.path().to_path_buf(), ttl);
+    (dir, cache)
+  }
+
+  fn entry(id: i64, fetched_at: u64) -> CachedMapInfo {
+    CachedMapInfo {
+      id,
+      author: format!("Author{id}"),
+      xml: format!("<C><P /><Z><S /></Z><!-- {id} --></C>"),
+      p: Some(17),
+      fetched_at,
+      previous_xml: None,
+    }
+  }
+
+  #[test]
+  fn lookup_splits_fresh_stale_missing() {
+    let (_dir, cache) = temp_cache(Duration::from_secs(100));
+    cache.put(&entry(1, 1_000)).unwrap();
+    cache.put(&entry(2, 950)).unwrap();
+
+    let got = cache.lookup(&[1, 2, 3], 1_060);
+    assert_eq!(got.fresh, vec![entry(1, 1_000)]);
+    assert_eq!(got.stale, vec![entry(2, 950)]);
+    assert_eq!(got.missing, vec![3]);
+    assert_eq!(got.to_fetch(), vec![2, 3]);
+
+    // refetch atualiza o timestamp
+    cache.put(&entry(2, 1_060)).unwrap();
+    assert_eq!(cache.lookup(&[2], 1_060).fresh.len(), 1);
+  }
+
+  #[test]
+  fn namespaces_do_not_share_entries() {
+    let (_dir, cache) = temp_cache(Duration::from_secs(100));
+    let root = cache.dir();
+    cache.set_namespace("cypher801-cypher801.app");
+    cache.put(&entry(1, 1_000)).unwrap();
+    assert!(cache.get(1).is_some());
+
+    // outro servidor não enxerga a entrada do primeiro
+    cache.set_namespace("cypher801-127.0.0.1_8080");
+    assert!(cache.get(1).is_none());
+    assert_eq!(cache.stats(1_000).entries, 0);
+
+    cache.set_namespace("cypher801-cypher801.app");
+    assert_eq!(cache.dir(), root.join("cypher801-cypher801.app"));
+    assert_eq!(cache.lookup(&[1], 1_000).fresh.len(), 1);
+  }
+
+  #[test]
+  fn refetch_keeps_previous_xml() {
+    let (_dir, cache) = temp_cache(Duration::from_secs(100));
+    let v1 = entry(5, 100);
+    cache.put(&v1).unwrap();
+    assert_eq!(cache.get(5).unwrap().previous_xml, None);
+
+    let v2 = CachedMapInfo {
+      xml: "<C><P L=\"1600\" /></C>".into(),
+      ..entry(5, 200)
+    };
+    cache.put(&v2).unwrap();
+    assert_eq!(cache.get(5).unwrap().previous_xml.as_deref(), Some(v1.xml.as_str()));
+
+    // mesmo XML de novo: continua apontando para a versão anterior
+    cache.put(&CachedMapInfo { fetched_at: 300, ..v2.clone() }).unwrap();
+    let got = cache.get(5).unwrap();
+    assert_eq!((got.fetched_at, got.previous_xml), (300, Some(v1.xml)));
+  }
+
+  #[test]
+  fn stats_and_clear() {
+    let (_dir, cache) = temp_cache(Duration::from_secs(100));
+    assert_eq!(cache.stats(0).entries, 0);
+    assert_eq!(cache.clear(false, 0).unwrap(), 0);
+
+    cache.put(&entry(10, 500)).unwrap();
+    cache.put(&entry(11, 900)).unwrap();
+    fs::write(cache.dir().join("junk.txt"), "x").unwrap();
+    // id no arquivo não bate com o nome: ignorado
+    fs::write(cache.dir().join("12.json"), serde_json::to_vec(&entry(13, 900)).unwrap()).unwrap();
+
+    let stats = cache.stats(950);
+    assert_eq!(stats.entries, 2);
+    assert_eq!(stats.expired, 1);
+    assert_eq!(stats.oldest_fetched_at, Some(500));
+    assert_eq!(stats.newest_fetched_at, Some(900));
+    assert_eq!(stats.items.iter().map(|i| i.id).collect::<Vec<_>>(), vec![11, 10]);
+    assert!(stats.bytes > 0);
+
+    // vencido + ilegível
+    assert_eq!(cache.clear(true, 950).unwrap(), 2);
+    assert!(cache.get(10).is_none());
+    assert!(cache.get(11).is_some());
+
+    assert_eq!(cache.clear(false, 950).unwrap(), 1);
+    assert_eq!(cache.stats(950).entries, 0);
+    assert!(cache.dir().join("junk.txt").exists());
+  }
+}
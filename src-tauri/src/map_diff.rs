@@ -0,0 +1,489 @@
+// Diff estrutural entre duas versões do mesmo mapa (`map_xml::MapXml`).
+//
+// O XML não tem ids por elemento, então o pareamento é por proximidade: primeiro os elementos
+// idênticos, depois os do mesmo grupo (tipo de decoração, código do objeto, ...) mais próximos
+// dentro de `MATCH_RADIUS`. O que sobra vira adicionado/removido.
+
+use std::collections::BTreeMap;
+
+use crate::map_xml::{JointKind, MapXml, PointKind};
+
+/// Distância máxima (px) para considerar que um elemento "se moveu" em vez de sumir e aparecer outro.
+const MATCH_RADIUS: f32 = 150.0;
+/// Peso de cada campo diferente no custo do pareamento (em px).
+const FIELD_COST: f32 = 25.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeKind {
+  Added,
+  Removed,
+  Changed,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldChange {
+  pub field: &'static str,
+  pub before: Option<String>,
+  pub after: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElementChange {
+  /// "ground", "mouseSpawn", "shamanSpawn", "hole", "cheese", "decoration", "shamanObject", "joint".
+  pub element: &'static str,
+  pub change: ChangeKind,
+  /// Ex.: "lava ground", "decoration 12".
+  pub label: String,
+  /// Posições nas listas do modelo (`grounds`, `points`, ...).
+  pub before_index: Option<usize>,
+  pub after_index: Option<usize>,
+  pub x: f32,
+  pub y: f32,
+  /// Só em `Changed` (inclui "position" quando moveu).
+  pub fields: Vec<FieldChange>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MapDiff {
+  pub properties: Vec<FieldChange>,
+  pub elements: Vec<ElementChange>,
+  /// Uma linha de resumo seguida de uma frase por mudança.
+  pub summary: Vec<String>,
+}
+
+impl MapDiff {
+  pub fn is_empty(&self) -> bool {
+    self.properties.is_empty() && self.elements.is_empty()
+  }
+}
+
+/// Elemento reduzido ao que importa para comparar.
+struct Item {
+  element: &'static str,
+  /// Só pareia dentro do mesmo grupo.
+  group: String,
+  label: String,
+  index: usize,
+  x: f32,
+  y: f32,
+  fields: Vec<(&'static str, String)>,
+}
+
+impl Item {
+  fn new(element: &'static str, group: impl Into<String>, label: impl Into<String>, index: usize, x: f32, y: f32) -> Self {
+    Self {
+      element,
+      group: group.into(),
+      label: label.into(),
+      index,
+      x,
+      y,
+      fields: vec![],
+    }
+  }
+
+  fn field(mut self, name: &'static str, value: impl ToString) -> Self {
+    self.fields.push((name, value.to_string()));
+    self
+  }
+
+  fn same_place(&self, other: &Item) -> bool {
+    self.x == other.x && self.y == other.y
+  }
+
+  fn distance(&self, other: &Item) -> f32 {
+    (self.x - other.x).hypot(self.y - other.y)
+  }
+
+  fn field_changes(&self, after: &Item) -> Vec<FieldChange> {
+    let mut out = Vec::new();
+    if !self.same_place(after) {
+      out.push(FieldChange {
+        field: "position",
+        before: Some(pos(self.x, self.y)),
+        after: Some(pos(after.x, after.y)),
+      });
+    }
+    for ((name, b), (_, a)) in self.fields.iter().zip(&after.fields) {
+      if a != b {
+        out.push(FieldChange {
+          field: name,
+          before: Some(b.clone()),
+          after: Some(a.clone()),
+        });
+      }
+    }
+    out
+  }
+}
+
+fn pos(x: f32, y: f32) -> String {
+  format!("({x}, {y})")
+}
+
+fn opt(v: Option<impl ToString>) -> String {
+  v.map(|v| v.to_string()).unwrap_or_else(|| "-".into())
+}
+
+fn point_element(kind: PointKind) -> (&'static str, &'static str) {
+  match kind {
+    PointKind::MouseSpawn => ("mouseSpawn", "mouse spawn"),
+    PointKind::ShamanSpawn => ("shamanSpawn", "shaman spawn"),
+    PointKind::Hole => ("hole", "hole"),
+    PointKind::Cheese => ("cheese", "cheese"),
+  }
+}
+
+fn joint_name(kind: JointKind) -> &'static str {
+  match kind {
+    JointKind::Distance => "distance",
+    JointKind::Revolute => "revolute",
+    JointKind::Prismatic => "prismatic",
+    JointKind::Pulley => "pulley",
+  }
+}
+
+fn items(map: &MapXml) -> Vec<Item> {
+  let mut out = Vec::new();
+
+  // tipo do ground é campo (não grupo): trocar madeira por lava no mesmo lugar é "changed"
+  for g in &map.grounds {
+    out.push(
+      Item::new("ground", "ground", format!("{} ground", g.ground_type.name()), g.index, g.x, g.y)
+        .field("type", g.ground_type.name())
+        .field("size", format!("{}x{}", g.width, g.height))
+        .field("angle", g.angle)
+        .field("dynamic", g.dynamic)
+        .field("mass", g.mass)
+        .field("friction", g.friction)
+        .field("restitution", g.restitution)
+        .field("color", opt(g.color.as_deref()))
+        .field("collision", g.collision)
+        .field("foreground", g.foreground)
+        .field("vanish", opt(g.vanish_ms)),
+    );
+  }
+
+  for (i, p) in map.points.iter().enumerate() {
+    let (element, label) = point_element(p.kind);
+    out.push(Item::new(element, element, label, i, p.x, p.y));
+  }
+
+  for (i, d) in map.decorations.iter().enumerate() {
+    let id = d.decoration_id;
+    out.push(
+      Item::new("decoration", id.to_string(), format!("decoration {id}"), i, d.x, d.y)
+        .field("flipped", d.flipped)
+        .field("colors", opt(d.colors.as_deref())),
+    );
+  }
+
+  for (i, o) in map.shaman_objects.iter().enumerate() {
+    let code = o.code;
+    out.push(
+      Item::new("shamanObject", code.to_string(), format!("shaman object {code}"), i, o.x, o.y)
+        .field("angle", o.angle)
+        .field("ghost", o.ghost),
+    );
+  }
+
+  for (i, j) in map.joints.iter().enumerate() {
+    let name = joint_name(j.kind);
+    let (x, y) = j.point1.unwrap_or((0.0, 0.0));
+    out.push(
+      Item::new("joint", name, format!("{name} joint"), i, x, y)
+        .field("end", opt(j.point2.map(|(x, y)| pos(x, y))))
+        .field("grounds", format!("{}-{}", opt(j.ground1), opt(j.ground2)))
+        .field("color", opt(j.color.as_deref()))
+        .field("line width", opt(j.line_width)),
+    );
+  }
+
+  out
+}
+
+fn property_changes(before: &MapXml, after: &MapXml) -> Vec<FieldChange> {
+  let describe = |m: &MapXml| -> BTreeMap<&'static str, String> {
+    let p = &m.properties;
+    BTreeMap::from([
+      ("width", p.width.to_string()),
+      ("height", p.height.to_string()),
+      ("wind", p.wind.to_string()),
+      ("gravity", p.gravity.to_string()),
+      ("background", opt(p.background)),
+      ("night", p.night.to_string()),
+      ("collision", p.collision.to_string()),
+      ("soulmate", p.soulmate.to_string()),
+      ("defilante", p.defilante.to_string()),
+      ("shaman object multiplier", opt(p.shaman_object_multiplier)),
+    ])
+  };
+  let (b, a) = (describe(before), describe(after));
+  let mut out: Vec<FieldChange> = b
+    .iter()
+    .filter(|(k, v)| a.get(*k) != Some(*v))
+    .map(|(k, v)| FieldChange {
+      field: k,
+      before: Some(v.clone()),
+      after: a.get(k).cloned(),
+    })
+    .collect();
+
+  // atributos de <P> que o modelo não interpreta: compara o texto cru
+  const MODELED: &[&str] = &["L", "H", "G", "F", "N", "C", "A", "defilante", "mgoc"];
+  let (ba, aa) = (&before.properties.attributes, &after.properties.attributes);
+  let keys: std::collections::BTreeSet<&String> = ba.keys().chain(aa.keys()).collect();
+  for k in keys {
+    if MODELED.contains(&k.as_str()) || ba.get(k) == aa.get(k) {
+      continue;
+    }
+    // nome do atributo é dinâmico; `field` é &'static, então vai no texto
+    out.push(FieldChange {
+      field: "attribute",
+      before: ba.get(k).map(|v| format!("{k}={v}")),
+      after: aa.get(k).map(|v| format!("{k}={v}")),
+    });
+  }
+  out
+}
+
+fn pair_items(before: Vec<Item>, after: Vec<Item>) -> Vec<ElementChange> {
+  let mut b_used = vec![false; before.len()];
+  let mut a_used = vec![false; after.len()];
+  let mut changes = Vec::new();
+
+  // 1) idênticos
+  for (bi, b) in before.iter().enumerate() {
+    let found = after
+      .iter()
+      .enumerate()
+      .find(|(ai, a)| !a_used[*ai] && a.element == b.element && a.group == b.group && a.same_place(b) && a.fields == b.fields);
+    if let Some((ai, _)) = found {
+      b_used[bi] = true;
+      a_used[ai] = true;
+    }
+  }
+
+  // 2) mais próximos do mesmo grupo, guloso pelo menor custo
+  let mut candidates: Vec<(f32, usize, usize)> = Vec::new();
+  for (bi, b) in before.iter().enumerate().filter(|(i, _)| !b_used[*i]) {
+    for (ai, a) in after.iter().enumerate().filter(|(i, _)| !a_used[*i]) {
+      if a.element != b.element || a.group != b.group {
+        continue;
+      }
+      let dist = b.distance(a);
+      if dist <= MATCH_RADIUS {
+        let differing = b.fields.iter().zip(&a.fields).filter(|(x, y)| x != y).count();
+        candidates.push((dist + differing as f32 * FIELD_COST, bi, ai));
+      }
+    }
+  }
+  candidates.sort_by(|x, y| x.0.total_cmp(&y.0).then(x.1.cmp(&y.1)).then(x.2.cmp(&y.2)));
+  let mut paired = Vec::new();
+  for (_, bi, ai) in candidates {
+    if b_used[bi] || a_used[ai] {
+      continue;
+    }
+    b_used[bi] = true;
+    a_used[ai] = true;
+    paired.push((bi, ai));
+  }
+  // na ordem do mapa antigo, não na do pareamento
+  paired.sort_unstable();
+  for (bi, ai) in paired {
+    let (b, a) = (&before[bi], &after[ai]);
+    changes.push(ElementChange {
+      element: a.element,
+      change: ChangeKind::Changed,
+      label: b.label.clone(),
+      before_index: Some(b.index),
+      after_index: Some(a.index),
+      x: b.x,
+      y: b.y,
+      fields: b.field_changes(a),
+    });
+  }
+
+  // 3) o resto
+  let leftover = |items: &[Item], used: &[bool], change: ChangeKind| -> Vec<ElementChange> {
+    items
+      .iter()
+      .zip(used)
+      .filter(|(_, u)| !**u)
+      .map(|(it, _)| ElementChange {
+        element: it.element,
+        change,
+        label: it.label.clone(),
+        before_index: (change == ChangeKind::Removed).then_some(it.index),
+        after_index: (change == ChangeKind::Added).then_some(it.index),
+        x: it.x,
+        y: it.y,
+        fields: vec![],
+      })
+      .collect()
+  };
+  changes.extend(leftover(&before, &b_used, ChangeKind::Removed));
+  changes.extend(leftover(&after, &a_used, ChangeKind::Added));
+  changes
+}
+
+fn describe_field(f: &FieldChange) -> String {
+  let (b, a) = (f.before.as_deref().unwrap_or("-"), f.after.as_deref().unwrap_or("-"));
+  match f.field {
+    "position" => format!("moved to {a}"),
+    "attribute" => describe_attribute(f.before.as_deref(), f.after.as_deref()),
+    name => format!("{name} {b} → {a}"),
+  }
+}
+
+// `before`/`after` de atributo vêm como "nome=valor"
+fn describe_attribute(before: Option<&str>, after: Option<&str>) -> String {
+  fn split(kv: Option<&str>) -> Option<(&str, &str)> {
+    kv.map(|kv| kv.split_once('=').unwrap_or((kv, "")))
+  }
+  match (split(before), split(after)) {
+    (None, Some((k, v))) => format!("{k} added (\"{v}\")"),
+    (Some((k, v)), None) => format!("{k} removed (\"{v}\")"),
+    (Some((k, b)), Some((_, a))) => format!("{k} changed {b} → {a}"),
+    (None, None) => "unchanged".into(),
+  }
+}
+
+fn summarize(diff: &MapDiff) -> Vec<String> {
+  if diff.is_empty() {
+    return vec!["no changes".into()];
+  }
+  let count = |k: ChangeKind| diff.elements.iter().filter(|e| e.change == k).count();
+  let (added, removed, changed) = (count(ChangeKind::Added), count(ChangeKind::Removed), count(ChangeKind::Changed));
+  let mut lines = vec![format!(
+    "{} property change(s); {} element(s) added, {} removed, {} changed",
+    diff.properties.len(),
+    added,
+    removed,
+    changed
+  )];
+
+  for p in &diff.properties {
+    lines.push(match p.field {
+      "attribute" => format!("map attribute {}", describe_field(p)),
+      _ => format!("map {}", describe_field(p)),
+    });
+  }
+  for e in &diff.elements {
+    let at = pos(e.x, e.y);
+    lines.push(match e.change {
+      ChangeKind::Added => format!("added {} at {at}", e.label),
+      ChangeKind::Removed => format!("removed {} at {at}", e.label),
+      ChangeKind::Changed => {
+        let fields: Vec<String> = e.fields.iter().map(describe_field).collect();
+        format!("{} at {at}: {}", e.label, fields.join(", "))
+      }
+    });
+  }
+  lines
+}
+
+pub fn diff(before: &MapXml, after: &MapXml) -> MapDiff {
+  let mut diff = MapDiff {
+    properties: property_changes(before, after),
+    elements: pair_items(items(before), items(after)),
+    summary: vec![],
+  };
+  diff.summary = summarize(&diff);
+  diff
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::map_xml::parse_map_xml;
+
+  const BEFORE: &str = r#"<C><P L="800" /><Z><S>
+    <S T="0" X="400" Y="390" L="800" H="20" />
+    <S T="1" X="200" Y="300" L="100" H="10" />
+    <S T="6" X="600" Y="250" L="120" H="10" />
+    <S T="10" X="50" Y="50" L="20" H="20" />
+    </S><D><DS X="50" Y="360" /><T X="750" Y="370" /><F X="400" Y="100" /><P T="12" X="300" Y="380" /></D>
+    <O><O C="1" X="500" Y="200" P="0,0" /></O></Z></C>"#;
+
+  fn diff_of(before: &str, after: &str) -> MapDiff {
+    diff(&parse_map_xml(before).unwrap(), &parse_map_xml(after).unwrap())
+  }
+
+  #[test]
+  fn reordering_is_not_a_change() {
+    let shuffled = r#"<C><P L="800"/><Z><O><O C="1" X="500" Y="200" P="0,0"/></O><D><P T="12" X="300" Y="380"/><F X="400" Y="100"/><T X="750" Y="370"/><DS X="50" Y="360"/></D><S><S T="10" X="50" Y="50" L="20" H="20"/><S T="6" X="600" Y="250" L="120" H="10"/><S T="0" X="400" Y="390" L="800" H="20"/><S T="1" X="200" Y="300" L="100" H="10"/></S></Z></C>"#;
+    let d = diff_of(BEFORE, shuffled);
+    assert!(d.is_empty(), "{:?}", d.summary);
+    assert_eq!(d.summary, vec!["no changes"]);
+  }
+
+  #[test]
+  fn moved_resized_retyped_added_removed() {
+    let after = BEFORE
+      // movido
+      .replace(r#"X="200" Y="300" L="100""#, r#"X="230" Y="300" L="100""#)
+      // redimensionado
+      .replace(r#"L="120" H="10""#, r#"L="200" H="10""#)
+      // tipo trocado no mesmo lugar
+      .replace(r#"T="10" X="50""#, r#"T="3" X="50""#)
+      // toca removida, queijo novo longe do antigo
+      .replace(r#"<T X="750" Y="370" />"#, r#"<F X="100" Y="100" />"#)
+      .replace(r#"<C><P L="800" />"#, r#"<C><P L="1600" N="" />"#);
+    let d = diff_of(BEFORE, &after);
+
+    let props: Vec<_> = d.properties.iter().map(|p| (p.field, p.after.as_deref())).collect();
+    assert_eq!(props, vec![("night", Some("true")), ("width", Some("1600"))]);
+
+    let elements: Vec<_> = d.elements.iter().map(|e| (e.change, e.label.as_str())).collect();
+    assert_eq!(
+      elements,
+      vec![
+        (ChangeKind::Changed, "ice ground"),
+        (ChangeKind::Changed, "grass ground"),
+        (ChangeKind::Changed, "stone ground"),
+        (ChangeKind::Removed, "hole"),
+        (ChangeKind::Added, "cheese"),
+      ]
+    );
+
+    assert_eq!(
+      d.summary,
+      vec![
+        "2 property change(s); 1 element(s) added, 1 removed, 3 changed",
+        "map night false → true",
+        "map width 800 → 1600",
+        "ice ground at (200, 300): moved to (230, 300)",
+        "grass ground at (600, 250): size 120x10 → 200x10",
+        "stone ground at (50, 50): type stone → lava",
+        "removed hole at (750, 370)",
+        "added cheese at (100, 100)",
+      ]
+    );
+  }
+
+  #[test]
+  fn far_moves_and_unknown_attributes() {
+    let after = BEFORE
+      .replace(r#"<O C="1" X="500" Y="200" P="0,0" />"#, r#"<O C="1" X="100" Y="200" P="0,0" />"#)
+      .replace(r#"<C><P L="800" />"#, r#"<C><P L="800" Ca="" bh="1" />"#);
+    let d = diff_of(BEFORE, &after);
+    // 400 px é longe demais para ser "movido"
+    let elements: Vec<_> = d.elements.iter().map(|e| (e.change, e.element)).collect();
+    assert_eq!(
+      elements,
+      vec![(ChangeKind::Removed, "shamanObject"), (ChangeKind::Added, "shamanObject")]
+    );
+    assert_eq!(d.properties.len(), 2);
+    assert_eq!(d.summary[1], "map attribute Ca added (\"\")");
+    assert_eq!(d.summary[2], "map attribute bh added (\"1\")");
+
+    let edited = after.replace(r#"bh="1""#, r#"bh="2""#).replace(r#" Ca="""#, "");
+    let changed = diff_of(&after, &edited);
+    assert_eq!(changed.summary[1..], ["map attribute Ca removed (\"\")", "map attribute bh changed 1 → 2"]);
+  }
+}
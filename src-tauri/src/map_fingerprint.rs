@@ -0,0 +1,362 @@
+// Impressão digital de mapas para achar reenvios (mesmo mapa com outro código) e cópias
+// levemente editadas.
+//
+// - `hash`: igualdade exata do conteúdo, independente da ordem dos elementos/atributos e da
+//   formatação do XML.
+// - `features`: multiconjunto de elementos com posição quantizada (grade de `GRID` px); a
+//   similaridade é o Jaccard entre dois multiconjuntos.
+
+use std::{
+  fs, io,
+  path::PathBuf,
+  sync::Mutex,
+};
+
+use crate::{
+  atomic_file::write_json_atomic,
+  map_xml::{JointKind, MapXml, PointKind},
+};
+
+/// Tolerância de posição/tamanho para "quase igual".
+const GRID: f32 = 10.0;
+const ANGLE_STEP: f32 = 5.0;
+pub const DEFAULT_THRESHOLD: f32 = 0.85;
+const MAX_MATCHES: usize = 5;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fingerprint {
+  /// Hex de 16 dígitos.
+  pub hash: String,
+  /// Hashes dos elementos quantizados, ordenados (com repetição).
+  pub features: Vec<u64>,
+}
+
+/// FNV-1a: estável entre builds (o DefaultHasher não garante isso), então serve de chave em
+/// disco. Também usado pelo cache de previews.
+pub(crate) fn fnv1a(data: &[u8]) -> u64 {
+  data.iter().fold(0xcbf2_9ce4_8422_2325, |h, b| (h ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3))
+}
+
+fn q(v: f32) -> i64 {
+  (v / GRID).round() as i64
+}
+
+fn q_angle(a: f32) -> i64 {
+  ((a / ANGLE_STEP).round() as i64).rem_euclid((360.0 / ANGLE_STEP) as i64)
+}
+
+// uma casa decimal: o editor do jogo grava floats com ruído
+fn exact(v: f32) -> i64 {
+  (v * 10.0).round() as i64
+}
+
+fn point_tag(kind: PointKind) -> &'static str {
+  match kind {
+    PointKind::MouseSpawn => "DS",
+    PointKind::ShamanSpawn => "DC",
+    PointKind::Hole => "T",
+    PointKind::Cheese => "F",
+  }
+}
+
+fn joint_tag(kind: JointKind) -> &'static str {
+  match kind {
+    JointKind::Distance => "JD",
+    JointKind::Revolute => "JR",
+    JointKind::Prismatic => "JP",
+    JointKind::Pulley => "JPL",
+  }
+}
+
+/// Linhas canônicas (exata, quantizada) de cada elemento do mapa.
+fn canonical_elements(map: &MapXml) -> Vec<(String, String)> {
+  let mut out = Vec::new();
+  let p = &map.properties;
+  let flags = format!("{}{}{}{}", u8::from(p.night), u8::from(p.collision), u8::from(p.soulmate), u8::from(p.defilante));
+  out.push((
+    format!("P|{}|{}|{}|{}|{flags}", exact(p.width), exact(p.height), exact(p.wind), exact(p.gravity)),
+    format!("P|{}|{}|{flags}", q(p.width), q(p.height)),
+  ));
+
+  for g in &map.grounds {
+    let color = g.color.as_deref().unwrap_or("");
+    let t = g.ground_type.0;
+    let dynamic = u8::from(g.dynamic);
+    out.push((
+      format!(
+        "S|{t}|{}|{}|{}|{}|{}|{dynamic}|{}|{}|{}|{color}|{}",
+        exact(g.x),
+        exact(g.y),
+        exact(g.width),
+        exact(g.height),
+        exact(g.angle),
+        exact(g.mass),
+        exact(g.friction),
+        exact(g.restitution),
+        g.collision
+      ),
+      format!(
+        "S|{t}|{}|{}|{}|{}|{}|{dynamic}|{color}",
+        q(g.x),
+        q(g.y),
+        q(g.width),
+        q(g.height),
+        q_angle(g.angle)
+      ),
+    ));
+  }
+
+  for pt in &map.points {
+    let tag = point_tag(pt.kind);
+    out.push((
+      format!("{tag}|{}|{}", exact(pt.x), exact(pt.y)),
+      format!("{tag}|{}|{}", q(pt.x), q(pt.y)),
+    ));
+  }
+
+  for d in &map.decorations {
+    let flip = u8::from(d.flipped);
+    out.push((
+      format!("P{}|{}|{}|{flip}|{}", d.decoration_id, exact(d.x), exact(d.y), d.colors.as_deref().unwrap_or("")),
+      format!("P{}|{}|{}", d.decoration_id, q(d.x), q(d.y)),
+    ));
+  }
+
+  for o in &map.shaman_objects {
+    let ghost = u8::from(o.ghost);
+    out.push((
+      format!("O{}|{}|{}|{}|{ghost}", o.code, exact(o.x), exact(o.y), exact(o.angle)),
+      format!("O{}|{}|{}|{}", o.code, q(o.x), q(o.y), q_angle(o.angle)),
+    ));
+  }
+
+  for j in &map.joints {
+    let tag = joint_tag(j.kind);
+    let pt = |p: Option<(f32, f32)>, f: fn(f32) -> i64| p.map(|(x, y)| format!("{},{}", f(x), f(y))).unwrap_or_default();
+    // índices de ground dependem da ordem no XML: só entram no hash exato
+    let grounds = format!("{:?}-{:?}", j.ground1, j.ground2);
+    out.push((
+      format!("{tag}|{grounds}|{}|{}|{}", pt(j.point1, exact), pt(j.point2, exact), j.color.as_deref().unwrap_or("")),
+      format!("{tag}|{}|{}", pt(j.point1, q), pt(j.point2, q)),
+    ));
+  }
+
+  out
+}
+
+pub fn fingerprint(map: &MapXml) -> Fingerprint {
+  let (mut exact_lines, quantized): (Vec<String>, Vec<String>) = canonical_elements(map).into_iter().unzip();
+  exact_lines.sort();
+  let mut features: Vec<u64> = quantized.iter().map(|l| fnv1a(l.as_bytes())).collect();
+  features.sort_unstable();
+  Fingerprint {
+    hash: format!("{:016x}", fnv1a(exact_lines.join("\n").as_bytes())),
+    features,
+  }
+}
+
+/// Jaccard de multiconjuntos (`a` e `b` ordenados). 1.0 = mesmos elementos.
+pub fn similarity(a: &[u64], b: &[u64]) -> f32 {
+  if a.is_empty() && b.is_empty() {
+    return 1.0;
+  }
+  let (mut i, mut j, mut common) = (0, 0, 0usize);
+  while i < a.len() && j < b.len() {
+    match a[i].cmp(&b[j]) {
+      std::cmp::Ordering::Less => i += 1,
+      std::cmp::Ordering::Greater => j += 1,
+      std::cmp::Ordering::Equal => {
+        common += 1;
+        i += 1;
+        j += 1;
+      }
+    }
+  }
+  let union = a.len() + b.len() - common;
+  common as f32 / union as f32
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewedMap {
+  pub map_id: i64,
+  pub hash: String,
+  pub features: Vec<u64>,
+  pub category: Option<String>,
+  pub decision: Option<String>,
+  pub author: Option<String>,
+  /// Unix (segundos).
+  pub reviewed_at: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FingerprintMatch {
+  pub map_id: i64,
+  /// Mesmo conteúdo (hash igual).
+  pub exact: bool,
+  pub similarity: f32,
+  pub category: Option<String>,
+  pub decision: Option<String>,
+  pub author: Option<String>,
+  pub reviewed_at: u64,
+}
+
+/// Mapas já revisados, persistidos num JSON só (um registro por código, o review mais recente).
+pub struct FingerprintStore {
+  path: PathBuf,
+  entries: Mutex<Vec<ReviewedMap>>,
+}
+
+impl FingerprintStore {
+  pub fn load(path: PathBuf) -> Self {
+    let entries = fs::read(&path)
+      .ok()
+      .and_then(|raw| serde_json::from_slice(&raw).ok())
+      .unwrap_or_default();
+    Self {
+      path,
+      entries: Mutex::new(entries),
+    }
+  }
+
+  fn save(&self, entries: &[ReviewedMap]) -> io::Result<()> {
+    write_json_atomic(&self.path, entries)
+  }
+
+  /// Grava (ou substitui) os reviews e persiste. Um registro sem decisão não apaga um que tem:
+  /// o mapa pode voltar pra fila e sair de novo sem ser revisado. Retorna quantos entraram.
+  pub fn record(&self, reviewed: Vec<ReviewedMap>) -> io::Result<usize> {
+    let mut entries = self.entries.lock().map_err(|_| io::Error::other("fingerprint store lock poisoned"))?;
+    let mut n = 0;
+    for r in reviewed {
+      match entries.iter_mut().find(|e| e.map_id == r.map_id) {
+        Some(e) if e.decision.is_some() && r.decision.is_none() => continue,
+        Some(e) => *e = r,
+        None => entries.push(r),
+      }
+      n += 1;
+    }
+    self.save(&entries)?;
+    Ok(n)
+  }
+
+  /// Reviews parecidos com `fp` (exatos primeiro, depois por similaridade), ignorando `exclude_id`.
+  pub fn matches(&self, fp: &Fingerprint, threshold: f32, exclude_id: Option<i64>) -> Vec<FingerprintMatch> {
+    let Ok(entries) = self.entries.lock() else {
+      return vec![];
+    };
+    let mut out: Vec<FingerprintMatch> = entries
+      .iter()
+      .filter(|e| Some(e.map_id) != exclude_id)
+      .filter_map(|e| {
+        let exact = e.hash == fp.hash;
+        let similarity = if exact { 1.0 } else { similarity(&fp.features, &e.features) };
+        (exact || similarity >= threshold).then(|| FingerprintMatch {
+          map_id: e.map_id,
+          exact,
+          similarity,
+          category: e.category.clone(),
+          decision: e.decision.clone(),
+          author: e.author.clone(),
+          reviewed_at: e.reviewed_at,
+        })
+      })
+      .collect();
+    out.sort_by(|a, b| {
+      b.exact
+        .cmp(&a.exact)
+        .then(b.similarity.total_cmp(&a.similarity))
+        .then(b.reviewed_at.cmp(&a.reviewed_at))
+    });
+    out.truncate(MAX_MATCHES);
+    out
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::map_xml::parse_map_xml;
+
+  const BASE: &str = r#"<C><P /><Z><S>
+    <S T="0" X="400" Y="390" L="800" H="20" P="0,0,0.3,0.2,0,0,0,0" />
+    <S T="1" X="200" Y="300" L="100" H="10" />
+    <S T="6" X="600" Y="250" L="120" H="10" P="0,0,0.3,0.2,30,0,0,0" />
+    <S T="2" X="100" Y="200" L="40" H="10" />
+    <S T="12" X="700" Y="100" L="50" H="50" o="ff0000" />
+    </S><D><DS X="50" Y="360" /><T X="750" Y="370" /><F X="400" Y="100" /></D></Z></C>"#;
+
+  fn fp(xml: &str) -> Fingerprint {
+    fingerprint(&parse_map_xml(xml).unwrap())
+  }
+
+  #[test]
+  fn order_and_formatting_do_not_matter() {
+    // mesmos elementos, outra ordem, atributos trocados de lugar e sem espaços
+    let shuffled = r#"<C><P/><Z><D><F Y="100" X="400"/><T X="750" Y="370"/><DS X="50" Y="360"/></D><S><S T="12" o="FF0000" H="50" L="50" Y="100" X="700"/><S T="2" X="100" Y="200" L="40" H="10"/><S X="600" T="6" Y="250" L="120" H="10" P="0,0,0.3,0.2,30,0,0,0"/><S T="1" X="200" Y="300" L="100" H="10"/><S T="0" X="400" Y="390" L="800" H="20"/></S></Z></C>"#;
+    let (a, b) = (fp(BASE), fp(shuffled));
+    assert_eq!(a.hash, b.hash);
+    assert_eq!(a.features, b.features);
+    assert_eq!(similarity(&a.features, &b.features), 1.0);
+  }
+
+  #[test]
+  fn small_edits_are_similar_but_not_exact() {
+    let nudged = BASE.replace(r#"X="200" Y="300""#, r#"X="202" Y="301""#);
+    let (a, b) = (fp(BASE), fp(&nudged));
+    assert_ne!(a.hash, b.hash);
+    // deslocamento dentro da grade não muda as features
+    assert_eq!(similarity(&a.features, &b.features), 1.0);
+
+    let edited = BASE.replace(r#"<S T="2" X="100" Y="200" L="40" H="10" />"#, r#"<S T="3" X="100" Y="200" L="40" H="10" />"#);
+    let c = fp(&edited);
+    // 9 elementos, 1 trocado: 8 / 10
+    assert!((similarity(&a.features, &c.features) - 0.8).abs() < 1e-6);
+
+    let other = fp(r#"<C><P L="1600" /><Z><S><S T="10" X="10" Y="10" L="10" H="10" /></S></Z></C>"#);
+    assert!(similarity(&a.features, &other.features) < 0.2);
+  }
+
+  #[test]
+  fn store_matches_and_persists() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("fingerprints.json");
+    let store = FingerprintStore::load(path.clone());
+    let base = fp(BASE);
+    let reviewed = |map_id, f: &Fingerprint, decision: &str, at| ReviewedMap {
+      map_id,
+      hash: f.hash.clone(),
+      features: f.features.clone(),
+      category: Some("P4".into()),
+      decision: Some(decision.into()),
+      author: Some("Someone".into()),
+      reviewed_at: at,
+    };
+    let edited = fp(&BASE.replace(r#"T="2""#, r#"T="3""#));
+    store
+      .record(vec![reviewed(100, &edited, "p1ed", 10), reviewed(200, &base, "left_as_is", 20)])
+      .unwrap();
+    // re-review do mesmo código substitui
+    store.record(vec![reviewed(200, &base, "will_be_discussed", 30)]).unwrap();
+    // ...mas um registro sem decisão não apaga a anterior
+    let undecided = ReviewedMap {
+      decision: None,
+      reviewed_at: 40,
+      ..reviewed(100, &edited, "", 0)
+    };
+    assert_eq!(store.record(vec![undecided]).unwrap(), 0);
+    assert_eq!(store.matches(&edited, 1.0, None)[0].decision.as_deref(), Some("p1ed"));
+    assert_eq!(store.matches(&base, 0.0, None).len(), 2);
+
+    // 1 elemento trocado num mapa de 9 = 0.8
+    let found = store.matches(&base, 0.75, Some(999));
+    assert_eq!(found.iter().map(|m| (m.map_id, m.exact)).collect::<Vec<_>>(), vec![(200, true), (100, false)]);
+    assert_eq!(found[0].decision.as_deref(), Some("will_be_discussed"));
+    assert!(store.matches(&base, 0.95, Some(200)).is_empty());
+
+    let reloaded = FingerprintStore::load(path.clone());
+    assert_eq!(reloaded.matches(&base, DEFAULT_THRESHOLD, None).len(), 1);
+    assert_eq!(reloaded.matches(&base, 0.75, None).len(), 2);
+  }
+}
@@ -0,0 +1,560 @@
+// Busca de mapInfo (author/p/xml por id) em chunks paralelos.
+//
+// A origem é um `MapInfoProvider`: o Cypher801 (URL/key configuráveis) ou um diretório local
+// com `<id>.xml` (dump offline). A escolha feita nas configurações (`ProviderConfig`) fica
+// salva; o que ela não traz vem do env/.env. Cada chunk é independente: erro transitório (timeout, 429,
+// 5xx) tenta de novo com backoff, erro definitivo marca só os ids daquele chunk como `failed`.
+// O resultado sai por id, na ordem pedida, para o chamador decidir o que fazer (cache, UI).
+
+use std::{
+  collections::HashMap,
+  fs, io,
+  path::{Path, PathBuf},
+  sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+  },
+  thread,
+  time::Duration,
+};
+
+use crate::atomic_file::write_json_atomic;
+
+pub const CYPHER_CHUNK_SIZE: usize = 80;
+pub const DEFAULT_CONCURRENCY: usize = 4;
+pub const MAX_ATTEMPTS: usize = 3;
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(400);
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct MapInfoEntry {
+  pub id: i64,
+  /// Vazio quando a origem não sabe (diretório local).
+  #[serde(default)]
+  pub author: String,
+  pub xml: String,
+  #[serde(default)]
+  pub p: Option<i64>,
+}
+
+impl From<crate::map_cache::CachedMapInfo> for MapInfoEntry {
+  fn from(e: crate::map_cache::CachedMapInfo) -> Self {
+    Self {
+      id: e.id,
+      author: e.author,
+      xml: e.xml,
+      p: e.p,
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChunkError {
+  /// Vale tentar de novo (timeout, conexão, 429/5xx).
+  Transient(String),
+  Fatal(String),
+}
+
+impl ChunkError {
+  fn message(&self) -> &str {
+    match self {
+      ChunkError::Transient(m) | ChunkError::Fatal(m) => m,
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Fetched {
+  Found(MapInfoEntry),
+  /// O chunk respondeu, mas sem esse id (mapa apagado / id inexistente).
+  NotFound,
+  Failed(String),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Progress {
+  pub done: usize,
+  pub total: usize,
+  pub found: usize,
+  pub not_found: usize,
+  pub failed: usize,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct FetchOptions {
+  pub chunk_size: usize,
+  pub concurrency: usize,
+  pub max_attempts: usize,
+  pub retry_delay: Duration,
+}
+
+impl Default for FetchOptions {
+  fn default() -> Self {
+    Self {
+      chunk_size: CYPHER_CHUNK_SIZE,
+      concurrency: DEFAULT_CONCURRENCY,
+      max_attempts: MAX_ATTEMPTS,
+      retry_delay: RETRY_BASE_DELAY,
+    }
+  }
+}
+
+fn fetch_with_retry<F>(chunk: &[i64], opts: &FetchOptions, fetch_chunk: &F) -> Result<Vec<MapInfoEntry>, ChunkError>
+where
+  F: Fn(&[i64]) -> Result<Vec<MapInfoEntry>, ChunkError>,
+{
+  let mut attempt = 1;
+  loop {
+    match fetch_chunk(chunk) {
+      Err(ChunkError::Transient(e)) if attempt < opts.max_attempts => {
+        log::warn!("mapInfo chunk failed (attempt {attempt}/{}): {e}", opts.max_attempts);
+        thread::sleep(opts.retry_delay * (1 << (attempt - 1)));
+        attempt += 1;
+      }
+      other => return other,
+    }
+  }
+}
+
+/// Busca `ids` em chunks com no máximo `opts.concurrency` requisições simultâneas.
+///
+/// `on_progress` é chamado (de qualquer worker) após cada chunk terminar.
+pub fn fetch_concurrent<F, P>(ids: &[i64], opts: FetchOptions, fetch_chunk: F, on_progress: P) -> Vec<(i64, Fetched)>
+where
+  F: Fn(&[i64]) -> Result<Vec<MapInfoEntry>, ChunkError> + Sync,
+  P: Fn(Progress) + Sync,
+{
+  let chunks: Vec<&[i64]> = ids.chunks(opts.chunk_size.max(1)).collect();
+  let next = AtomicUsize::new(0);
+  let results: Mutex<HashMap<i64, Fetched>> = Mutex::new(HashMap::new());
+  let progress = Mutex::new(Progress {
+    total: ids.len(),
+    ..Progress::default()
+  });
+
+  let workers = opts.concurrency.clamp(1, chunks.len().max(1));
+  thread::scope(|s| {
+    for _ in 0..workers {
+      s.spawn(|| loop {
+        let i = next.fetch_add(1, Ordering::SeqCst);
+        let Some(chunk) = chunks.get(i) else { break };
+
+        let outcome = fetch_with_retry(chunk, &opts, &fetch_chunk);
+        let mut found: HashMap<i64, MapInfoEntry> = HashMap::new();
+        let error = match outcome {
+          Ok(entries) => {
+            found.extend(entries.into_iter().map(|e| (e.id, e)));
+            None
+          }
+          Err(e) => Some(e.message().to_string()),
+        };
+
+        let mut delta = Progress::default();
+        let mut res = results.lock().unwrap_or_else(|p| p.into_inner());
+        for id in chunk.iter() {
+          let fetched = match (&error, found.remove(id)) {
+            (Some(e), _) => {
+              delta.failed += 1;
+              Fetched::Failed(e.clone())
+            }
+            (None, Some(entry)) => {
+              delta.found += 1;
+              Fetched::Found(entry)
+            }
+            (None, None) => {
+              delta.not_found += 1;
+              Fetched::NotFound
+            }
+          };
+          res.insert(*id, fetched);
+        }
+        drop(res);
+
+        let snapshot = {
+          let mut p = progress.lock().unwrap_or_else(|p| p.into_inner());
+          p.done += chunk.len();
+          p.found += delta.found;
+          p.not_found += delta.not_found;
+          p.failed += delta.failed;
+          *p
+        };
+        on_progress(snapshot);
+      });
+    }
+  });
+
+  let mut results = results.into_inner().unwrap_or_else(|p| p.into_inner());
+  ids
+    .iter()
+    .map(|id| {
+      let fetched = results
+        .remove(id)
+        .unwrap_or_else(|| Fetched::Failed("not fetched".into()));
+      (*id, fetched)
+    })
+    .collect()
+}
+
+/// Origem dos dados de mapa.
+pub trait MapInfoProvider: Send + Sync {
+  /// "cypher801" | "localDir"
+  fn kind(&self) -> &'static str;
+
+  /// Descrição para a UI/log (sem segredos).
+  fn describe(&self) -> String;
+
+  /// Ids ausentes na resposta viram `NotFound`.
+  fn fetch_chunk(&self, ids: &[i64]) -> Result<Vec<MapInfoEntry>, ChunkError>;
+
+  fn options(&self) -> FetchOptions {
+    FetchOptions::default()
+  }
+
+  /// Vale guardar no cache em disco? (não faz sentido para arquivos locais)
+  fn cacheable(&self) -> bool {
+    true
+  }
+
+  /// Subpasta do cache em disco: cada origem tem a sua, para a troca de servidor não
+  /// servir entradas do anterior.
+  fn cache_namespace(&self) -> String;
+}
+
+pub const CYPHER_DEFAULT_BASE_URL: &str = "https://cypher801.app/";
+
+/// Origem escolhida nas configurações (salva em JSON). Campos vazios vêm do env/.env.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ProviderConfig {
+  #[serde(rename_all = "camelCase")]
+  Cypher801 {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    base_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    key: Option<String>,
+  },
+  LocalDir { dir: String },
+}
+
+impl ProviderConfig {
+  pub fn load_file(path: &Path) -> Option<Self> {
+    let raw = fs::read(path).ok()?;
+    serde_json::from_slice(&raw).ok()
+  }
+
+  pub fn save_file(&self, path: &Path) -> io::Result<()> {
+    write_json_atomic(path, self)
+  }
+
+  /// Completa URL/key do Cypher801 com as de `env` (a key nunca vem do código).
+  pub fn with_defaults(self, env: &ProviderConfig) -> Self {
+    match (self, env) {
+      (
+        ProviderConfig::Cypher801 { base_url, key },
+        ProviderConfig::Cypher801 {
+          base_url: env_url,
+          key: env_key,
+        },
+      ) => ProviderConfig::Cypher801 {
+        base_url: base_url.or_else(|| env_url.clone()),
+        key: key.or_else(|| env_key.clone()),
+      },
+      (cfg, _) => cfg,
+    }
+  }
+
+  pub fn build(&self) -> Result<Arc<dyn MapInfoProvider>, String> {
+    match self {
+      ProviderConfig::Cypher801 { base_url, key } => Ok(Arc::new(Cypher801Provider::new(
+        base_url.as_deref().unwrap_or(CYPHER_DEFAULT_BASE_URL),
+        key.as_deref(),
+      )?)),
+      ProviderConfig::LocalDir { dir } => Ok(Arc::new(LocalDirProvider::new(dir.into())?)),
+    }
+  }
+}
+
+#[derive(serde::Deserialize)]
+struct CypherResponse {
+  error: bool,
+  #[serde(default)]
+  data: Vec<MapInfoEntry>,
+}
+
+/// `/mapInfo` do Cypher801 (ou um servidor compatível, p.ex. em testes).
+pub struct Cypher801Provider {
+  client: reqwest::blocking::Client,
+  /// Com `/` no fim.
+  base_url: String,
+  key: Option<String>,
+}
+
+impl Cypher801Provider {
+  pub fn new(base_url: &str, key: Option<&str>) -> Result<Self, String> {
+    let client = reqwest::blocking::Client::builder()
+      .timeout(Duration::from_secs(8))
+      .build()
+      .map_err(|e| e.to_string())?;
+    let mut base_url = base_url.trim().to_string();
+    if !base_url.ends_with('/') {
+      base_url.push('/');
+    }
+    Ok(Self {
+      client,
+      base_url,
+      key: key.map(str::trim).filter(|k| !k.is_empty()).map(str::to_string),
+    })
+  }
+}
+
+impl MapInfoProvider for Cypher801Provider {
+  fn kind(&self) -> &'static str {
+    "cypher801"
+  }
+
+  fn describe(&self) -> String {
+    self.base_url.clone()
+  }
+
+  fn fetch_chunk(&self, ids: &[i64]) -> Result<Vec<MapInfoEntry>, ChunkError> {
+    let Some(key) = &self.key else {
+      return Err(ChunkError::Fatal("no Cypher801 key configured (CYPHER801_KEY or settings)".into()));
+    };
+    let joined = ids.iter().map(|x| x.to_string()).collect::<Vec<_>>().join(",");
+    let url = format!("{}mapInfo/?maps={joined}&key={key}", self.base_url);
+
+    let resp = self.client.get(url).send().map_err(|e| {
+      if e.is_timeout() || e.is_connect() || e.is_request() {
+        ChunkError::Transient(e.without_url().to_string())
+      } else {
+        ChunkError::Fatal(e.without_url().to_string())
+      }
+    })?;
+    let status = resp.status();
+    if status.as_u16() == 429 || status.is_server_error() {
+      return Err(ChunkError::Transient(format!("http {status}")));
+    }
+    if !status.is_success() {
+      return Err(ChunkError::Fatal(format!("http {status}")));
+    }
+
+    let body = resp.text().map_err(|e| ChunkError::Transient(e.without_url().to_string()))?;
+    let parsed: CypherResponse = serde_json::from_str(&body).map_err(|e| ChunkError::Fatal(e.to_string()))?;
+    if parsed.error {
+      return Err(ChunkError::Fatal("cypher801 returned error".into()));
+    }
+    Ok(parsed.data)
+  }
+
+  fn cache_namespace(&self) -> String {
+    // "https://cypher801.app/" -> "cypher801-cypher801.app"
+    let rest = self.base_url.split_once("://").map_or(self.base_url.as_str(), |(_, r)| r);
+    let safe: String = rest
+      .chars()
+      .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' })
+      .collect();
+    format!("cypher801-{}", safe.trim_matches('_'))
+  }
+}
+
+/// Diretório com `<id>.xml` (dump offline). Não tem author/p.
+pub struct LocalDirProvider {
+  dir: PathBuf,
+}
+
+impl LocalDirProvider {
+  pub fn new(dir: PathBuf) -> Result<Self, String> {
+    if !dir.is_dir() {
+      return Err(format!("not a directory: {}", dir.display()));
+    }
+    Ok(Self { dir })
+  }
+}
+
+impl MapInfoProvider for LocalDirProvider {
+  fn kind(&self) -> &'static str {
+    "localDir"
+  }
+
+  fn describe(&self) -> String {
+    self.dir.display().to_string()
+  }
+
+  fn fetch_chunk(&self, ids: &[i64]) -> Result<Vec<MapInfoEntry>, ChunkError> {
+    let mut out = Vec::new();
+    for &id in ids {
+      match fs::read_to_string(self.dir.join(format!("{id}.xml"))) {
+        Ok(xml) => out.push(MapInfoEntry {
+          id,
+          author: String::new(),
+          xml: xml.trim().to_string(),
+          p: None,
+        }),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(ChunkError::Fatal(format!("{id}.xml: {e}"))),
+      }
+    }
+    Ok(out)
+  }
+
+  fn options(&self) -> FetchOptions {
+    // um arquivo por chunk: erro de leitura afeta só aquele id
+    FetchOptions {
+      chunk_size: 1,
+      concurrency: 2,
+      max_attempts: 1,
+      ..FetchOptions::default()
+    }
+  }
+
+  fn cacheable(&self) -> bool {
+    false
+  }
+
+  fn cache_namespace(&self) -> String {
+    "localDir".into()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn entry(id: i64) -> MapInfoEntry {
+    MapInfoEntry {
+      id,
+      author: format!("Author{id}"),
+      xml: "<C />".into(),
+      p: Some(17),
+    }
+  }
+
+  fn opts() -> FetchOptions {
+    FetchOptions {
+      chunk_size: 2,
+      concurrency: 3,
+      max_attempts: 3,
+      retry_delay: Duration::from_millis(1),
+    }
+  }
+
+  #[test]
+  fn partial_failures_are_per_chunk() {
+    let ids: Vec<i64> = (1..=7).collect();
+    let attempts = Mutex::new(HashMap::<i64, usize>::new());
+    let progress = Mutex::new(Vec::<Progress>::new());
+
+    let out = fetch_concurrent(
+      &ids,
+      opts(),
+      |chunk| {
+        let n = {
+          let mut a = attempts.lock().unwrap();
+          let n = a.entry(chunk[0]).or_default();
+          *n += 1;
+          *n
+        };
+        match chunk[0] {
+          // falha uma vez e depois responde
+          1 if n == 1 => Err(ChunkError::Transient("timeout".into())),
+          // nunca responde
+          3 => Err(ChunkError::Transient("timeout".into())),
+          5 => Err(ChunkError::Fatal("bad json".into())),
+          // 7 não existe
+          _ => Ok(chunk.iter().filter(|id| **id != 7).map(|id| entry(*id)).collect()),
+        }
+      },
+      |p| progress.lock().unwrap().push(p),
+    );
+
+    let expected = vec![
+      (1, Fetched::Found(entry(1))),
+      (2, Fetched::Found(entry(2))),
+      (3, Fetched::Failed("timeout".into())),
+      (4, Fetched::Failed("timeout".into())),
+      (5, Fetched::Failed("bad json".into())),
+      (6, Fetched::Failed("bad json".into())),
+      (7, Fetched::NotFound),
+    ];
+    assert_eq!(out, expected);
+
+    let attempts = attempts.into_inner().unwrap();
+    assert_eq!(attempts[&1], 2);
+    assert_eq!(attempts[&3], 3);
+    // erro definitivo não é repetido
+    assert_eq!(attempts[&5], 1);
+
+    let progress = progress.into_inner().unwrap();
+    assert_eq!(progress.len(), 4);
+    assert_eq!(
+      progress.iter().max_by_key(|p| p.done).copied(),
+      Some(Progress {
+        done: 7,
+        total: 7,
+        found: 2,
+        not_found: 1,
+        failed: 4,
+      })
+    );
+  }
+
+  #[test]
+  fn local_dir_provider() {
+    let tmp = tempfile::tempdir().unwrap();
+    let dir = tmp.path().to_path_buf();
+    fs::write(dir.join("123.xml"), "  <C><P /></C>\n").unwrap();
+    fs::create_dir(dir.join("456.xml")).unwrap();
+
+    let provider = LocalDirProvider::new(dir.clone()).unwrap();
+    let out = fetch_concurrent(&[123, 789, 456], provider.options(), |c| provider.fetch_chunk(c), |_| {});
+    let expected = MapInfoEntry {
+      id: 123,
+      author: String::new(),
+      xml: "<C><P /></C>".into(),
+      p: None,
+    };
+    assert_eq!(out[0].1, Fetched::Found(expected));
+    assert_eq!(out[1].1, Fetched::NotFound);
+    assert!(matches!(&out[2].1, Fetched::Failed(e) if e.starts_with("456.xml")));
+
+    assert!(LocalDirProvider::new(dir.join("123.xml")).is_err());
+  }
+
+  #[test]
+  fn provider_config_fills_from_env() {
+    let env = ProviderConfig::Cypher801 {
+      base_url: Some("http://127.0.0.1:8080/api/".into()),
+      key: Some("env-key".into()),
+    };
+    let saved = ProviderConfig::Cypher801 {
+      base_url: None,
+      key: Some("saved-key".into()),
+    };
+    let merged = saved.with_defaults(&env);
+    assert_eq!(
+      merged,
+      ProviderConfig::Cypher801 {
+        base_url: Some("http://127.0.0.1:8080/api/".into()),
+        key: Some("saved-key".into()),
+      }
+    );
+    assert_eq!(merged.build().unwrap().cache_namespace(), "cypher801-127.0.0.1_8080_api");
+
+    let raw = r#"{"kind":"cypher801","baseUrl":"https://cypher801.app/"}"#;
+    let parsed: ProviderConfig = serde_json::from_str(raw).unwrap();
+    assert_eq!(parsed.build().unwrap().cache_namespace(), "cypher801-cypher801.app");
+    // sem key em lugar nenhum: nem tenta a rede
+    let fetched = parsed.build().unwrap().fetch_chunk(&[1]);
+    assert!(matches!(fetched, Err(ChunkError::Fatal(e)) if e.contains("CYPHER801_KEY")));
+
+    let local = ProviderConfig::LocalDir { dir: "/nonexistent-mapinfo-dir".into() };
+    assert_eq!(local.clone().with_defaults(&env), local);
+    assert!(local.build().is_err());
+  }
+
+  #[test]
+  fn empty_input() {
+    let out = fetch_concurrent(&[], opts(), |_| Ok(vec![]), |_| panic!("no chunks"));
+    assert!(out.is_empty());
+  }
+}
@@ -0,0 +1,290 @@
+// Regras de aceitação por categoria aplicadas ao `map_xml::MapXml`.
+//
+// Cada categoria revisada tem um `RuleSet` (tabela abaixo); as regras comuns valem para todas.
+// Nada aqui reprova mapa sozinho: o resultado é uma lista de avisos para o reviewer.
+
+use crate::map_xml::{MapXml, PointKind};
+
+/// Largura "padrão" do jogo; bootcamp acima disso costuma ser recusado.
+pub const BOOTCAMP_MAX_WIDTH: f32 = 800.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+  Info,
+  Warning,
+  Error,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintWarning {
+  pub rule: &'static str,
+  pub severity: Severity,
+  pub message: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Shaman {
+  Allowed,
+  /// Categoria sem shaman: spawn de shaman / objetos pré-colocados são suspeitos.
+  Forbidden,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct RuleSet {
+  code: u32,
+  needs_hole: bool,
+  needs_cheese: bool,
+  /// Survivor: toca/queijo não fazem sentido.
+  no_hole_or_cheese: bool,
+  shaman: Shaman,
+  max_width: Option<f32>,
+  needs_defilante: bool,
+}
+
+const fn playable(code: u32) -> RuleSet {
+  RuleSet {
+    code,
+    needs_hole: true,
+    needs_cheese: true,
+    no_hole_or_cheese: false,
+    shaman: Shaman::Allowed,
+    max_width: None,
+    needs_defilante: false,
+  }
+}
+
+const fn survivor(code: u32) -> RuleSet {
+  RuleSet {
+    needs_hole: false,
+    needs_cheese: false,
+    no_hole_or_cheese: true,
+    ..playable(code)
+  }
+}
+
+const fn no_shaman(code: u32) -> RuleSet {
+  RuleSet {
+    shaman: Shaman::Forbidden,
+    ..playable(code)
+  }
+}
+
+// Uma por categoria revisada de `categories.json` (reviewed: true); o teste
+// `rule_sets_cover_the_reviewed_categories` pega quando as duas listas divergem.
+const RULE_SETS: &[RuleSet] = &[
+  RuleSet {
+    max_width: Some(BOOTCAMP_MAX_WIDTH),
+    ..no_shaman(3)
+  },
+  playable(4),
+  playable(5),
+  playable(6),
+  no_shaman(7),
+  playable(8),
+  playable(9),
+  survivor(10),
+  survivor(11),
+  no_shaman(17),
+  RuleSet {
+    needs_cheese: false,
+    needs_defilante: true,
+    ..no_shaman(18)
+  },
+  survivor(24),
+  playable(66),
+];
+
+fn rule_set(category: u32) -> Option<&'static RuleSet> {
+  RULE_SETS.iter().find(|r| r.code == category)
+}
+
+struct Out(Vec<LintWarning>);
+
+impl Out {
+  fn push(&mut self, rule: &'static str, severity: Severity, message: impl Into<String>) {
+    self.0.push(LintWarning {
+      rule,
+      severity,
+      message: message.into(),
+    });
+  }
+}
+
+fn common_rules(map: &MapXml, out: &mut Out) {
+  let p = &map.properties;
+
+  if map.grounds.is_empty() {
+    out.push("no-grounds", Severity::Warning, "map has no grounds");
+  }
+
+  for pt in &map.points {
+    let inside = (0.0..=p.width).contains(&pt.x) && (0.0..=p.height).contains(&pt.y);
+    if !inside {
+      let what = match pt.kind {
+        PointKind::MouseSpawn => "mouse spawn",
+        PointKind::ShamanSpawn => "shaman spawn",
+        PointKind::Hole => "hole",
+        PointKind::Cheese => "cheese",
+      };
+      out.push(
+        "out-of-bounds",
+        Severity::Warning,
+        format!("{what} at ({}, {}) is outside the {}x{} map", pt.x, pt.y, p.width, p.height),
+      );
+    }
+  }
+
+  for w in &map.warnings {
+    out.push("xml-parse", Severity::Info, w.clone());
+  }
+}
+
+fn category_rules(map: &MapXml, rules: &RuleSet, out: &mut Out) {
+  let p = &map.properties;
+  let holes = map.points_of(PointKind::Hole).count();
+  let cheeses = map.points_of(PointKind::Cheese).count();
+
+  if rules.needs_hole && holes == 0 {
+    out.push("missing-hole", Severity::Error, "map has no hole");
+  }
+  if rules.needs_cheese && cheeses == 0 {
+    out.push("missing-cheese", Severity::Error, "map has no cheese");
+  }
+  if rules.no_hole_or_cheese && holes + cheeses > 0 {
+    out.push(
+      "unexpected-hole-or-cheese",
+      Severity::Warning,
+      format!("survivor map has {holes} hole(s) and {cheeses} cheese(s)"),
+    );
+  }
+
+  if rules.shaman == Shaman::Forbidden {
+    let spawns = map.points_of(PointKind::ShamanSpawn).count();
+    if spawns > 0 {
+      out.push(
+        "shaman-spawn",
+        Severity::Warning,
+        format!("{spawns} shaman spawn(s) in a no-shaman category"),
+      );
+    }
+    if !map.shaman_objects.is_empty() {
+      out.push(
+        "shaman-objects",
+        Severity::Warning,
+        format!("{} shaman object(s) placed in a no-shaman category", map.shaman_objects.len()),
+      );
+    }
+  }
+
+  if let Some(max) = rules.max_width {
+    if p.width > max {
+      out.push(
+        "map-too-wide",
+        Severity::Warning,
+        format!("map is {} wide (max {max} for P{})", p.width, rules.code),
+      );
+    }
+  }
+
+  if rules.needs_defilante && !p.defilante {
+    out.push("missing-defilante", Severity::Error, "defilante flag is not set");
+  }
+}
+
+/// Avisos do mapa para a categoria `P<category>`, do mais grave para o menos grave.
+pub fn lint(map: &MapXml, category: u32) -> Vec<LintWarning> {
+  let mut out = Out(Vec::new());
+  if let Some(rules) = rule_set(category) {
+    category_rules(map, rules, &mut out);
+  }
+  common_rules(map, &mut out);
+  // sort estável: dentro da mesma severidade mantém a ordem das regras
+  out.0.sort_by_key(|w| std::cmp::Reverse(w.severity));
+  out.0
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::map_xml::parse_map_xml;
+
+  fn rules_of(xml: &str, category: u32) -> Vec<(&'static str, Severity)> {
+    lint(&parse_map_xml(xml).unwrap(), category)
+      .into_iter()
+      .map(|w| (w.rule, w.severity))
+      .collect()
+  }
+
+  type Expected<'a> = &'a [(&'a str, Severity)];
+
+  #[test]
+  fn rule_sets_cover_the_reviewed_categories() {
+    let mut reviewed: Vec<String> = crate::categories::all()
+      .iter()
+      .filter(|c| c.reviewed)
+      .map(|c| c.code.clone())
+      .collect();
+    let mut rules: Vec<String> = RULE_SETS.iter().map(|r| format!("P{}", r.code)).collect();
+    reviewed.sort();
+    rules.sort();
+    assert_eq!(rules, reviewed);
+  }
+
+  const GROUND: &str = r#"<S T="0" X="400" Y="390" L="800" H="20" />"#;
+
+  #[test]
+  fn category_table() {
+    let complete = format!(r#"<C><P /><Z><S>{GROUND}</S><D><T X="700" Y="370" /><F X="100" Y="300" /><DS X="50" Y="360" /></D></Z></C>"#);
+    let empty_d = format!(r#"<C><P /><Z><S>{GROUND}</S><D><DC X="60" Y="350" /></D><O><O C="1" X="1" Y="1" /></O></Z></C>"#);
+    let wide = format!(r#"<C><P L="1600" /><Z><S>{GROUND}</S><D><T X="700" Y="370" /><F X="100" Y="300" /></D></Z></C>"#);
+    let defilante = format!(r#"<C><P L="4800" defilante="0,0,0,1" /><Z><S>{GROUND}</S><D><T X="700" Y="370" /></D></Z></C>"#);
+
+    use Severity::*;
+    let cases: &[(&str, u32, Expected)] = &[
+      (&complete, 4, &[]),
+      (&complete, 17, &[]),
+      // survivor não tem toca/queijo
+      (&complete, 10, &[("unexpected-hole-or-cheese", Warning)]),
+      (&empty_d, 4, &[("missing-hole", Error), ("missing-cheese", Error)]),
+      (
+        &empty_d,
+        7,
+        &[
+          ("missing-hole", Error),
+          ("missing-cheese", Error),
+          ("shaman-spawn", Warning),
+          ("shaman-objects", Warning),
+        ],
+      ),
+      (&empty_d, 11, &[]),
+      (&wide, 3, &[("map-too-wide", Warning)]),
+      (&wide, 5, &[]),
+      (&wide, 18, &[("missing-defilante", Error)]),
+      (&defilante, 18, &[]),
+      // categoria sem regras: só as comuns
+      (&empty_d, 0, &[]),
+    ];
+    for (xml, category, expected) in cases {
+      assert_eq!(rules_of(xml, *category), expected.to_vec(), "P{category}: {xml}");
+    }
+  }
+
+  #[test]
+  fn common_rules_apply_everywhere() {
+    let xml = r#"<C><P /><Z><D><DS X="900" Y="10" /><T X="10" Y="-5" /><F X="1" Y="1" /></D><L><JX /></L></Z></C>"#;
+    let got = lint(&parse_map_xml(xml).unwrap(), 4);
+    let rules: Vec<_> = got.iter().map(|w| (w.rule, w.severity)).collect();
+    assert_eq!(
+      rules,
+      vec![
+        ("no-grounds", Severity::Warning),
+        ("out-of-bounds", Severity::Warning),
+        ("out-of-bounds", Severity::Warning),
+        ("xml-parse", Severity::Info),
+      ]
+    );
+    assert_eq!(got[1].message, "mouse spawn at (900, 10) is outside the 800x400 map");
+  }
+}
@@ -0,0 +1,386 @@
+// Miniatura PNG do mapa, desenhada na CPU (tiny-skia) a partir do `map_xml::MapXml`.
+//
+// Não tenta imitar o jogo pixel a pixel: cada tipo de ground tem uma cor chapada, objetos de
+// shaman viram quadradinhos e spawn/toca/queijo viram marcadores. O suficiente para bater o
+// olho na fila antes de mandar `/np`.
+
+use std::{
+  fs, io,
+  path::{Path, PathBuf},
+  time::SystemTime,
+};
+
+use tiny_skia::{Color, FillRule, Paint, PathBuilder, Pixmap, Rect, Stroke, Transform};
+
+use crate::{
+  atomic_file::write_atomic,
+  map_fingerprint::fnv1a,
+  map_xml::{Ground, MapXml, PointKind},
+};
+
+pub const DEFAULT_WIDTH: u32 = 400;
+pub const MIN_SIZE: u32 = 32;
+pub const MAX_SIZE: u32 = 2000;
+/// Teto do cache de PNGs em disco; acima disso os menos usados saem primeiro.
+pub const DEFAULT_CACHE_BYTES: u64 = 64 * 1024 * 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PreviewOptions {
+  pub width: u32,
+  /// `None`: proporcional ao mapa.
+  pub height: Option<u32>,
+  /// Desenha grounds invisíveis (T=14) em contorno.
+  pub show_invisible: bool,
+}
+
+impl Default for PreviewOptions {
+  fn default() -> Self {
+    Self {
+      width: DEFAULT_WIDTH,
+      height: None,
+      show_invisible: false,
+    }
+  }
+}
+
+impl PreviewOptions {
+  fn output_size(&self, map: &MapXml) -> (u32, u32) {
+    let width = self.width.clamp(MIN_SIZE, MAX_SIZE);
+    let height = match self.height {
+      Some(h) => h,
+      None => {
+        let p = &map.properties;
+        let ratio = if p.width > 0.0 { p.height / p.width } else { 0.5 };
+        (width as f32 * ratio).round() as u32
+      }
+    };
+    (width, height.clamp(MIN_SIZE, MAX_SIZE))
+  }
+}
+
+fn rgb(hex: u32) -> Color {
+  Color::from_rgba8((hex >> 16) as u8, (hex >> 8) as u8, hex as u8, 255)
+}
+
+fn with_alpha(mut c: Color, a: f32) -> Color {
+  c.set_alpha(a);
+  c
+}
+
+fn parse_hex(hex: &str) -> Option<Color> {
+  let h = hex.trim().trim_start_matches('#').trim_start_matches("0x");
+  if h.is_empty() || h.len() > 6 {
+    return None;
+  }
+  u32::from_str_radix(h, 16).ok().map(rgb)
+}
+
+fn ground_color(g: &Ground) -> Option<Color> {
+  let c = match g.ground_type.0 {
+    0 => rgb(0x86_5a_2c),
+    1 => rgb(0x9f_d6_f0),
+    2 => rgb(0x6c_b8_42),
+    3 => rgb(0xf0_5a_28),
+    4 => rgb(0x5b_3a_21),
+    5 => rgb(0x8b_5a_2b),
+    6 => rgb(0x5d_a1_30),
+    7 => rgb(0xe4_c4_7a),
+    8 => with_alpha(rgb(0xf2_f2_f2), 0.8),
+    9 => with_alpha(rgb(0x3b_8d_db), 0.55),
+    10 => rgb(0x8c_8c_8c),
+    11 => rgb(0xf5_fb_ff),
+    12 | 13 => g.color.as_deref().and_then(parse_hex).unwrap_or(rgb(0x32_46_50)),
+    14 => return None,
+    15 => with_alpha(rgb(0xdd_dd_dd), 0.5),
+    16 => rgb(0xa6_7c_52),
+    17 => rgb(0xe0_86_2b),
+    18 => rgb(0xe8_5f_a8),
+    19 => rgb(0x7b_e0_4a),
+    _ => rgb(0xff_00_ff),
+  };
+  Some(c)
+}
+
+fn paint(color: Color) -> Paint<'static> {
+  let mut p = Paint::default();
+  p.set_color(color);
+  p.anti_alias = true;
+  p
+}
+
+fn draw_ground(pixmap: &mut Pixmap, g: &Ground, base: Transform, show_invisible: bool) {
+  let invisible = g.ground_type.0 == 14;
+  let color = match ground_color(g) {
+    Some(c) => c,
+    None if invisible && show_invisible => rgb(0xff_ff_ff),
+    None => return,
+  };
+  let ts = base.pre_rotate_at(g.angle, g.x, g.y);
+
+  let path = if g.ground_type.is_circle() {
+    // círculo usa L como raio
+    PathBuilder::from_circle(g.x, g.y, g.width.abs().max(1.0))
+  } else {
+    let (w, h) = (g.width.abs().max(1.0), g.height.abs().max(1.0));
+    Rect::from_xywh(g.x - w / 2.0, g.y - h / 2.0, w, h).map(PathBuilder::from_rect)
+  };
+  let Some(path) = path else { return };
+
+  if invisible {
+    let stroke = Stroke {
+      width: 1.0,
+      ..Stroke::default()
+    };
+    pixmap.stroke_path(&path, &paint(with_alpha(color, 0.6)), &stroke, ts, None);
+  } else {
+    pixmap.fill_path(&path, &paint(color), FillRule::Winding, ts, None);
+  }
+}
+
+fn draw_marker(pixmap: &mut Pixmap, x: f32, y: f32, radius: f32, color: Color, ts: Transform) {
+  if let Some(path) = PathBuilder::from_circle(x, y, radius) {
+    pixmap.fill_path(&path, &paint(color), FillRule::Winding, ts, None);
+    let stroke = Stroke {
+      width: radius / 4.0,
+      ..Stroke::default()
+    };
+    pixmap.stroke_path(&path, &paint(rgb(0x10_10_10)), &stroke, ts, None);
+  }
+}
+
+/// Desenha o mapa. `None` só se o tamanho for inválido.
+pub fn render(map: &MapXml, opts: &PreviewOptions) -> Option<Pixmap> {
+  let (out_w, out_h) = opts.output_size(map);
+  let mut pixmap = Pixmap::new(out_w, out_h)?;
+
+  let p = &map.properties;
+  let (map_w, map_h) = (p.width.max(1.0), p.height.max(1.0));
+  let scale = (out_w as f32 / map_w).min(out_h as f32 / map_h);
+  // centraliza quando a proporção pedida não bate com a do mapa
+  let (dx, dy) = ((out_w as f32 - map_w * scale) / 2.0, (out_h as f32 - map_h * scale) / 2.0);
+  let base = Transform::from_row(scale, 0.0, 0.0, scale, dx, dy);
+
+  pixmap.fill(rgb(0x20_22_2c));
+  let sky = if p.night { rgb(0x2b_2f_3e) } else { rgb(0x6a_74_95) };
+  if let Some(area) = Rect::from_xywh(0.0, 0.0, map_w, map_h) {
+    pixmap.fill_rect(area, &paint(sky), base, None);
+  }
+
+  // joints com linha desenhada ficam atrás dos grounds (como no jogo, por padrão)
+  for j in &map.joints {
+    let (Some(color), Some((x1, y1)), Some((x2, y2))) = (j.color.as_deref(), j.point1, j.point2) else {
+      continue;
+    };
+    let Some(color) = parse_hex(color) else { continue };
+    let mut pb = PathBuilder::new();
+    pb.move_to(x1, y1);
+    pb.line_to(x2, y2);
+    if let Some(path) = pb.finish() {
+      let stroke = Stroke {
+        width: j.line_width.unwrap_or(1.0).max(1.0),
+        line_cap: tiny_skia::LineCap::Round,
+        ..Stroke::default()
+      };
+      pixmap.stroke_path(&path, &paint(color), &stroke, base, None);
+    }
+  }
+
+  for g in &map.grounds {
+    draw_ground(&mut pixmap, g, base, opts.show_invisible);
+  }
+
+  for o in &map.shaman_objects {
+    if let Some(rect) = Rect::from_xywh(o.x - 8.0, o.y - 8.0, 16.0, 16.0) {
+      let alpha = if o.ghost { 0.4 } else { 0.85 };
+      let ts = base.pre_rotate_at(o.angle, o.x, o.y);
+      pixmap.fill_rect(rect, &paint(with_alpha(rgb(0xc8_c8_c8), alpha)), ts, None);
+    }
+  }
+
+  // marcadores com tamanho mínimo na tela, senão somem em miniaturas
+  let radius = (6.0 / scale).max(10.0);
+  for pt in &map.points {
+    let color = match pt.kind {
+      PointKind::MouseSpawn => rgb(0xff_ff_ff),
+      PointKind::ShamanSpawn => rgb(0x4f_a0_ff),
+      PointKind::Hole => rgb(0x22_22_22),
+      PointKind::Cheese => rgb(0xff_d2_3f),
+    };
+    draw_marker(&mut pixmap, pt.x, pt.y, radius, color, base);
+  }
+
+  Some(pixmap)
+}
+
+pub fn render_png(map: &MapXml, opts: &PreviewOptions) -> Result<Vec<u8>, String> {
+  let pixmap = render(map, opts).ok_or("invalid preview size")?;
+  pixmap.encode_png().map_err(|e| e.to_string())
+}
+
+/// PNGs em disco, um por (mapa, tamanho, conteúdo do XML), limitado a `max_bytes`.
+///
+/// O mtime marca o último uso (acerto no cache renova), e a limpeza apaga pelo mais antigo.
+pub struct PreviewCache {
+  dir: PathBuf,
+  max_bytes: u64,
+}
+
+impl PreviewCache {
+  pub fn new(dir: PathBuf, max_bytes: u64) -> Self {
+    Self { dir, max_bytes }
+  }
+
+  pub fn path_for(&self, map_id: i64, xml: &str, opts: &PreviewOptions) -> PathBuf {
+    let h = match opts.height {
+      Some(h) => h.to_string(),
+      None => "auto".into(),
+    };
+    let inv = if opts.show_invisible { "-inv" } else { "" };
+    self
+      .dir
+      .join(format!("{map_id}-{}x{h}{inv}-{:016x}.png", opts.width, fnv1a(xml.as_bytes())))
+  }
+
+  /// Devolve o PNG do cache ou renderiza e grava. `bool` = veio do cache.
+  pub fn get_or_render(
+    &self,
+    map_id: i64,
+    xml: &str,
+    map: impl FnOnce() -> Result<MapXml, String>,
+    opts: &PreviewOptions,
+  ) -> Result<(PathBuf, bool), String> {
+    let path = self.path_for(map_id, xml, opts);
+    if path.is_file() {
+      // falhar aqui só deixa o arquivo mais perto da limpeza
+      let _ = fs::File::options()
+        .write(true)
+        .open(&path)
+        .and_then(|f| f.set_modified(SystemTime::now()));
+      return Ok((path, true));
+    }
+    let png = render_png(&map()?, opts)?;
+    write_atomic(&path, &png).map_err(|e| e.to_string())?;
+    if let Err(e) = self.prune(&path) {
+      log::warn!("preview cache prune failed: {e}");
+    }
+    Ok((path, false))
+  }
+
+  /// Apaga os PNGs menos usados até caber em `max_bytes`; `keep` (o recém-gravado) fica.
+  fn prune(&self, keep: &Path) -> io::Result<()> {
+    let mut files = Vec::new();
+    let mut total = 0u64;
+    for entry in fs::read_dir(&self.dir)? {
+      let entry = entry?;
+      let path = entry.path();
+      if path.extension().and_then(|e| e.to_str()) != Some("png") {
+        continue;
+      }
+      let meta = entry.metadata()?;
+      total += meta.len();
+      if path != keep {
+        files.push((meta.modified().unwrap_or(SystemTime::UNIX_EPOCH), meta.len(), path));
+      }
+    }
+    files.sort();
+    for (_, len, path) in files {
+      if total <= self.max_bytes {
+        break;
+      }
+      fs::remove_file(&path)?;
+      total -= len;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::map_xml::parse_map_xml;
+
+  const MAP: &str = r#"<C><P L="1600" H="800" /><Z><S>
+    <S T="3" X="800" Y="790" L="1600" H="20" />
+    <S T="12" X="100" Y="100" L="100" H="100" o="00ff00" />
+    </S><D><F X="1500" Y="100" /></D></Z></C>"#;
+
+  fn pixel(p: &Pixmap, map_x: f32, map_y: f32, scale: f32) -> (u8, u8, u8) {
+    let c = p.pixel((map_x * scale) as u32, (map_y * scale) as u32).unwrap();
+    (c.red(), c.green(), c.blue())
+  }
+
+  #[test]
+  fn draws_grounds_and_points() {
+    let map = parse_map_xml(MAP).unwrap();
+    let p = render(&map, &PreviewOptions::default()).unwrap();
+    assert_eq!((p.width(), p.height()), (400, 200));
+    let scale = 0.25;
+    assert_eq!(pixel(&p, 800.0, 790.0, scale), (0xf0, 0x5a, 0x28));
+    assert_eq!(pixel(&p, 100.0, 100.0, scale), (0x00, 0xff, 0x00));
+    assert_eq!(pixel(&p, 1500.0, 100.0, scale), (0xff, 0xd2, 0x3f));
+    assert_eq!(pixel(&p, 800.0, 400.0, scale), (0x6a, 0x74, 0x95));
+
+    let png = render_png(&map, &PreviewOptions::default()).unwrap();
+    assert_eq!(&png[1..4], b"PNG");
+  }
+
+  #[test]
+  fn fixed_size_letterboxes() {
+    let map = parse_map_xml(MAP).unwrap();
+    let opts = PreviewOptions {
+      width: 100,
+      height: Some(100),
+      show_invisible: false,
+    };
+    let p = render(&map, &opts).unwrap();
+    assert_eq!((p.width(), p.height()), (100, 100));
+    // mapa 2:1 num quadrado: faixa de cima fica fora do mapa
+    let c = p.pixel(50, 5).unwrap();
+    assert_eq!((c.red(), c.green(), c.blue()), (0x20, 0x22, 0x2c));
+  }
+
+  #[test]
+  fn cache_roundtrip() {
+    let dir = tempfile::tempdir().unwrap();
+    let cache = PreviewCache::new(dir.path().to_path_buf(), DEFAULT_CACHE_BYTES);
+    let opts = PreviewOptions::default();
+
+    let (path, cached) = cache.get_or_render(7, MAP, || parse_map_xml(MAP).map_err(|e| e.to_string()), &opts).unwrap();
+    assert!(!cached && path.is_file());
+    let (again, cached) = cache.get_or_render(7, MAP, || panic!("should hit cache"), &opts).unwrap();
+    assert!(cached);
+    assert_eq!(path, again);
+
+    // XML diferente = outro arquivo
+    assert_ne!(cache.path_for(7, "<C/>", &opts), path);
+  }
+
+  #[test]
+  fn cache_evicts_least_recently_used() {
+    let dir = tempfile::tempdir().unwrap();
+    let opts = PreviewOptions::default();
+    let len = render_png(&parse_map_xml(MAP).unwrap(), &opts).unwrap().len() as u64;
+    // cabem dois PNGs iguais
+    let cache = PreviewCache::new(dir.path().to_path_buf(), 2 * len);
+    let render = |id: i64| {
+      cache
+        .get_or_render(id, MAP, || parse_map_xml(MAP).map_err(|e| e.to_string()), &opts)
+        .unwrap()
+        .0
+    };
+    let age = |path: &Path, secs: u64| {
+      let f = fs::File::options().write(true).open(path).unwrap();
+      f.set_modified(SystemTime::now() - std::time::Duration::from_secs(secs)).unwrap();
+    };
+
+    let a = render(1);
+    let b = render(2);
+    age(&a, 20);
+    age(&b, 10);
+    // acerto em `a` renova o uso: quem sai é `b`
+    assert!(cache.get_or_render(1, MAP, || panic!("should hit cache"), &opts).unwrap().1);
+    let c = render(3);
+    assert!(a.is_file() && c.is_file());
+    assert!(!b.is_file());
+  }
+}
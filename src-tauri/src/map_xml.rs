@@ -0,0 +1,602 @@
+// Parser do XML de mapa do Transformice para um modelo tipado.
+//
+// Estrutura: `<C><P .../><Z><S>grounds</S><D>pontos/decorações</D><O>objetos de shaman</O>
+// <L>joints</L></Z></C>`. O parser é tolerante: atributo numérico inválido vira o default do
+// jogo e gera um aviso em `warnings` (mapas antigos têm muito lixo).
+
+use std::collections::BTreeMap;
+
+pub const DEFAULT_WIDTH: f32 = 800.0;
+pub const DEFAULT_HEIGHT: f32 = 400.0;
+pub const DEFAULT_GRAVITY: f32 = 10.0;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MapXmlError {
+  Empty,
+  Xml(String),
+  /// Raiz diferente de `<C>`.
+  UnexpectedRoot(String),
+}
+
+impl std::fmt::Display for MapXmlError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      MapXmlError::Empty => write!(f, "empty map xml"),
+      MapXmlError::Xml(e) => write!(f, "invalid map xml: {e}"),
+      MapXmlError::UnexpectedRoot(name) => write!(f, "invalid map xml: root is <{name}>, expected <C>"),
+    }
+  }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MapProperties {
+  pub width: f32,
+  pub height: f32,
+  pub wind: f32,
+  pub gravity: f32,
+  /// `F`: fundo do mapa (id do jogo).
+  pub background: Option<i32>,
+  /// `N`: noite.
+  pub night: bool,
+  /// `C`: colisão entre ratos.
+  pub collision: bool,
+  /// `A`: soulmate.
+  pub soulmate: bool,
+  /// `defilante` (racing horizontal).
+  pub defilante: bool,
+  /// `mgoc`: multiplicador de objetos do shaman.
+  pub shaman_object_multiplier: Option<f32>,
+  /// Todos os atributos de `<P>`, inclusive os que não estão modelados.
+  pub attributes: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Serialize)]
+pub struct GroundType(pub u8);
+
+impl GroundType {
+  pub fn name(self) -> &'static str {
+    match self.0 {
+      0 => "wood",
+      1 => "ice",
+      2 => "trampoline",
+      3 => "lava",
+      4 => "chocolate",
+      5 => "earth",
+      6 => "grass",
+      7 => "sand",
+      8 => "cloud",
+      9 => "water",
+      10 => "stone",
+      11 => "snow",
+      12 => "rectangle",
+      13 => "circle",
+      14 => "invisible",
+      15 => "cobweb",
+      16 => "wood2",
+      17 => "grass2",
+      18 => "grass3",
+      19 => "acid",
+      _ => "unknown",
+    }
+  }
+
+  /// Retângulo/círculo coloridos (`o` define a cor).
+  pub fn is_colored(self) -> bool {
+    matches!(self.0, 12 | 13)
+  }
+
+  pub fn is_circle(self) -> bool {
+    self.0 == 13
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Ground {
+  /// Posição na lista `<S>` (é por ela que os joints referenciam).
+  pub index: usize,
+  pub ground_type: GroundType,
+  /// Centro.
+  pub x: f32,
+  pub y: f32,
+  pub width: f32,
+  pub height: f32,
+  pub dynamic: bool,
+  pub mass: f32,
+  pub friction: f32,
+  pub restitution: f32,
+  /// Graus.
+  pub angle: f32,
+  /// `o` (hex, sem `#`), só em retângulo/círculo.
+  pub color: Option<String>,
+  /// `c`: 1 = colide com tudo (default), 2 = só objetos, 3 = só ratos, 4 = nada.
+  pub collision: u8,
+  /// `N`: visível só no primeiro plano (não colide com ratos de fora).
+  pub foreground: bool,
+  /// `v`: some depois de N ms.
+  pub vanish_ms: Option<u32>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PointKind {
+  /// `DS`
+  MouseSpawn,
+  /// `DC` / `DC2`
+  ShamanSpawn,
+  /// `T`
+  Hole,
+  /// `F`
+  Cheese,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MapPoint {
+  pub kind: PointKind,
+  pub x: f32,
+  pub y: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Decoration {
+  /// `T`: id da decoração.
+  pub decoration_id: i32,
+  pub x: f32,
+  pub y: f32,
+  pub flipped: bool,
+  /// `C`: cores customizadas (hex, separadas por vírgula).
+  pub colors: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShamanObject {
+  /// `C`: código do objeto (0 = seta, 1/2 = caixas, ...).
+  pub code: i32,
+  pub x: f32,
+  pub y: f32,
+  pub angle: f32,
+  pub ghost: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JointKind {
+  /// `JD` (também usado como linha decorativa)
+  Distance,
+  /// `JR`
+  Revolute,
+  /// `JP`
+  Prismatic,
+  /// `JPL`
+  Pulley,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Joint {
+  pub kind: JointKind,
+  /// Índices em `grounds`; `None` = preso ao mundo.
+  pub ground1: Option<usize>,
+  pub ground2: Option<usize>,
+  pub point1: Option<(f32, f32)>,
+  pub point2: Option<(f32, f32)>,
+  /// Linha desenhada (`c="cor,largura,alpha,frente"`).
+  pub color: Option<String>,
+  pub line_width: Option<f32>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MapXml {
+  pub properties: MapProperties,
+  pub grounds: Vec<Ground>,
+  pub points: Vec<MapPoint>,
+  pub decorations: Vec<Decoration>,
+  pub shaman_objects: Vec<ShamanObject>,
+  pub joints: Vec<Joint>,
+  pub warnings: Vec<String>,
+}
+
+impl MapXml {
+  pub fn points_of(&self, kind: PointKind) -> impl Iterator<Item = &MapPoint> {
+    self.points.iter().filter(move |p| p.kind == kind)
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MapSummary {
+  pub width: f32,
+  pub height: f32,
+  pub wind: f32,
+  pub gravity: f32,
+  pub background: Option<i32>,
+  pub grounds: usize,
+  /// Por nome do tipo ("wood", "lava", ...).
+  pub grounds_by_type: BTreeMap<String, usize>,
+  pub dynamic_grounds: usize,
+  pub shaman_objects: usize,
+  pub decorations: usize,
+  pub joints: usize,
+  pub mouse_spawns: usize,
+  pub shaman_spawns: usize,
+  pub holes: usize,
+  pub cheeses: usize,
+  /// "night", "collision", "soulmate", "defilante".
+  pub flags: Vec<&'static str>,
+  pub warnings: Vec<String>,
+}
+
+impl MapXml {
+  pub fn summary(&self) -> MapSummary {
+    let p = &self.properties;
+    let mut grounds_by_type = BTreeMap::new();
+    for g in &self.grounds {
+      *grounds_by_type.entry(g.ground_type.name().to_string()).or_insert(0) += 1;
+    }
+    let flags = [
+      (p.night, "night"),
+      (p.collision, "collision"),
+      (p.soulmate, "soulmate"),
+      (p.defilante, "defilante"),
+    ]
+    .into_iter()
+    .filter_map(|(on, name)| on.then_some(name))
+    .collect();
+
+    MapSummary {
+      width: p.width,
+      height: p.height,
+      wind: p.wind,
+      gravity: p.gravity,
+      background: p.background,
+      grounds: self.grounds.len(),
+      grounds_by_type,
+      dynamic_grounds: self.grounds.iter().filter(|g| g.dynamic).count(),
+      shaman_objects: self.shaman_objects.len(),
+      decorations: self.decorations.len(),
+      joints: self.joints.len(),
+      mouse_spawns: self.points_of(PointKind::MouseSpawn).count(),
+      shaman_spawns: self.points_of(PointKind::ShamanSpawn).count(),
+      holes: self.points_of(PointKind::Hole).count(),
+      cheeses: self.points_of(PointKind::Cheese).count(),
+      flags,
+      warnings: self.warnings.clone(),
+    }
+  }
+}
+
+/// Lê atributos de um elemento acumulando avisos quando o valor não parseia.
+struct Attrs<'a, 'input> {
+  node: roxmltree::Node<'a, 'input>,
+  context: String,
+  warnings: &'a mut Vec<String>,
+}
+
+impl Attrs<'_, '_> {
+  fn raw(&self, name: &str) -> Option<&str> {
+    self.node.attribute(name).map(str::trim).filter(|v| !v.is_empty())
+  }
+
+  fn num<T: std::str::FromStr>(&mut self, name: &str, default: T) -> T {
+    match self.raw(name) {
+      None => default,
+      Some(v) => match v.parse::<T>() {
+        Ok(n) => n,
+        Err(_) => {
+          let msg = format!("{}: invalid {name}={v:?}", self.context);
+          self.warnings.push(msg);
+          default
+        }
+      },
+    }
+  }
+
+  fn opt_num<T: std::str::FromStr>(&mut self, name: &str) -> Option<T> {
+    let v = self.raw(name)?;
+    match v.parse::<T>() {
+      Ok(n) => Some(n),
+      Err(_) => {
+        let msg = format!("{}: invalid {name}={v:?}", self.context);
+        self.warnings.push(msg);
+        None
+      }
+    }
+  }
+
+  /// Atributo presente (qualquer valor diferente de "0"/"false") = ligado.
+  fn flag(&self, name: &str) -> bool {
+    match self.node.attribute(name) {
+      None => false,
+      Some(v) => !matches!(v.trim(), "0" | "false"),
+    }
+  }
+
+  /// Lista separada por vírgula, posição `i` (vazio = default).
+  fn list_num(&mut self, name: &str, i: usize, default: f32) -> f32 {
+    let Some(v) = self.raw(name) else { return default };
+    let Some(item) = v.split(',').nth(i).map(str::trim).filter(|s| !s.is_empty()) else {
+      return default;
+    };
+    match item.parse::<f32>() {
+      Ok(n) if n.is_finite() => n,
+      _ => {
+        let msg = format!("{}: invalid {name}[{i}]={item:?}", self.context);
+        self.warnings.push(msg);
+        default
+      }
+    }
+  }
+
+  fn point(&mut self, name: &str) -> Option<(f32, f32)> {
+    self.raw(name)?;
+    let x = self.list_num(name, 0, f32::NAN);
+    let y = self.list_num(name, 1, f32::NAN);
+    (x.is_finite() && y.is_finite()).then_some((x, y))
+  }
+}
+
+fn attrs<'a, 'input>(node: roxmltree::Node<'a, 'input>, context: String, warnings: &'a mut Vec<String>) -> Attrs<'a, 'input> {
+  Attrs { node, context, warnings }
+}
+
+fn child<'a, 'input>(node: roxmltree::Node<'a, 'input>, name: &str) -> Option<roxmltree::Node<'a, 'input>> {
+  node.children().find(|n| n.is_element() && n.tag_name().name() == name)
+}
+
+fn elements<'a, 'input>(node: Option<roxmltree::Node<'a, 'input>>) -> impl Iterator<Item = roxmltree::Node<'a, 'input>> {
+  node.into_iter().flat_map(|n| n.children().filter(|c| c.is_element()))
+}
+
+fn parse_properties(node: Option<roxmltree::Node>, warnings: &mut Vec<String>) -> MapProperties {
+  let Some(node) = node else {
+    return MapProperties {
+      width: DEFAULT_WIDTH,
+      height: DEFAULT_HEIGHT,
+      gravity: DEFAULT_GRAVITY,
+      ..MapProperties::default()
+    };
+  };
+  let mut a = attrs(node, "P".into(), warnings);
+  MapProperties {
+    width: a.num("L", DEFAULT_WIDTH),
+    height: a.num("H", DEFAULT_HEIGHT),
+    wind: a.list_num("G", 0, 0.0),
+    gravity: a.list_num("G", 1, DEFAULT_GRAVITY),
+    background: a.opt_num("F"),
+    night: a.flag("N"),
+    collision: a.flag("C"),
+    soulmate: a.flag("A"),
+    defilante: a.flag("defilante"),
+    shaman_object_multiplier: a.opt_num("mgoc"),
+    attributes: node
+      .attributes()
+      .map(|at| (at.name().to_string(), at.value().to_string()))
+      .collect(),
+  }
+}
+
+fn parse_ground(index: usize, node: roxmltree::Node, warnings: &mut Vec<String>) -> Ground {
+  let mut a = attrs(node, format!("S[{index}]"), warnings);
+  let ground_type = GroundType(a.num("T", 0));
+  // P="dinâmico,massa,atrito,restituição,ângulo,..."
+  Ground {
+    index,
+    ground_type,
+    x: a.num("X", 0.0),
+    y: a.num("Y", 0.0),
+    width: a.num("L", 10.0),
+    height: a.num("H", 10.0),
+    dynamic: a.list_num("P", 0, 0.0) != 0.0,
+    mass: a.list_num("P", 1, 0.0),
+    friction: a.list_num("P", 2, 0.3),
+    restitution: a.list_num("P", 3, 0.2),
+    angle: a.list_num("P", 4, 0.0),
+    color: a
+      .raw("o")
+      .filter(|_| ground_type.is_colored())
+      .map(|c| c.trim_start_matches('#').to_ascii_lowercase()),
+    collision: a.num("c", 1),
+    foreground: a.flag("N"),
+    vanish_ms: a.opt_num("v"),
+  }
+}
+
+fn parse_joint(kind: JointKind, i: usize, node: roxmltree::Node, warnings: &mut Vec<String>) -> Joint {
+  let mut a = attrs(node, format!("L[{i}]"), warnings);
+  let line = a.raw("c").map(str::to_string);
+  Joint {
+    kind,
+    ground1: a.opt_num("M1"),
+    ground2: a.opt_num("M2"),
+    point1: a.point("P1"),
+    point2: a.point("P2"),
+    color: line
+      .as_deref()
+      .and_then(|c| c.split(',').next())
+      .map(|c| c.trim().trim_start_matches('#').to_ascii_lowercase())
+      .filter(|c| !c.is_empty()),
+    line_width: line.as_ref().map(|_| a.list_num("c", 1, 1.0)),
+  }
+}
+
+pub fn parse_map_xml(xml: &str) -> Result<MapXml, MapXmlError> {
+  let xml = xml.trim();
+  if xml.is_empty() {
+    return Err(MapXmlError::Empty);
+  }
+  let doc = roxmltree::Document::parse(xml).map_err(|e| MapXmlError::Xml(e.to_string()))?;
+  let root = doc.root_element();
+  if root.tag_name().name() != "C" {
+    return Err(MapXmlError::UnexpectedRoot(root.tag_name().name().to_string()));
+  }
+
+  let mut warnings = Vec::new();
+  let properties = parse_properties(child(root, "P"), &mut warnings);
+  let z = child(root, "Z");
+
+  let grounds = elements(z.and_then(|z| child(z, "S")))
+    .filter(|n| n.tag_name().name() == "S")
+    .enumerate()
+    .map(|(i, n)| parse_ground(i, n, &mut warnings))
+    .collect();
+
+  let mut points = Vec::new();
+  let mut decorations = Vec::new();
+  for (i, n) in elements(z.and_then(|z| child(z, "D"))).enumerate() {
+    let name = n.tag_name().name();
+    let mut a = attrs(n, format!("D[{i}]"), &mut warnings);
+    let kind = match name {
+      "DS" => PointKind::MouseSpawn,
+      "DC" | "DC2" => PointKind::ShamanSpawn,
+      "T" => PointKind::Hole,
+      "F" => PointKind::Cheese,
+      "P" => {
+        decorations.push(Decoration {
+          decoration_id: a.num("T", 0),
+          x: a.num("X", 0.0),
+          y: a.num("Y", 0.0),
+          flipped: a.list_num("P", 1, 0.0) != 0.0,
+          colors: a.raw("C").map(str::to_string),
+        });
+        continue;
+      }
+      other => {
+        warnings.push(format!("D[{i}]: unknown element <{other}>"));
+        continue;
+      }
+    };
+    points.push(MapPoint {
+      kind,
+      x: a.num("X", 0.0),
+      y: a.num("Y", 0.0),
+    });
+  }
+
+  let shaman_objects = elements(z.and_then(|z| child(z, "O")))
+    .enumerate()
+    .map(|(i, n)| {
+      let mut a = attrs(n, format!("O[{i}]"), &mut warnings);
+      ShamanObject {
+        code: a.num("C", 0),
+        x: a.num("X", 0.0),
+        y: a.num("Y", 0.0),
+        angle: a.list_num("P", 0, 0.0),
+        ghost: a.list_num("P", 1, 0.0) != 0.0,
+      }
+    })
+    .collect();
+
+  let mut joints = Vec::new();
+  for (i, n) in elements(z.and_then(|z| child(z, "L"))).enumerate() {
+    let kind = match n.tag_name().name() {
+      "JD" => JointKind::Distance,
+      "JR" => JointKind::Revolute,
+      "JP" => JointKind::Prismatic,
+      "JPL" => JointKind::Pulley,
+      other => {
+        warnings.push(format!("L[{i}]: unknown joint <{other}>"));
+        continue;
+      }
+    };
+    joints.push(parse_joint(kind, i, n, &mut warnings));
+  }
+
+  Ok(MapXml {
+    properties,
+    grounds,
+    points,
+    decorations,
+    shaman_objects,
+    joints,
+    warnings,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const SAMPLE: &str = r##"<C><P L="1600" G="2,12" F="7" N="" defilante="0,0,0,1" mgoc="50" /><Z><S>
+    <S T="6" X="800" Y="390" L="1600" H="20" P="0,0,0.3,0.2,0,0,0,0" />
+    <S T="12" X="300" Y="200" L="60" H="10" P="1,5,0.3,0.2,45,0,0,0" o="#FF00AA" c="3" />
+    <S T="3" X="x" Y="100" L="40" H="40" />
+    </S><D><DS X="50" Y="360" /><DC X="60" Y="350" /><T X="1500" Y="370" /><F X="800" Y="100" />
+    <P X="100" Y="380" T="12" P="0,1" /><Q /></D>
+    <O><O X="200" Y="100" C="22" P="90,1" /></O>
+    <L><JR M1="1" M2="0" P1="300,200" /><JD c="ffffff,3,1,0" P1="0,0" P2="10,10" /><JX /></L></Z></C>"##;
+
+  #[test]
+  fn parses_sample_map() {
+    let map = parse_map_xml(SAMPLE).unwrap();
+    let p = &map.properties;
+    assert_eq!((p.width, p.height, p.wind, p.gravity), (1600.0, 400.0, 2.0, 12.0));
+    assert_eq!(p.background, Some(7));
+    assert!(p.night && p.defilante && !p.collision && !p.soulmate);
+    assert_eq!(p.shaman_object_multiplier, Some(50.0));
+    assert_eq!(p.attributes.get("L").map(String::as_str), Some("1600"));
+
+    assert_eq!(map.grounds.len(), 3);
+    let g = &map.grounds[1];
+    assert_eq!(g.index, 1);
+    assert_eq!(g.ground_type.name(), "rectangle");
+    assert!(g.dynamic);
+    assert_eq!((g.mass, g.angle), (5.0, 45.0));
+    assert_eq!(g.color.as_deref(), Some("ff00aa"));
+    assert_eq!(g.collision, 3);
+    assert_eq!(map.grounds[2].ground_type.name(), "lava");
+
+    assert_eq!(map.points_of(PointKind::MouseSpawn).count(), 1);
+    assert_eq!(map.points_of(PointKind::Hole).next().map(|p| (p.x, p.y)), Some((1500.0, 370.0)));
+    assert_eq!(map.decorations.len(), 1);
+    assert!(map.decorations[0].flipped);
+    assert_eq!(map.shaman_objects[0].code, 22);
+    assert!(map.shaman_objects[0].ghost);
+
+    assert_eq!(map.joints.len(), 2);
+    assert_eq!(map.joints[0].kind, JointKind::Revolute);
+    assert_eq!((map.joints[0].ground1, map.joints[0].ground2), (Some(1), Some(0)));
+    assert_eq!(map.joints[1].color.as_deref(), Some("ffffff"));
+    assert_eq!(map.joints[1].line_width, Some(3.0));
+    assert_eq!(map.joints[1].point2, Some((10.0, 10.0)));
+
+    assert_eq!(
+      map.warnings,
+      vec![
+        "S[2]: invalid X=\"x\"".to_string(),
+        "D[5]: unknown element <Q>".to_string(),
+        "L[2]: unknown joint <JX>".to_string(),
+      ]
+    );
+  }
+
+  #[test]
+  fn summary_counts() {
+    let s = parse_map_xml(SAMPLE).unwrap().summary();
+    assert_eq!(s.grounds, 3);
+    assert_eq!(s.grounds_by_type.get("grass"), Some(&1));
+    assert_eq!(s.grounds_by_type.get("lava"), Some(&1));
+    assert_eq!(s.dynamic_grounds, 1);
+    assert_eq!((s.mouse_spawns, s.shaman_spawns, s.holes, s.cheeses), (1, 1, 1, 1));
+    assert_eq!(s.flags, vec!["night", "defilante"]);
+  }
+
+  #[test]
+  fn minimal_and_invalid() {
+    let map = parse_map_xml("<C><P /><Z><S /><D /><O /></Z></C>").unwrap();
+    assert_eq!(map.properties.width, DEFAULT_WIDTH);
+    assert_eq!(map.properties.gravity, DEFAULT_GRAVITY);
+    assert!(map.grounds.is_empty() && map.warnings.is_empty());
+
+    // sem <P> nem <Z> ainda é um mapa (vazio)
+    assert_eq!(parse_map_xml("<C/>").unwrap().properties.height, DEFAULT_HEIGHT);
+
+    assert_eq!(parse_map_xml("  "), Err(MapXmlError::Empty));
+    assert_eq!(parse_map_xml("<X/>"), Err(MapXmlError::UnexpectedRoot("X".into())));
+    assert!(matches!(parse_map_xml("<C><P></C>"), Err(MapXmlError::Xml(_))));
+  }
+}
@@ -0,0 +1,285 @@
+// Extração de mapcodes de texto livre (mesmas regras de `src/app/mapcodes.ts`) e validação
+// estrita dos códigos reais do jogo (`@123456`) antes de mandar qualquer comando.
+
+use std::fmt;
+
+// @1 .. @999999999 (os mapas atuais estão na casa dos 7 dígitos)
+const MAX_MAPCODE_DIGITS: usize = 9;
+
+fn strip_wrapping_quotes(s: &str) -> &str {
+  let t = s.trim();
+  if t.len() >= 2 && ((t.starts_with('"') && t.ends_with('"')) || (t.starts_with('\'') && t.ends_with('\''))) {
+    return t[1..t.len() - 1].trim();
+  }
+  t
+}
+
+fn strip_common_punctuation(s: &str) -> &str {
+  // remove wrappers comuns (CSV / logs)
+  let t = s.trim();
+  let t = t.trim_start_matches(['<', '(', '[']);
+  let t = t.trim_end_matches(['>', ']', ')']);
+  // remove pontuação no fim (",", ".", etc)
+  let t = t.trim_end_matches(['.', ',', ';', ':', '!', '?']);
+  t.trim()
+}
+
+fn is_mapcode_token(t: &str) -> bool {
+  (2..=64).contains(&t.len()) && t.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-')
+}
+
+pub fn normalize_mapcode(raw: &str) -> Option<String> {
+  let t = raw.trim();
+  if t.is_empty() {
+    return None;
+  }
+
+  let t = strip_common_punctuation(strip_wrapping_quotes(t));
+  if t.is_empty() {
+    return None;
+  }
+
+  // aceita copiar do chat com @mapcode
+  let t = t.trim_start_matches('@');
+
+  // se veio com texto extra, pega só o 1º token
+  let t = t.split_whitespace().next().unwrap_or("").trim();
+  if t.is_empty() {
+    return None;
+  }
+
+  // evita lixo óbvio (mantém flexível o suficiente para tokens comuns)
+  if !is_mapcode_token(t) {
+    return None;
+  }
+
+  Some(t.to_string())
+}
+
+pub fn parse_mapcodes_from_text(text: &str) -> Vec<String> {
+  let normalized = text.replace("\r\n", "\n").replace('\r', "\n");
+  let mut out = Vec::new();
+
+  for line in normalized.split('\n') {
+    let trimmed = line.trim();
+    if trimmed.is_empty() {
+      continue;
+    }
+
+    // CSV: separadores comuns (vírgula / ponto-vírgula / TAB)
+    let tokens = trimmed
+      .split([',', '\t', ';'])
+      .flat_map(|chunk| chunk.split_whitespace());
+
+    let has_at = trimmed.contains('@');
+    for tok in tokens {
+      if has_at && !tok.contains('@') {
+        continue;
+      }
+
+      let Some(mc) = normalize_mapcode(tok) else { continue };
+
+      // quando não há "@", reduz falso-positivo (ex.: header CSV)
+      if !has_at && !mc.bytes().any(|b| b.is_ascii_digit()) {
+        continue;
+      }
+
+      out.push(mc);
+    }
+  }
+
+  out
+}
+
+pub fn uniq_preserve_order(items: Vec<String>) -> Vec<String> {
+  let mut seen = std::collections::HashSet::new();
+  items.into_iter().filter(|it| seen.insert(it.clone())).collect()
+}
+
+/// Código de mapa válido no jogo (`@<id>`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MapCode(u64);
+
+impl MapCode {
+  pub fn id(self) -> u64 {
+    self.0
+  }
+}
+
+impl fmt::Display for MapCode {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "@{}", self.0)
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MapcodeError {
+  Empty,
+  NotNumeric(String),
+  LeadingZero(String),
+  TooLong(String),
+}
+
+impl fmt::Display for MapcodeError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      MapcodeError::Empty => write!(f, "empty mapcode"),
+      MapcodeError::NotNumeric(raw) => write!(f, "invalid mapcode (not numeric): {raw}"),
+      MapcodeError::LeadingZero(raw) => write!(f, "invalid mapcode (leading zero): {raw}"),
+      MapcodeError::TooLong(raw) => write!(f, "invalid mapcode (too long): {raw}"),
+    }
+  }
+}
+
+/// Validação estrita: `@123456` ou `123456`, só dígitos, sem zero à esquerda.
+pub fn parse_map_code(raw: &str) -> Result<MapCode, MapcodeError> {
+  let t = raw.trim();
+  let digits = t.strip_prefix('@').unwrap_or(t);
+  if digits.is_empty() {
+    return Err(MapcodeError::Empty);
+  }
+  if !digits.bytes().all(|b| b.is_ascii_digit()) {
+    return Err(MapcodeError::NotNumeric(t.to_string()));
+  }
+  if digits.starts_with('0') {
+    return Err(MapcodeError::LeadingZero(t.to_string()));
+  }
+  if digits.len() > MAX_MAPCODE_DIGITS {
+    return Err(MapcodeError::TooLong(t.to_string()));
+  }
+  digits
+    .parse::<u64>()
+    .map(MapCode)
+    .map_err(|_| MapcodeError::NotNumeric(t.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use proptest::prelude::*;
+
+  #[test]
+  fn normalize_table() {
+    let cases: &[(&str, Option<&str>)] = &[
+      ("", None),
+      ("   ", None),
+      ("@123456", Some("123456")),
+      ("@@123456", Some("123456")),
+      ("  @7654321  ", Some("7654321")),
+      ("\"@123456\"", Some("123456")),
+      ("'123456'", Some("123456")),
+      ("<@123456>", Some("123456")),
+      ("(@123456)", Some("123456")),
+      // igual ao TS: o wrapper é removido antes da pontuação final
+      ("(@123456),", None),
+      ("[123456]", Some("123456")),
+      ("@123456.", Some("123456")),
+      ("@123456!?", Some("123456")),
+      ("@123456 p17 pls", Some("123456")),
+      ("abc_DEF-1", Some("abc_DEF-1")),
+      ("a", None),
+      ("@", None),
+      ("12345#", None),
+      ("ção1", None),
+      (&"9".repeat(64), Some(&"9".repeat(64))),
+    ];
+    for (raw, expected) in cases {
+      assert_eq!(normalize_mapcode(raw).as_deref(), *expected, "input: {raw:?}");
+    }
+    // 65 chars já é lixo
+    assert_eq!(normalize_mapcode(&"9".repeat(65)), None);
+  }
+
+  #[test]
+  fn parse_text_table() {
+    let cases: &[(&str, &[&str])] = &[
+      ("", &[]),
+      ("@123456", &["123456"]),
+      ("@12, @34\t@56; @78", &["12", "34", "56", "78"]),
+      // tokens de 1 char não passam no filtro de tamanho
+      ("@1, @2", &[]),
+      ("@11 @22\r\n@33\r@44", &["11", "22", "33", "44"]),
+      // com "@" na linha, só tokens com "@" contam
+      ("[Author] @1234567 p17 pls", &["1234567"]),
+      ("map @123456 and 999999", &["123456"]),
+      // sem "@", exige dígito (evita header CSV)
+      ("mapcode,submitter\n123456,Someone\n7654321,Other", &["123456", "7654321"]),
+      ("123456\t654321;111111", &["123456", "654321", "111111"]),
+      // duplicados são mantidos (dedupe é à parte)
+      ("@123456 @123456", &["123456", "123456"]),
+    ];
+    for (text, expected) in cases {
+      let expected: Vec<String> = expected.iter().map(|s| s.to_string()).collect();
+      assert_eq!(parse_mapcodes_from_text(text), expected, "input: {text:?}");
+    }
+  }
+
+  #[test]
+  fn strict_table() {
+    let cases: &[(&str, Result<u64, MapcodeError>)] = &[
+      ("@123456", Ok(123456)),
+      ("123456", Ok(123456)),
+      ("  @7 ", Ok(7)),
+      ("@999999999", Ok(999_999_999)),
+      ("", Err(MapcodeError::Empty)),
+      ("@", Err(MapcodeError::Empty)),
+      ("@@123", Err(MapcodeError::NotNumeric("@@123".into()))),
+      ("@12a", Err(MapcodeError::NotNumeric("@12a".into()))),
+      ("@-12", Err(MapcodeError::NotNumeric("@-12".into()))),
+      ("@12 34", Err(MapcodeError::NotNumeric("@12 34".into()))),
+      ("@0123", Err(MapcodeError::LeadingZero("@0123".into()))),
+      ("@0", Err(MapcodeError::LeadingZero("@0".into()))),
+      ("@1000000000", Err(MapcodeError::TooLong("@1000000000".into()))),
+    ];
+    for (raw, expected) in cases {
+      assert_eq!(parse_map_code(raw).map(MapCode::id), *expected, "input: {raw:?}");
+    }
+    assert_eq!(parse_map_code("123").unwrap().to_string(), "@123");
+  }
+
+  #[test]
+  fn uniq_keeps_first_occurrence() {
+    let items = ["3", "1", "3", "2", "1"].iter().map(|s| s.to_string()).collect();
+    assert_eq!(uniq_preserve_order(items), vec!["3", "1", "2"]);
+  }
+
+  proptest! {
+    #[test]
+    fn normalize_is_idempotent(raw in "\\PC{0,80}") {
+      if let Some(mc) = normalize_mapcode(&raw) {
+        prop_assert_eq!(normalize_mapcode(&mc), Some(mc.clone()));
+        prop_assert!(is_mapcode_token(&mc));
+      }
+    }
+
+    #[test]
+    fn parsed_tokens_are_valid(text in "[@a-z0-9 ,;\t\n_\\-]{0,200}") {
+      for mc in parse_mapcodes_from_text(&text) {
+        prop_assert!(is_mapcode_token(&mc), "{:?}", mc);
+        prop_assert!(!mc.contains('@'));
+      }
+    }
+
+    #[test]
+    fn strict_roundtrip(id in 1u64..=999_999_999) {
+      let code = parse_map_code(&format!("@{id}")).unwrap();
+      prop_assert_eq!(code.id(), id);
+      prop_assert_eq!(parse_map_code(&code.to_string()), Ok(code));
+      prop_assert_eq!(parse_map_code(&id.to_string()), Ok(code));
+    }
+
+    #[test]
+    fn strict_accepts_only_digits(raw in "\\PC{0,20}") {
+      if let Ok(code) = parse_map_code(&raw) {
+        let t = raw.trim();
+        prop_assert_eq!(t.strip_prefix('@').unwrap_or(t), code.id().to_string());
+      }
+    }
+
+    #[test]
+    fn chat_lines_yield_their_code(id in 1u64..=9_999_999, author in "[A-Za-z][A-Za-z0-9_]{2,15}") {
+      let line = format!("[{author}] @{id} p17 pls");
+      prop_assert_eq!(parse_mapcodes_from_text(&line), vec![id.to_string()]);
+    }
+  }
+}
@@ -0,0 +1,426 @@
+// Estado do mass perm (lista de mapas → `/p N @code` um por um).
+//
+// Só a máquina de estados: quem digita na janela e agenda os ticks é o `lib.rs`. Cada ação
+// devolve o `Step` a enviar (se houver) já com o progresso atualizado — um envio que falha
+// não volta o índice, igual ao fluxo antigo da webview.
+
+use std::{fs, io, path::Path};
+
+use crate::atomic_file::write_json_atomic;
+
+pub const DEFAULT_INTERVAL_MS: u64 = 300;
+pub const MIN_INTERVAL_MS: u64 = 100;
+pub const MAX_INTERVAL_MS: u64 = 5_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RunStatus {
+  /// Nada carregado ou ainda não começou.
+  Idle,
+  Running,
+  Paused,
+  Done,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Action {
+  /// Play/pause.
+  Toggle,
+  Pause,
+  /// Reenvia o mapa atual sem avançar.
+  PlayCurrent,
+  /// Envia o atual e avança.
+  Next,
+  /// Volta um e reenvia (só pausado).
+  Prev,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+  pub index: usize,
+  pub mapcode: String,
+  /// "auto" | "manual" | "current" (vai na mensagem de status)
+  pub source: &'static str,
+}
+
+/// O que é salvo em disco para retomar depois.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Progress {
+  pub mapcodes: Vec<String>,
+  pub category: i32,
+  /// Próximo mapa a enviar.
+  pub index: usize,
+  pub last_sent: Option<usize>,
+  pub interval_ms: u64,
+  /// Manda `/np` antes do `/p` (para ver o mapa enquanto perma).
+  pub play_first: bool,
+}
+
+impl Default for Progress {
+  fn default() -> Self {
+    Self {
+      mapcodes: vec![],
+      category: 4,
+      index: 0,
+      last_sent: None,
+      interval_ms: DEFAULT_INTERVAL_MS,
+      play_first: false,
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snapshot {
+  pub status: RunStatus,
+  pub mapcodes: Vec<String>,
+  pub category: i32,
+  pub index: usize,
+  pub total: usize,
+  pub last_sent: Option<usize>,
+  pub current: Option<String>,
+  pub interval_ms: u64,
+  pub play_first: bool,
+  pub last_command: Option<String>,
+  /// Última mensagem para a barra de status ("auto: sent /p 4 @1", "Paused.", erro...).
+  pub message: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct Runner {
+  progress: Progress,
+  status: RunStatus,
+  last_command: Option<String>,
+  message: Option<String>,
+}
+
+impl Default for Runner {
+  fn default() -> Self {
+    Self::from_progress(Progress::default())
+  }
+}
+
+fn clamp_interval(ms: u64) -> u64 {
+  ms.clamp(MIN_INTERVAL_MS, MAX_INTERVAL_MS)
+}
+
+impl Runner {
+  /// Retomado do disco nunca volta rodando.
+  pub fn from_progress(mut progress: Progress) -> Self {
+    progress.interval_ms = clamp_interval(progress.interval_ms);
+    progress.index = progress.index.min(progress.mapcodes.len());
+    let status = if progress.mapcodes.is_empty() {
+      RunStatus::Idle
+    } else if progress.index >= progress.mapcodes.len() {
+      RunStatus::Done
+    } else if progress.index > 0 || progress.last_sent.is_some() {
+      RunStatus::Paused
+    } else {
+      RunStatus::Idle
+    };
+    Self {
+      progress,
+      status,
+      last_command: None,
+      message: None,
+    }
+  }
+
+  pub fn load_file(path: &Path) -> Self {
+    let progress = fs::read(path)
+      .ok()
+      .and_then(|raw| serde_json::from_slice(&raw).ok())
+      .unwrap_or_default();
+    Self::from_progress(progress)
+  }
+
+  pub fn save_file(&self, path: &Path) -> io::Result<()> {
+    if self.progress.mapcodes.is_empty() {
+      return match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+      };
+    }
+    write_json_atomic(path, &self.progress)
+  }
+
+  pub fn status(&self) -> RunStatus {
+    self.status
+  }
+
+  pub fn is_loaded(&self) -> bool {
+    !self.progress.mapcodes.is_empty()
+  }
+
+  pub fn category(&self) -> i32 {
+    self.progress.category
+  }
+
+  pub fn play_first(&self) -> bool {
+    self.progress.play_first
+  }
+
+  pub fn interval_ms(&self) -> u64 {
+    self.progress.interval_ms
+  }
+
+  /// Nova lista: zera o progresso.
+  pub fn load(&mut self, mapcodes: Vec<String>) {
+    let n = mapcodes.len();
+    self.progress = Progress {
+      mapcodes,
+      index: 0,
+      last_sent: None,
+      ..self.progress.clone()
+    };
+    self.status = RunStatus::Idle;
+    self.last_command = None;
+    self.message = Some(format!("Loaded {n} map(s)."));
+  }
+
+  pub fn clear(&mut self) {
+    self.load(vec![]);
+    self.message = Some("Cleared.".into());
+  }
+
+  /// Vale também rodando (o intervalo novo entra no próximo tick).
+  pub fn configure(&mut self, category: Option<i32>, interval_ms: Option<u64>, play_first: Option<bool>) {
+    if let Some(c) = category {
+      self.progress.category = c;
+    }
+    if let Some(ms) = interval_ms {
+      self.progress.interval_ms = clamp_interval(ms);
+    }
+    if let Some(p) = play_first {
+      self.progress.play_first = p;
+    }
+  }
+
+  /// Escolhe o mapa atual na lista (só parado).
+  pub fn seek(&mut self, index: usize) -> Result<(), String> {
+    if self.status == RunStatus::Running {
+      return Err("Pause first to change the current map.".into());
+    }
+    if !self.is_loaded() {
+      return Err("No maps loaded.".into());
+    }
+    self.progress.index = index.min(self.progress.mapcodes.len() - 1);
+    self.status = RunStatus::Paused;
+    Ok(())
+  }
+
+  fn step_at(&mut self, index: usize, source: &'static str) -> Step {
+    self.progress.last_sent = Some(index);
+    Step {
+      index,
+      mapcode: self.progress.mapcodes[index].clone(),
+      source,
+    }
+  }
+
+  fn finish_if_at_end(&mut self) {
+    if self.progress.index >= self.progress.mapcodes.len() {
+      self.status = RunStatus::Done;
+      self.message = Some("Done.".into());
+    }
+  }
+
+  /// Aplica a ação; erro = nada a fazer (mensagem para o status).
+  pub fn apply(&mut self, action: Action) -> Result<Option<Step>, String> {
+    let len = self.progress.mapcodes.len();
+    let index = self.progress.index;
+    match action {
+      Action::Toggle if self.status == RunStatus::Running => self.apply(Action::Pause),
+      Action::Toggle => {
+        if len == 0 {
+          return Err("No maps loaded.".into());
+        }
+        if index >= len {
+          return Err("Already finished. Load a new list or clear.".into());
+        }
+        self.status = RunStatus::Running;
+        self.message = Some("Running… focus the game window.".into());
+        Ok(None)
+      }
+      Action::Pause => {
+        if self.status == RunStatus::Running {
+          self.status = RunStatus::Paused;
+          self.message = Some("Paused.".into());
+        }
+        Ok(None)
+      }
+      Action::PlayCurrent => {
+        if index >= len {
+          return Err("No current map selected.".into());
+        }
+        Ok(Some(self.step_at(index, "current")))
+      }
+      Action::Next => {
+        if len == 0 {
+          return Err("No maps loaded.".into());
+        }
+        if index >= len {
+          return Err("Done.".into());
+        }
+        let step = self.step_at(index, "manual");
+        self.progress.index += 1;
+        if self.status != RunStatus::Running {
+          self.status = RunStatus::Paused;
+          self.finish_if_at_end();
+        }
+        Ok(Some(step))
+      }
+      Action::Prev => {
+        if self.status == RunStatus::Running {
+          return Err("Pause first to go back.".into());
+        }
+        if index == 0 || len == 0 {
+          return Err("Already at the first map.".into());
+        }
+        self.progress.index = index - 1;
+        self.status = RunStatus::Paused;
+        Ok(Some(self.step_at(index - 1, "manual")))
+      }
+    }
+  }
+
+  /// Um passo do modo automático; `None` quando não está rodando (ou acabou).
+  pub fn tick(&mut self) -> Option<Step> {
+    if self.status != RunStatus::Running {
+      return None;
+    }
+    if self.progress.index >= self.progress.mapcodes.len() {
+      self.finish_if_at_end();
+      return None;
+    }
+    let step = self.step_at(self.progress.index, "auto");
+    self.progress.index += 1;
+    Some(step)
+  }
+
+  /// Resultado do envio de um `Step` (comando enviado ou erro).
+  pub fn record(&mut self, step: &Step, result: Result<String, String>) {
+    self.message = Some(match &result {
+      Ok(cmd) => format!("{}: sent {cmd}", step.source),
+      Err(e) => format!("{}: failed ({e})", step.source),
+    });
+    if let Ok(cmd) = result {
+      self.last_command = Some(cmd);
+    }
+    if self.status == RunStatus::Running {
+      self.finish_if_at_end();
+    }
+  }
+
+  pub fn set_message(&mut self, message: impl Into<String>) {
+    self.message = Some(message.into());
+  }
+
+  pub fn snapshot(&self) -> Snapshot {
+    let p = &self.progress;
+    Snapshot {
+      status: self.status,
+      mapcodes: p.mapcodes.clone(),
+      category: p.category,
+      index: p.index,
+      total: p.mapcodes.len(),
+      last_sent: p.last_sent,
+      current: p.mapcodes.get(p.index).cloned(),
+      interval_ms: p.interval_ms,
+      play_first: p.play_first,
+      last_command: self.last_command.clone(),
+      message: self.message.clone(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn runner(n: usize) -> Runner {
+    let mut r = Runner::default();
+    r.load((1..=n).map(|i| format!("@{i}")).collect());
+    r
+  }
+
+  fn sent(step: Option<Step>) -> Option<(usize, &'static str)> {
+    step.map(|s| (s.index, s.source))
+  }
+
+  #[test]
+  fn auto_run_walks_the_list() {
+    let mut r = runner(3);
+    assert_eq!(r.tick(), None, "idle does not tick");
+    assert_eq!(r.apply(Action::Toggle), Ok(None));
+    assert_eq!(r.status(), RunStatus::Running);
+
+    assert_eq!(sent(r.tick()), Some((0, "auto")));
+    // pausa no meio e retoma
+    r.apply(Action::Toggle).unwrap();
+    assert_eq!(r.tick(), None);
+    assert_eq!(r.apply(Action::Prev).map(sent), Ok(Some((0, "manual"))));
+    r.apply(Action::Toggle).unwrap();
+    for i in 0..3 {
+      let step = r.tick().unwrap();
+      assert_eq!(step.index, i);
+      r.record(&step, Ok(format!("/p 4 {}", step.mapcode)));
+    }
+    assert_eq!(r.status(), RunStatus::Done);
+    assert_eq!(r.tick(), None);
+
+    let snap = r.snapshot();
+    assert_eq!((snap.index, snap.total, snap.last_sent), (3, 3, Some(2)));
+    assert_eq!(snap.last_command.as_deref(), Some("/p 4 @3"));
+    assert_eq!(snap.message.as_deref(), Some("Done."));
+    assert!(r.apply(Action::Toggle).is_err());
+  }
+
+  #[test]
+  fn manual_actions() {
+    let mut r = runner(2);
+    assert_eq!(r.apply(Action::Prev), Err("Already at the first map.".into()));
+    assert_eq!(r.apply(Action::PlayCurrent).map(sent), Ok(Some((0, "current"))));
+    assert_eq!(r.snapshot().index, 0);
+    assert_eq!(r.apply(Action::Next).map(sent), Ok(Some((0, "manual"))));
+    assert_eq!(r.apply(Action::Next).map(sent), Ok(Some((1, "manual"))));
+    assert_eq!(r.status(), RunStatus::Done);
+    assert_eq!(r.apply(Action::Next), Err("Done.".into()));
+
+    r.seek(0).unwrap();
+    assert_eq!(r.status(), RunStatus::Paused);
+    r.apply(Action::Toggle).unwrap();
+    assert!(r.seek(1).is_err());
+    assert_eq!(r.apply(Action::Prev), Err("Pause first to go back.".into()));
+
+    // falha no envio não volta o índice
+    let step = r.tick().unwrap();
+    r.record(&step, Err("no window".into()));
+    assert_eq!(r.snapshot().message.as_deref(), Some("auto: failed (no window)"));
+    assert_eq!(r.snapshot().index, 1);
+  }
+
+  #[test]
+  fn progress_persists_and_resumes_paused() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("massperm.json");
+    let mut r = runner(3);
+    r.configure(Some(17), Some(20), Some(true));
+    r.apply(Action::Toggle).unwrap();
+    r.tick();
+    r.save_file(&path).unwrap();
+
+    let back = Runner::load_file(&path);
+    assert_eq!(back.status(), RunStatus::Paused);
+    assert_eq!((back.category(), back.interval_ms(), back.play_first()), (17, MIN_INTERVAL_MS, true));
+    assert_eq!(back.snapshot().current.as_deref(), Some("@2"));
+
+    // lista vazia apaga o arquivo
+    r.clear();
+    r.save_file(&path).unwrap();
+    assert!(!path.exists());
+    assert_eq!(Runner::load_file(&path).status(), RunStatus::Idle);
+  }
+}
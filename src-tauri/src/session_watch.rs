@@ -0,0 +1,122 @@
+// Categorias vigiadas pelo poller da Session API e o último lote visto de cada uma.
+//
+// Lote novo = `thread_id`/`collected_at` diferente do último visto. A primeira leitura de uma
+// categoria só guarda a referência (não avisa no startup). Abrir a sessão à mão também conta
+// como visto. O laço e a notificação ficam no `lib.rs`.
+
+use std::{collections::BTreeMap, fs, io, path::Path, time::Duration};
+
+use crate::atomic_file::write_json_atomic;
+
+pub const DEFAULT_INTERVAL_SECS: u64 = 300;
+pub const MIN_INTERVAL_SECS: u64 = 60;
+pub const MAX_INTERVAL_SECS: u64 = 3600;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Seen {
+  pub thread_id: String,
+  pub collected_at: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Watch {
+  /// Códigos já normalizados ("P17").
+  pub categories: Vec<String>,
+  pub interval_secs: u64,
+  #[serde(default)]
+  pub last_seen: BTreeMap<String, Seen>,
+}
+
+impl Default for Watch {
+  fn default() -> Self {
+    Self {
+      categories: Vec::new(),
+      interval_secs: DEFAULT_INTERVAL_SECS,
+      last_seen: BTreeMap::new(),
+    }
+  }
+}
+
+impl Watch {
+  pub fn load_file(path: &Path) -> Self {
+    fs::read(path)
+      .ok()
+      .and_then(|raw| serde_json::from_slice(&raw).ok())
+      .unwrap_or_default()
+  }
+
+  pub fn save_file(&self, path: &Path) -> io::Result<()> {
+    write_json_atomic(path, self)
+  }
+
+  /// Troca a lista (sem repetidos); esquece o último lote das categorias que saíram.
+  pub fn set(&mut self, categories: Vec<String>, interval_secs: Option<u64>) {
+    let mut clean: Vec<String> = Vec::new();
+    for c in categories {
+      if !clean.contains(&c) {
+        clean.push(c);
+      }
+    }
+    self.last_seen.retain(|c, _| clean.contains(c));
+    self.categories = clean;
+    if let Some(secs) = interval_secs {
+      self.interval_secs = secs.clamp(MIN_INTERVAL_SECS, MAX_INTERVAL_SECS);
+    }
+  }
+
+  pub fn interval(&self) -> Duration {
+    Duration::from_secs(self.interval_secs.clamp(MIN_INTERVAL_SECS, MAX_INTERVAL_SECS))
+  }
+
+  /// Registra o lote atual; `true` só quando ele é novo em relação a um já visto.
+  pub fn observe(&mut self, category: &str, seen: Seen) -> bool {
+    if !self.categories.iter().any(|c| c == category) {
+      return false;
+    }
+    match self.last_seen.insert(category.to_string(), seen.clone()) {
+      Some(prev) => prev != seen,
+      None => false,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn seen(thread_id: &str, collected_at: &str) -> Seen {
+    Seen {
+      thread_id: thread_id.into(),
+      collected_at: collected_at.into(),
+    }
+  }
+
+  #[test]
+  fn new_batch_only_after_a_baseline() {
+    let mut w = Watch::default();
+    w.set(vec!["P4".into(), "P17".into(), "P4".into()], Some(5));
+    assert_eq!(w.categories, ["P4", "P17"]);
+    assert_eq!(w.interval(), Duration::from_secs(MIN_INTERVAL_SECS));
+
+    assert!(!w.observe("P17", seen("100", "2026-10-01T10:00:00Z")));
+    assert!(!w.observe("P17", seen("100", "2026-10-01T10:00:00Z")));
+    // mesmo tópico, coleta nova
+    assert!(w.observe("P17", seen("100", "2026-10-08T10:00:00Z")));
+    assert!(w.observe("P17", seen("101", "2026-10-08T10:00:00Z")));
+    // categoria fora da lista não guarda nada
+    assert!(!w.observe("P9", seen("1", "x")));
+    assert!(!w.observe("P9", seen("2", "y")));
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("session_watch.json");
+    w.save_file(&path).unwrap();
+    let mut back = Watch::load_file(&path);
+    assert_eq!(back, w);
+
+    back.set(vec!["P4".into()], None);
+    assert_eq!(back.interval_secs, MIN_INTERVAL_SECS);
+    assert!(back.last_seen.is_empty());
+  }
+}
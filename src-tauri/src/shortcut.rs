@@ -0,0 +1,600 @@
+// Parser de aceleradores (hotkeys globais) com suporte a layout de teclado.
+//
+// O `global-hotkey` identifica teclas por `Code`, mas cada backend resolve isso de um jeito:
+// - macOS: keycode físico (posição no teclado US)
+// - X11: keysym do caractere US da tecla, resolvido pelo layout ativo
+// - Windows: virtual keys (VK_OEM_COMMA etc. seguem o caractere, não a posição)
+//
+// Então `<` pode ser Shift+Comma (US/ABNT2) ou a tecla ISO ao lado do Shift (AZERTY), e o
+// `Code` certo depende do layout do revisor e de como a plataforma casa a tecla.
+
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeyboardLayout {
+  #[default]
+  Us,
+  Abnt2,
+  Azerty,
+}
+
+impl FromStr for KeyboardLayout {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.trim().to_ascii_lowercase().as_str() {
+      "" | "us" | "en-us" | "qwerty" => Ok(KeyboardLayout::Us),
+      "abnt2" | "abnt" | "br" | "pt-br" => Ok(KeyboardLayout::Abnt2),
+      "azerty" | "fr" | "fr-fr" => Ok(KeyboardLayout::Azerty),
+      other => Err(format!("unknown keyboard layout: {other}")),
+    }
+  }
+}
+
+impl fmt::Display for KeyboardLayout {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      KeyboardLayout::Us => write!(f, "us"),
+      KeyboardLayout::Abnt2 => write!(f, "abnt2"),
+      KeyboardLayout::Azerty => write!(f, "azerty"),
+    }
+  }
+}
+
+/// Como o backend do `global-hotkey` casa um `Code` com a tecla real.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Platform {
+  /// Posição física (macOS).
+  Macos,
+  /// Caractere da tecla no layout ativo (X11 / Windows).
+  Other,
+}
+
+impl Platform {
+  pub fn current() -> Self {
+    if cfg!(target_os = "macos") {
+      Platform::Macos
+    } else {
+      Platform::Other
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Modifiers {
+  pub ctrl: bool,
+  pub alt: bool,
+  pub shift: bool,
+  pub super_key: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Accelerator {
+  pub modifiers: Modifiers,
+  /// Nome do `Code` (ex.: "Comma", "KeyA", "PageUp").
+  pub code: &'static str,
+  platform: Platform,
+}
+
+impl Accelerator {
+  /// String aceita pelo parser do `global-hotkey` (`Shortcut::from_str`).
+  pub fn to_accelerator_string(&self) -> String {
+    let mut parts: Vec<&str> = Vec::with_capacity(5);
+    if self.modifiers.ctrl {
+      parts.push("Control");
+    }
+    if self.modifiers.alt {
+      parts.push("Alt");
+    }
+    if self.modifiers.shift {
+      parts.push("Shift");
+    }
+    if self.modifiers.super_key {
+      parts.push("Super");
+    }
+    parts.push(self.code);
+    parts.join("+")
+  }
+
+  /// Forma canônica para exibir/comparar (mesma combinação -> mesma string).
+  pub fn display(&self) -> String {
+    let mut parts: Vec<&str> = Vec::with_capacity(5);
+    if self.modifiers.ctrl {
+      parts.push("Ctrl");
+    }
+    if self.modifiers.alt {
+      parts.push(if self.platform == Platform::Macos { "Option" } else { "Alt" });
+    }
+    if self.modifiers.shift {
+      parts.push("Shift");
+    }
+    if self.modifiers.super_key {
+      parts.push(if self.platform == Platform::Macos { "Cmd" } else { "Super" });
+    }
+    let key = self
+      .code
+      .strip_prefix("Key")
+      .or_else(|| self.code.strip_prefix("Digit"))
+      .unwrap_or(self.code);
+    parts.push(key);
+    parts.join("+")
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AcceleratorError {
+  Empty,
+  MissingKey(String),
+  MultipleKeys(String),
+  UnknownKey(String),
+  /// A tecla existe no layout, mas o `global-hotkey` não consegue registrá-la.
+  UnsupportedKey { key: String, code: &'static str },
+}
+
+impl fmt::Display for AcceleratorError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      AcceleratorError::Empty => write!(f, "empty hotkey"),
+      AcceleratorError::MissingKey(raw) => write!(f, "hotkey without a main key: {raw}"),
+      AcceleratorError::MultipleKeys(raw) => write!(f, "hotkey with more than one main key: {raw}"),
+      AcceleratorError::UnknownKey(key) => write!(f, "unknown key: {key}"),
+      AcceleratorError::UnsupportedKey { key, code } => {
+        write!(f, "key {key} is on the {code} key in this layout, which global hotkeys cannot register")
+      }
+    }
+  }
+}
+
+// Teclas que o parser/backends do `global-hotkey` aceitam.
+const SUPPORTED_CODES: &[&str] = &[
+  "Backquote", "Backslash", "BracketLeft", "BracketRight", "Comma", "Equal", "Minus", "Period", "Quote",
+  "Semicolon", "Slash", "Digit0", "Digit1", "Digit2", "Digit3", "Digit4", "Digit5", "Digit6", "Digit7",
+  "Digit8", "Digit9", "KeyA", "KeyB", "KeyC", "KeyD", "KeyE", "KeyF", "KeyG", "KeyH", "KeyI", "KeyJ", "KeyK",
+  "KeyL", "KeyM", "KeyN", "KeyO", "KeyP", "KeyQ", "KeyR", "KeyS", "KeyT", "KeyU", "KeyV", "KeyW", "KeyX",
+  "KeyY", "KeyZ", "Backspace", "CapsLock", "Enter", "Space", "Tab", "Delete", "End", "Home", "Insert",
+  "PageDown", "PageUp", "PrintScreen", "ScrollLock", "Pause", "ArrowDown", "ArrowLeft", "ArrowRight",
+  "ArrowUp", "NumLock", "Numpad0", "Numpad1", "Numpad2", "Numpad3", "Numpad4", "Numpad5", "Numpad6",
+  "Numpad7", "Numpad8", "Numpad9", "NumpadAdd", "NumpadDecimal", "NumpadDivide", "NumpadEnter",
+  "NumpadEqual", "NumpadMultiply", "NumpadSubtract", "Escape", "F1", "F2", "F3", "F4", "F5", "F6", "F7",
+  "F8", "F9", "F10", "F11", "F12", "F13", "F14", "F15", "F16", "F17", "F18", "F19", "F20", "F21", "F22",
+  "F23", "F24", "AudioVolumeDown", "AudioVolumeUp", "AudioVolumeMute", "MediaPlay", "MediaPause",
+  "MediaPlayPause", "MediaStop", "MediaTrackNext", "MediaTrackPrevious",
+];
+
+// Teclas físicas que não estão no teclado US (sem suporte no `global-hotkey`).
+const EXTRA_PHYSICAL_CODES: &[&str] = &["IntlBackslash", "IntlRo"];
+
+// (code, sem shift, com shift) — só as teclas de símbolo; letras e dígitos são tratados à parte.
+type KeyRow = (&'static str, char, Option<char>);
+
+const US_SYMBOLS: &[KeyRow] = &[
+  ("Backquote", '`', Some('~')),
+  ("Digit1", '1', Some('!')),
+  ("Digit2", '2', Some('@')),
+  ("Digit3", '3', Some('#')),
+  ("Digit4", '4', Some('$')),
+  ("Digit5", '5', Some('%')),
+  ("Digit6", '6', Some('^')),
+  ("Digit7", '7', Some('&')),
+  ("Digit8", '8', Some('*')),
+  ("Digit9", '9', Some('(')),
+  ("Digit0", '0', Some(')')),
+  ("Minus", '-', Some('_')),
+  ("Equal", '=', Some('+')),
+  ("BracketLeft", '[', Some('{')),
+  ("BracketRight", ']', Some('}')),
+  ("Backslash", '\\', Some('|')),
+  ("Semicolon", ';', Some(':')),
+  ("Quote", '\'', Some('"')),
+  ("Comma", ',', Some('<')),
+  ("Period", '.', Some('>')),
+  ("Slash", '/', Some('?')),
+];
+
+const ABNT2_SYMBOLS: &[KeyRow] = &[
+  ("Backquote", '\'', Some('"')),
+  ("Digit1", '1', Some('!')),
+  ("Digit2", '2', Some('@')),
+  ("Digit3", '3', Some('#')),
+  ("Digit4", '4', Some('$')),
+  ("Digit5", '5', Some('%')),
+  ("Digit6", '6', Some('¨')),
+  ("Digit7", '7', Some('&')),
+  ("Digit8", '8', Some('*')),
+  ("Digit9", '9', Some('(')),
+  ("Digit0", '0', Some(')')),
+  ("Minus", '-', Some('_')),
+  ("Equal", '=', Some('+')),
+  ("BracketLeft", '´', Some('`')),
+  ("BracketRight", '[', Some('{')),
+  ("Backslash", ']', Some('}')),
+  ("Semicolon", 'ç', Some('Ç')),
+  ("Quote", '~', Some('^')),
+  ("IntlBackslash", '\\', Some('|')),
+  ("Comma", ',', Some('<')),
+  ("Period", '.', Some('>')),
+  ("Slash", ';', Some(':')),
+  ("IntlRo", '/', Some('?')),
+];
+
+const AZERTY_SYMBOLS: &[KeyRow] = &[
+  ("Backquote", '²', None),
+  ("Digit1", '&', Some('1')),
+  ("Digit2", 'é', Some('2')),
+  ("Digit3", '"', Some('3')),
+  ("Digit4", '\'', Some('4')),
+  ("Digit5", '(', Some('5')),
+  ("Digit6", '-', Some('6')),
+  ("Digit7", 'è', Some('7')),
+  ("Digit8", '_', Some('8')),
+  ("Digit9", 'ç', Some('9')),
+  ("Digit0", 'à', Some('0')),
+  ("Minus", ')', Some('°')),
+  ("Equal", '=', Some('+')),
+  ("BracketLeft", '^', Some('¨')),
+  ("BracketRight", '$', Some('£')),
+  ("Backslash", '*', Some('µ')),
+  ("Quote", 'ù', Some('%')),
+  ("IntlBackslash", '<', Some('>')),
+  ("KeyM", ',', Some('?')),
+  ("Comma", ';', Some('.')),
+  ("Period", ':', Some('/')),
+  ("Slash", '!', Some('§')),
+];
+
+// Letras fora do lugar no AZERTY: (letra, code físico)
+const AZERTY_LETTERS: &[(char, &str)] = &[('a', "KeyQ"), ('q', "KeyA"), ('z', "KeyW"), ('w', "KeyZ"), ('m', "Semicolon")];
+
+fn symbols(layout: KeyboardLayout) -> &'static [KeyRow] {
+  match layout {
+    KeyboardLayout::Us => US_SYMBOLS,
+    KeyboardLayout::Abnt2 => ABNT2_SYMBOLS,
+    KeyboardLayout::Azerty => AZERTY_SYMBOLS,
+  }
+}
+
+const LETTER_CODES: [&str; 26] = [
+  "KeyA", "KeyB", "KeyC", "KeyD", "KeyE", "KeyF", "KeyG", "KeyH", "KeyI", "KeyJ", "KeyK", "KeyL", "KeyM",
+  "KeyN", "KeyO", "KeyP", "KeyQ", "KeyR", "KeyS", "KeyT", "KeyU", "KeyV", "KeyW", "KeyX", "KeyY", "KeyZ",
+];
+const DIGIT_CODES: [&str; 10] = [
+  "Digit0", "Digit1", "Digit2", "Digit3", "Digit4", "Digit5", "Digit6", "Digit7", "Digit8", "Digit9",
+];
+
+/// Caractere digitado pela tecla física `code` (sem shift) no layout.
+fn unshifted_char(layout: KeyboardLayout, code: &str) -> Option<char> {
+  if let Some(row) = symbols(layout).iter().find(|r| r.0 == code && !r.0.starts_with("Digit")) {
+    return Some(row.1);
+  }
+  if layout == KeyboardLayout::Azerty {
+    if let Some((ch, _)) = AZERTY_LETTERS.iter().find(|(_, c)| *c == code) {
+      return Some(*ch);
+    }
+  }
+  let idx = LETTER_CODES.iter().position(|c| *c == code)?;
+  Some((b'a' + idx as u8) as char)
+}
+
+/// Tecla física + shift que produz `ch` no layout.
+fn physical_for_char(layout: KeyboardLayout, ch: char) -> Option<(&'static str, bool)> {
+  // dígitos sempre nomeiam a tecla da fileira de cima (mesmo no AZERTY, onde precisam de shift)
+  if let Some(d) = ch.to_digit(10) {
+    return Some((DIGIT_CODES[d as usize], false));
+  }
+  if ch.is_ascii_alphabetic() {
+    let lower = ch.to_ascii_lowercase();
+    if layout == KeyboardLayout::Azerty {
+      if let Some((_, code)) = AZERTY_LETTERS.iter().find(|(l, _)| *l == lower) {
+        return Some((code, false));
+      }
+    }
+    return Some((LETTER_CODES[(lower as u8 - b'a') as usize], false));
+  }
+  for (code, base, shifted) in symbols(layout) {
+    if *base == ch {
+      return Some((code, false));
+    }
+    if *shifted == Some(ch) {
+      return Some((code, true));
+    }
+  }
+  None
+}
+
+/// Converte a tecla física no `Code` que o backend da plataforma vai casar com ela.
+fn code_for_platform(layout: KeyboardLayout, platform: Platform, physical: &'static str) -> &'static str {
+  if platform == Platform::Macos {
+    return physical;
+  }
+  // X11/Windows: o `Code` é lido como "a tecla que digita o caractere US dele"
+  let Some(ch) = unshifted_char(layout, physical) else {
+    return physical;
+  };
+  if ch.is_ascii_alphabetic() {
+    return LETTER_CODES[(ch as u8 - b'a') as usize];
+  }
+  US_SYMBOLS
+    .iter()
+    .find(|r| r.1 == ch && !r.0.starts_with("Digit"))
+    .map(|r| r.0)
+    .unwrap_or(physical)
+}
+
+enum Token {
+  Ctrl,
+  Alt,
+  Shift,
+  Super,
+  CmdOrCtrl,
+  Physical(&'static str),
+  Char(char),
+}
+
+fn named_key(upper: &str) -> Option<&'static str> {
+  let code = match upper {
+    "DEL" | "DELETE" => "Delete",
+    "INS" | "INSERT" => "Insert",
+    "PGUP" | "PAGEUP" | "PRIOR" => "PageUp",
+    "PGDN" | "PGDOWN" | "PAGEDOWN" | "NEXT" => "PageDown",
+    "HOME" => "Home",
+    "END" => "End",
+    "ESC" | "ESCAPE" => "Escape",
+    "ENTER" | "RETURN" => "Enter",
+    "SPACE" | "SPACEBAR" => "Space",
+    "TAB" => "Tab",
+    "BS" | "BACKSPACE" => "Backspace",
+    "CAPSLOCK" => "CapsLock",
+    "PRTSC" | "PRINT" | "PRINTSCREEN" => "PrintScreen",
+    "SCROLLLOCK" => "ScrollLock",
+    "PAUSE" | "BREAK" | "PAUSEBREAK" => "Pause",
+    "UP" | "ARROWUP" => "ArrowUp",
+    "DOWN" | "ARROWDOWN" => "ArrowDown",
+    "LEFT" | "ARROWLEFT" => "ArrowLeft",
+    "RIGHT" | "ARROWRIGHT" => "ArrowRight",
+    "NUMLOCK" => "NumLock",
+    "NUMADD" | "NUMPADADD" | "NUMPLUS" | "NUMPADPLUS" => "NumpadAdd",
+    "NUMDECIMAL" | "NUMPADDECIMAL" => "NumpadDecimal",
+    "NUMDIVIDE" | "NUMPADDIVIDE" => "NumpadDivide",
+    "NUMENTER" | "NUMPADENTER" => "NumpadEnter",
+    "NUMEQUAL" | "NUMPADEQUAL" => "NumpadEqual",
+    "NUMMULTIPLY" | "NUMPADMULTIPLY" => "NumpadMultiply",
+    "NUMSUBTRACT" | "NUMPADSUBTRACT" => "NumpadSubtract",
+    "VOLUMEUP" | "AUDIOVOLUMEUP" => "AudioVolumeUp",
+    "VOLUMEDOWN" | "AUDIOVOLUMEDOWN" => "AudioVolumeDown",
+    "VOLUMEMUTE" | "AUDIOVOLUMEMUTE" => "AudioVolumeMute",
+    "MEDIAPLAY" => "MediaPlay",
+    "MEDIAPAUSE" => "MediaPause",
+    "MEDIAPLAYPAUSE" => "MediaPlayPause",
+    "MEDIASTOP" => "MediaStop",
+    "MEDIATRACKNEXT" => "MediaTrackNext",
+    "MEDIATRACKPREV" | "MEDIATRACKPREVIOUS" => "MediaTrackPrevious",
+    _ => {
+      // nomes de `Code` (KeyA, Digit1, Comma, F5, Numpad3, IntlBackslash...) são teclas físicas
+      let num = upper.strip_prefix("NUMPAD").or_else(|| upper.strip_prefix("NUM"));
+      if let Some(d) = num.and_then(|n| n.parse::<usize>().ok()).filter(|d| *d < 10) {
+        return Some(["Numpad0", "Numpad1", "Numpad2", "Numpad3", "Numpad4", "Numpad5", "Numpad6", "Numpad7", "Numpad8", "Numpad9"][d]);
+      }
+      return SUPPORTED_CODES
+        .iter()
+        .chain(EXTRA_PHYSICAL_CODES.iter())
+        .find(|c| c.eq_ignore_ascii_case(upper))
+        .copied();
+    }
+  };
+  Some(code)
+}
+
+fn parse_token(raw: &str) -> Option<Token> {
+  let mut chars = raw.chars();
+  if let (Some(ch), None) = (chars.next(), chars.next()) {
+    return Some(Token::Char(ch));
+  }
+  let upper = raw.to_uppercase();
+  Some(match upper.as_str() {
+    "CTRL" | "CONTROL" | "CTL" => Token::Ctrl,
+    "ALT" | "OPTION" | "OPT" => Token::Alt,
+    "SHIFT" => Token::Shift,
+    "CMD" | "COMMAND" | "SUPER" | "META" | "WIN" | "WINDOWS" => Token::Super,
+    "CMDORCTRL" | "CMDORCONTROL" | "COMMANDORCTRL" | "COMMANDORCONTROL" => Token::CmdOrCtrl,
+    "PLUS" => Token::Char('+'),
+    _ => Token::Physical(named_key(&upper)?),
+  })
+}
+
+/// Interpreta um acelerador ("Cmd+<", "Ctrl+Alt+PgUp", "Shift+IntlBackslash") para o layout dado.
+///
+/// Tokens de um caractere são caracteres (resolvidos pelo layout, adicionando Shift quando
+/// necessário); nomes (`Comma`, `KeyQ`, `PageUp`...) são teclas físicas e não passam pelo layout.
+pub fn parse_accelerator(raw: &str, layout: KeyboardLayout, platform: Platform) -> Result<Accelerator, AcceleratorError> {
+  let trimmed = raw.trim();
+  if trimmed.is_empty() {
+    return Err(AcceleratorError::Empty);
+  }
+
+  // "Ctrl++" -> tecla '+'
+  let mut parts: Vec<&str> = trimmed.split('+').map(|p| p.trim()).collect();
+  if trimmed.ends_with("++") || trimmed == "+" {
+    parts.retain(|p| !p.is_empty());
+    parts.push("+");
+  } else {
+    parts.retain(|p| !p.is_empty());
+  }
+
+  let mut modifiers = Modifiers::default();
+  let mut key: Option<(&'static str, bool)> = None;
+  let mut key_raw = String::new();
+
+  for part in parts {
+    let token = parse_token(part).ok_or_else(|| AcceleratorError::UnknownKey(part.to_string()))?;
+    let resolved = match token {
+      Token::Ctrl => {
+        modifiers.ctrl = true;
+        continue;
+      }
+      Token::Alt => {
+        modifiers.alt = true;
+        continue;
+      }
+      Token::Shift => {
+        modifiers.shift = true;
+        continue;
+      }
+      Token::Super => {
+        modifiers.super_key = true;
+        continue;
+      }
+      Token::CmdOrCtrl => {
+        if platform == Platform::Macos {
+          modifiers.super_key = true;
+        } else {
+          modifiers.ctrl = true;
+        }
+        continue;
+      }
+      Token::Physical(code) => (code, false),
+      Token::Char(ch) => {
+        let (physical, needs_shift) =
+          physical_for_char(layout, ch).ok_or_else(|| AcceleratorError::UnknownKey(part.to_string()))?;
+        (code_for_platform(layout, platform, physical), needs_shift)
+      }
+    };
+    if key.is_some() {
+      return Err(AcceleratorError::MultipleKeys(trimmed.to_string()));
+    }
+    key = Some(resolved);
+    key_raw = part.to_string();
+  }
+
+  let Some((code, needs_shift)) = key else {
+    return Err(AcceleratorError::MissingKey(trimmed.to_string()));
+  };
+  if !SUPPORTED_CODES.contains(&code) {
+    return Err(AcceleratorError::UnsupportedKey { key: key_raw, code });
+  }
+  if needs_shift {
+    modifiers.shift = true;
+  }
+
+  Ok(Accelerator {
+    modifiers,
+    code,
+    platform,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn accel(raw: &str, layout: KeyboardLayout, platform: Platform) -> String {
+    parse_accelerator(raw, layout, platform)
+      .map(|a| a.to_accelerator_string())
+      .unwrap_or_else(|e| format!("error: {e}"))
+  }
+
+  #[test]
+  fn us_matches_previous_normalization() {
+    for platform in [Platform::Macos, Platform::Other] {
+      let us = |raw| accel(raw, KeyboardLayout::Us, platform);
+      assert_eq!(us("PageUp"), "PageUp");
+      assert_eq!(us("Cmd+<"), "Shift+Super+Comma");
+      assert_eq!(us("Cmd+Shift+<"), "Shift+Super+Comma");
+      assert_eq!(us("Cmd+>"), "Shift+Super+Period");
+      assert_eq!(us("Ctrl+?"), "Control+Shift+Slash");
+      assert_eq!(us("Ctrl+,"), "Control+Comma");
+      assert_eq!(us("Ctrl+."), "Control+Period");
+      assert_eq!(us("Ctrl+/"), "Control+Slash");
+      assert_eq!(us("Ctrl+Alt+R"), "Control+Alt+KeyR");
+    }
+  }
+
+  #[test]
+  fn abnt2_layout() {
+    let mac = |raw| accel(raw, KeyboardLayout::Abnt2, Platform::Macos);
+    let other = |raw| accel(raw, KeyboardLayout::Abnt2, Platform::Other);
+
+    // `<` e `>` ficam em Shift+vírgula/ponto, como no US
+    assert_eq!(mac("Cmd+<"), "Shift+Super+Comma");
+    assert_eq!(other("Ctrl+>"), "Control+Shift+Period");
+    // `;` fica na posição do `/` US
+    assert_eq!(mac("Ctrl+;"), "Control+Slash");
+    assert_eq!(other("Ctrl+;"), "Control+Semicolon");
+    // `/` e `?` ficam na tecla ABNT2 extra (IntlRo)
+    assert_eq!(other("Ctrl+/"), "Control+Slash");
+    assert_eq!(other("Ctrl+?"), "Control+Shift+Slash");
+    assert!(mac("Ctrl+?").starts_with("error: key ? is on the IntlRo key"));
+    // ç é uma tecla física própria
+    assert_eq!(mac("Alt+ç"), "Alt+Semicolon");
+  }
+
+  #[test]
+  fn azerty_layout() {
+    let mac = |raw| accel(raw, KeyboardLayout::Azerty, Platform::Macos);
+    let other = |raw| accel(raw, KeyboardLayout::Azerty, Platform::Other);
+
+    // `<` é a tecla ISO ao lado do Shift, que o global-hotkey não registra
+    assert!(mac("Cmd+<").starts_with("error: key < is on the IntlBackslash key"));
+    assert!(other("Ctrl+>").starts_with("error: key > is on the IntlBackslash key"));
+    // `?` = Shift + tecla da vírgula (posição do M no US)
+    assert_eq!(mac("Ctrl+?"), "Control+Shift+KeyM");
+    assert_eq!(other("Ctrl+?"), "Control+Shift+Comma");
+    // `/` = Shift + tecla do `:` (posição do ponto no US)
+    assert_eq!(mac("Ctrl+/"), "Control+Shift+Period");
+    assert_eq!(other("Ctrl+/"), "Control+Shift+Period");
+    // letras trocadas de lugar
+    assert_eq!(mac("Ctrl+A"), "Control+KeyQ");
+    assert_eq!(other("Ctrl+A"), "Control+KeyA");
+    assert_eq!(mac("Ctrl+M"), "Control+Semicolon");
+    // dígitos nomeiam a fileira de cima, sem Shift implícito
+    assert_eq!(mac("Ctrl+Alt+1"), "Control+Alt+Digit1");
+    // teclas físicas explícitas não passam pelo layout
+    assert_eq!(mac("Ctrl+KeyA"), "Control+KeyA");
+    assert_eq!(other("Shift+Comma"), "Shift+Comma");
+  }
+
+  #[test]
+  fn aliases() {
+    let us = |raw| accel(raw, KeyboardLayout::Us, Platform::Other);
+    assert_eq!(us("Meta+Del"), "Super+Delete");
+    assert_eq!(us("Super+Delete"), "Super+Delete");
+    assert_eq!(us("win+pgup"), "Super+PageUp");
+    assert_eq!(us("Ctrl+PgDn"), "Control+PageDown");
+    assert_eq!(us("Option+Ins"), "Alt+Insert");
+    assert_eq!(us("Esc"), "Escape");
+    assert_eq!(us("Num5"), "Numpad5");
+    assert_eq!(us("Ctrl++"), "Control+Shift+Equal");
+    assert_eq!(us("Ctrl+Plus"), "Control+Shift+Equal");
+    assert_eq!(us("CmdOrCtrl+K"), "Control+KeyK");
+    assert_eq!(accel("CmdOrCtrl+K", KeyboardLayout::Us, Platform::Macos), "Super+KeyK");
+  }
+
+  #[test]
+  fn display_is_canonical() {
+    let display = |raw, platform| parse_accelerator(raw, KeyboardLayout::Us, platform).unwrap().display();
+    assert_eq!(display("shift+ctrl+a", Platform::Other), "Ctrl+Shift+A");
+    assert_eq!(display("Ctrl+Shift+A", Platform::Other), "Ctrl+Shift+A");
+    assert_eq!(display("Cmd+<", Platform::Macos), "Shift+Cmd+Comma");
+    assert_eq!(display("Meta+<", Platform::Other), "Shift+Super+Comma");
+    assert_eq!(display("Alt+1", Platform::Macos), "Option+1");
+    assert_eq!(display("PgUp", Platform::Other), "PageUp");
+  }
+
+  #[test]
+  fn errors() {
+    let parse = |raw| parse_accelerator(raw, KeyboardLayout::Us, Platform::Other);
+    assert_eq!(parse("  "), Err(AcceleratorError::Empty));
+    assert_eq!(parse("Ctrl+Shift"), Err(AcceleratorError::MissingKey("Ctrl+Shift".into())));
+    assert_eq!(parse("Ctrl+A+B"), Err(AcceleratorError::MultipleKeys("Ctrl+A+B".into())));
+    assert_eq!(parse("Ctrl+Hyper"), Err(AcceleratorError::UnknownKey("Hyper".into())));
+    assert_eq!(parse("Ctrl+ç"), Err(AcceleratorError::UnknownKey("ç".into())));
+  }
+
+  #[test]
+  fn layout_from_str() {
+    assert_eq!("pt-BR".parse::<KeyboardLayout>(), Ok(KeyboardLayout::Abnt2));
+    assert_eq!("AZERTY".parse::<KeyboardLayout>(), Ok(KeyboardLayout::Azerty));
+    assert_eq!("".parse::<KeyboardLayout>(), Ok(KeyboardLayout::Us));
+    assert!("dvorak".parse::<KeyboardLayout>().is_err());
+  }
+}
@@ -0,0 +1,183 @@
+// Limite de mapas por submitter (`limitPerUser` da Session API).
+//
+// Conta na ordem de envio (ordem da fila): os primeiros `limit` mapas de cada submitter valem,
+// o resto é excedente. Mapas que já vieram ignorados do import não contam, e o mesmo código
+// repetido pelo mesmo submitter conta uma vez só.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::mapcode;
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LimitItem {
+  pub id: String,
+  pub mapcode: String,
+  pub submitter: Option<String>,
+  /// Ignorado no import (o bot da sessão já descartou).
+  #[serde(default)]
+  pub imported_ignored: Option<bool>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmitterCount {
+  /// Como apareceu no primeiro mapa.
+  pub submitter: String,
+  /// Mapas na fila (inclui ignorados e repetidos).
+  pub total: usize,
+  /// Os que contam para o limite.
+  pub counted: usize,
+  pub overflow: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverflowItem {
+  pub id: String,
+  pub mapcode: String,
+  pub submitter: String,
+  /// Posição (1-based) entre os mapas que contam desse submitter.
+  pub position: usize,
+  /// Texto padrão para o review do item marcado como ignorado.
+  pub reason: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LimitReport {
+  /// `None`: sessão sem limite (nada é excedente).
+  pub limit: Option<usize>,
+  /// Só quem passou do limite, na ordem do primeiro mapa.
+  pub over_limit: Vec<SubmitterCount>,
+  /// Na ordem da fila.
+  pub overflow: Vec<OverflowItem>,
+  /// Itens sem submitter (não dá pra contar).
+  pub without_submitter: usize,
+}
+
+pub fn overflow_reason(limit: usize) -> String {
+  format!("Over the submission limit ({limit} per user)")
+}
+
+// nick do Transformice não diferencia maiúsculas
+fn submitter_key(raw: &str) -> String {
+  raw.trim().to_lowercase()
+}
+
+fn map_key(raw: &str) -> String {
+  mapcode::normalize_mapcode(raw).unwrap_or_else(|| raw.trim().to_string())
+}
+
+pub fn check(items: &[LimitItem], limit_per_user: Option<i64>) -> LimitReport {
+  let limit = limit_per_user.filter(|l| *l > 0).map(|l| l as usize);
+  let mut report = LimitReport {
+    limit,
+    ..LimitReport::default()
+  };
+
+  let mut order: Vec<String> = Vec::new();
+  let mut counts: HashMap<String, SubmitterCount> = HashMap::new();
+  let mut seen: HashSet<(String, String)> = HashSet::new();
+
+  for item in items {
+    let Some(submitter) = item.submitter.as_deref().map(str::trim).filter(|s| !s.is_empty()) else {
+      report.without_submitter += 1;
+      continue;
+    };
+    let key = submitter_key(submitter);
+    let count = counts.entry(key.clone()).or_insert_with(|| {
+      order.push(key.clone());
+      SubmitterCount {
+        submitter: submitter.to_string(),
+        total: 0,
+        counted: 0,
+        overflow: 0,
+      }
+    });
+    count.total += 1;
+
+    if item.imported_ignored.unwrap_or(false) || !seen.insert((key, map_key(&item.mapcode))) {
+      continue;
+    }
+    count.counted += 1;
+
+    if let Some(limit) = limit.filter(|l| count.counted > *l) {
+      count.overflow += 1;
+      report.overflow.push(OverflowItem {
+        id: item.id.clone(),
+        mapcode: item.mapcode.clone(),
+        submitter: count.submitter.clone(),
+        position: count.counted,
+        reason: overflow_reason(limit),
+      });
+    }
+  }
+
+  report.over_limit = order
+    .into_iter()
+    .filter_map(|k| counts.remove(&k))
+    .filter(|c| c.overflow > 0)
+    .collect();
+  report
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn item(id: &str, mapcode: &str, submitter: Option<&str>, ignored: bool) -> LimitItem {
+    LimitItem {
+      id: id.into(),
+      mapcode: mapcode.into(),
+      submitter: submitter.map(Into::into),
+      imported_ignored: Some(ignored),
+    }
+  }
+
+  #[test]
+  fn flags_maps_after_the_limit_in_order() {
+    let items = vec![
+      item("1", "@100", Some("Alice#0000"), false),
+      item("2", "@101", Some("Bob"), false),
+      // ignorado no import não conta
+      item("3", "@102", Some("alice#0000"), true),
+      item("4", "@103", Some(" ALICE#0000 "), false),
+      // repetido pelo mesmo submitter conta uma vez
+      item("5", "100", Some("Alice#0000"), false),
+      item("6", "@104", Some("Alice#0000"), false),
+      item("7", "@105", None, false),
+      item("8", "@106", Some("Alice#0000"), false),
+      item("9", "@107", Some("Bob"), false),
+    ];
+    let report = check(&items, Some(2));
+
+    assert_eq!(report.limit, Some(2));
+    assert_eq!(report.without_submitter, 1);
+    assert_eq!(
+      report.overflow.iter().map(|o| (o.id.as_str(), o.position)).collect::<Vec<_>>(),
+      vec![("6", 3), ("8", 4)]
+    );
+    assert_eq!(report.overflow[0].reason, "Over the submission limit (2 per user)");
+    assert_eq!(
+      report.over_limit,
+      vec![SubmitterCount {
+        submitter: "Alice#0000".into(),
+        total: 6,
+        counted: 4,
+        overflow: 2,
+      }]
+    );
+  }
+
+  #[test]
+  fn no_limit_means_no_overflow() {
+    let items = vec![item("1", "@1", Some("A"), false), item("2", "@2", Some("A"), false)];
+    for limit in [None, Some(0), Some(-1)] {
+      let report = check(&items, limit);
+      assert_eq!(report.limit, None);
+      assert!(report.overflow.is_empty() && report.over_limit.is_empty());
+    }
+    assert!(check(&items, Some(2)).overflow.is_empty());
+  }
+}
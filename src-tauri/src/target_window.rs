@@ -0,0 +1,287 @@
+// Confere a janela em foco antes de digitar um comando nela.
+//
+// Linux/X11: lê `_NET_ACTIVE_WINDOW` (EWMH) e compara título/WM_CLASS com a allow-list.
+// Entradas `class:` olham só o WM_CLASS: títulos são livres ("Steam Community – Discord"),
+// então Steam e o Flash Player standalone entram pela classe e só "transformice" pelo título.
+// Onde não dá para saber qual janela está em foco (Wayland puro, Windows, macOS) o envio
+// segue com um aviso no log, a não ser no modo estrito, que recusa. Lista vazia desliga a
+// checagem.
+
+use std::{
+  fs, io,
+  path::Path,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    RwLock,
+  },
+};
+
+use crate::atomic_file::write_json_atomic;
+
+pub const WRONG_TARGET_WINDOW: &str = "wrong_target_window";
+/// Modo estrito sem como ler a janela em foco.
+pub const UNCHECKED_TARGET_WINDOW: &str = "unchecked_target_window";
+
+/// Trechos procurados sem diferenciar maiúsculas: `class:x` no WM_CLASS, o resto no título.
+pub const DEFAULT_ALLOW_LIST: &[&str] = &["transformice", "class:flashplayer", "class:steam"];
+const CLASS_PREFIX: &str = "class:";
+
+static ALLOW_LIST: RwLock<Vec<String>> = RwLock::new(Vec::new());
+static STRICT: AtomicBool = AtomicBool::new(false);
+// o aviso de "não deu para conferir" sai uma vez por execução, não a cada comando
+static SKIP_WARNED: AtomicBool = AtomicBool::new(false);
+
+/// Conteúdo de `target_windows.json`.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Config {
+  pub allow_list: Vec<String>,
+  #[serde(default)]
+  pub strict: bool,
+}
+
+impl Default for Config {
+  fn default() -> Self {
+    Self {
+      allow_list: default_allow_list(),
+      strict: false,
+    }
+  }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WindowInfo {
+  pub title: String,
+  /// WM_CLASS (instância e classe separadas por espaço).
+  pub class: String,
+}
+
+pub fn default_allow_list() -> Vec<String> {
+  DEFAULT_ALLOW_LIST.iter().map(|s| s.to_string()).collect()
+}
+
+/// Normaliza (trim, minúsculas, sem vazios/repetidos) e troca a lista usada no envio.
+pub fn set_allow_list(patterns: Vec<String>) -> Vec<String> {
+  let mut clean: Vec<String> = Vec::new();
+  for p in patterns {
+    let p = p.trim().to_lowercase();
+    let p = match p.strip_prefix(CLASS_PREFIX) {
+      Some(class) => format!("{CLASS_PREFIX}{}", class.trim()),
+      None => p,
+    };
+    if !p.is_empty() && p != CLASS_PREFIX && !clean.contains(&p) {
+      clean.push(p);
+    }
+  }
+  if let Ok(mut g) = ALLOW_LIST.write() {
+    *g = clean.clone();
+  }
+  clean
+}
+
+pub fn set_strict(strict: bool) {
+  STRICT.store(strict, Ordering::Relaxed);
+}
+
+pub fn strict() -> bool {
+  STRICT.load(Ordering::Relaxed)
+}
+
+/// Config salva em disco; sem arquivo (ou inválido) usa a padrão.
+pub fn load_file(path: &Path) -> Config {
+  fs::read(path)
+    .ok()
+    .and_then(|raw| serde_json::from_slice(&raw).ok())
+    .unwrap_or_default()
+}
+
+pub fn save_file(path: &Path, config: &Config) -> io::Result<()> {
+  write_json_atomic(path, config)
+}
+
+pub fn allow_list() -> Vec<String> {
+  ALLOW_LIST.read().map(|g| g.clone()).unwrap_or_default()
+}
+
+/// Config em uso agora.
+pub fn config() -> Config {
+  Config {
+    allow_list: allow_list(),
+    strict: strict(),
+  }
+}
+
+pub fn matches(window: &WindowInfo, patterns: &[String]) -> bool {
+  let title = window.title.to_lowercase();
+  let class = window.class.to_lowercase();
+  patterns.iter().any(|p| match p.strip_prefix(CLASS_PREFIX) {
+    Some(c) => class.contains(c),
+    None => title.contains(p.as_str()),
+  })
+}
+
+fn describe(window: &WindowInfo) -> String {
+  match (window.title.trim(), window.class.trim()) {
+    ("", "") => "an unnamed window".to_string(),
+    (title, "") => format!("\"{title}\""),
+    ("", class) => format!("a window of class \"{class}\""),
+    (title, class) => format!("\"{title}\" ({class})"),
+  }
+}
+
+/// Decide o envio para a janela em foco (`None` = não deu para ler).
+///
+/// `Err("wrong_target_window: ...")` fora da allow-list; `Err("unchecked_target_window: ...")`
+/// no modo estrito quando a janela não pôde ser lida.
+pub fn check(window: Option<&WindowInfo>, patterns: &[String], strict: bool) -> Result<(), String> {
+  if patterns.is_empty() {
+    return Ok(());
+  }
+  let Some(window) = window else {
+    if strict {
+      return Err(format!(
+        "{UNCHECKED_TARGET_WINDOW}: the focused window can't be read here (Wayland, Windows, macOS or a WM without EWMH). Turn off strict mode to send anyway."
+      ));
+    }
+    if !SKIP_WARNED.swap(true, Ordering::Relaxed) {
+      log::warn!("target window check skipped: the focused window can't be read here; commands are typed unchecked");
+    }
+    return Ok(());
+  };
+  if matches(window, patterns) {
+    return Ok(());
+  }
+  Err(format!(
+    "{WRONG_TARGET_WINDOW}: the focused window is {}, not the game. Focus Transformice and try again.",
+    describe(window)
+  ))
+}
+
+/// `check` com a janela em foco de verdade e a config em uso.
+pub fn ensure_allowed() -> Result<(), String> {
+  let patterns = allow_list();
+  if patterns.is_empty() {
+    return Ok(());
+  }
+  check(active_window().as_ref(), &patterns, strict())
+}
+
+/// `None` quando não dá para consultar (sem X11, WM sem EWMH, nenhuma janela em foco).
+pub fn active_window() -> Option<WindowInfo> {
+  #[cfg(target_os = "linux")]
+  {
+    // no Wayland o XWayland só enxerga as próprias janelas; o foco de verdade fica escondido
+    if std::env::var_os("DISPLAY").is_some() && !crate::inject::wayland_session() {
+      return x11::active_window();
+    }
+  }
+  None
+}
+
+#[cfg(target_os = "linux")]
+mod x11 {
+  use x11rb::{
+    connection::Connection,
+    protocol::xproto::{Atom, AtomEnum, ConnectionExt as _, Window},
+    rust_connection::RustConnection,
+  };
+
+  use super::WindowInfo;
+
+  fn atom(conn: &RustConnection, name: &[u8]) -> Option<Atom> {
+    Some(conn.intern_atom(false, name).ok()?.reply().ok()?.atom)
+  }
+
+  fn property(conn: &RustConnection, win: Window, prop: Atom, ty: impl Into<Atom>) -> Option<Vec<u8>> {
+    let reply = conn
+      .get_property(false, win, prop, ty, 0, 1024)
+      .ok()?
+      .reply()
+      .ok()?;
+    Some(reply.value)
+  }
+
+  pub fn active_window() -> Option<WindowInfo> {
+    let (conn, screen_num) = x11rb::connect(None).ok()?;
+    let root = conn.setup().roots.get(screen_num)?.root;
+
+    let net_active = atom(&conn, b"_NET_ACTIVE_WINDOW")?;
+    let reply = conn
+      .get_property(false, root, net_active, AtomEnum::WINDOW, 0, 1)
+      .ok()?
+      .reply()
+      .ok()?;
+    let win = reply.value32()?.next().filter(|w| *w != 0)?;
+
+    // _NET_WM_NAME é UTF-8; WM_NAME fica de reserva para WMs antigos
+    let utf8 = atom(&conn, b"UTF8_STRING")?;
+    let net_name = atom(&conn, b"_NET_WM_NAME")?;
+    let title = property(&conn, win, net_name, utf8)
+      .filter(|v| !v.is_empty())
+      .or_else(|| property(&conn, win, AtomEnum::WM_NAME.into(), AtomEnum::STRING))
+      .map(|v| String::from_utf8_lossy(&v).into_owned())
+      .unwrap_or_default();
+
+    // WM_CLASS = "instância\0classe\0"
+    let class = property(&conn, win, AtomEnum::WM_CLASS.into(), AtomEnum::STRING)
+      .map(|v| {
+        v.split(|b| *b == 0)
+          .filter(|s| !s.is_empty())
+          .map(|s| String::from_utf8_lossy(s).into_owned())
+          .collect::<Vec<_>>()
+          .join(" ")
+      })
+      .unwrap_or_default();
+
+    Some(WindowInfo { title, class })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn win(title: &str, class: &str) -> WindowInfo {
+    WindowInfo {
+      title: title.into(),
+      class: class.into(),
+    }
+  }
+
+  #[test]
+  fn matches_title_or_class_case_insensitively() {
+    let patterns = default_allow_list();
+    assert!(matches(&win("Transformice", ""), &patterns));
+    assert!(matches(&win("Adobe Flash Player 32", "flashplayer FlashPlayer"), &patterns));
+    assert!(matches(&win("", "steam_app_1029 Steam_app_1029"), &patterns));
+    assert!(!matches(&win("#map-review | Discord", "discord Discord"), &patterns));
+    assert!(!matches(&win("Maps Reviewer", "app App"), &patterns));
+    // "steam"/"flash player" no título não bastam
+    assert!(!matches(&win("Steam Community – Discord", "discord Discord"), &patterns));
+    assert!(!matches(&win("Flash Player tips - Chromium", "chromium Chromium"), &patterns));
+    // e "transformice" na classe também não
+    assert!(!matches(&win("Wiki", "transformice-wiki Transformice-wiki"), &patterns));
+
+    assert_eq!(describe(&win("Discord", "discord")), "\"Discord\" (discord)");
+    assert_eq!(describe(&win("", "")), "an unnamed window");
+
+    assert_eq!(
+      set_allow_list(vec![" Steam ".into(), "steam".into(), String::new(), "Class: Flash".into(), "class:".into()]),
+      vec!["steam".to_string(), "class:flash".to_string()]
+    );
+    assert_eq!(allow_list(), vec!["steam".to_string(), "class:flash".to_string()]);
+  }
+
+  #[test]
+  fn strict_mode_refuses_when_the_window_is_unknown() {
+    let patterns = default_allow_list();
+    assert!(check(None, &patterns, false).is_ok());
+    let err = check(None, &patterns, true).unwrap_err();
+    assert!(err.starts_with(UNCHECKED_TARGET_WINDOW), "{err}");
+    assert!(check(Some(&win("Transformice", "")), &patterns, true).is_ok());
+    let err = check(Some(&win("Discord", "discord")), &patterns, false).unwrap_err();
+    assert!(err.starts_with(WRONG_TARGET_WINDOW), "{err}");
+    // lista vazia desliga tudo, inclusive o estrito
+    assert!(check(None, &[], true).is_ok());
+  }
+}
@@ -1,16 +1,27 @@
 import { normalizeMapcode, parseMapcodesFromText, uniqPreserveOrder } from './mapcodes'
-import { APP_VERSION, type AppState, type QueueItem } from './model'
+import { APP_VERSION, type AppState, type KeyboardLayout, type QueueItem } from './model'
 import { loadState, saveState } from './storage'
-import { buildExportPayloadV1 } from './export'
-import { CATEGORIES, REVIEW_CATEGORIES, parseCategoryNumber, type ReviewedCategoryCode } from './categories'
+import { buildExportPayloadV1, type ExportPayloadV1 } from './export'
+import { CATEGORIES, DECISION_LABEL, REVIEW_CATEGORIES, parseCategoryNumber, type ReviewedCategoryCode } from './categories'
 import {
   exportJsonToPath,
   validateAuthToken,
   fetchMapInfo,
-  fetchSessionFromApi,
+  onMapInfoProgress,
+  mapInfoCacheStats,
+  clearMapInfoCache,
+  getMapInfoProvider,
+  setMapInfoProvider,
+  renderMapPreview,
+  lintMaps,
+  checkSubmissionLimits,
+  fetchSessionsFromApi,
   submitSessionReview,
   openImportFileDialog,
   openExportSaveDialog,
+  onHotkeyChord,
+  onHotkeyChordArmed,
+  onHotkeyChordTimeout,
   onHotkeyNavPlay,
   onHotkeyReplayCurrent,
   onHotkeysStatus,
@@ -19,25 +30,82 @@ import {
   onHotkeyMassPermPlayCurrent,
   onHotkeyMassPermToggle,
   onMassPermHotkeysStatus,
-  onClipboardChanged,
+  onMapcodesCaptured,
   checkForUpdate,
   downloadAndInstallUpdate,
   relaunchApp,
   getAppVersion,
   readClipboardText,
   readTextFileFromPath,
+  registerChordHotkeys,
   registerHotkeys,
+  setKeyboardLayout,
   setReviewHotkeysEnabled,
   setMassPermHotkeysConfig,
   writeClipboardText,
   sendNpToActiveWindow,
   setNpContext,
-  sendPermToActiveWindow,
+  getTargetWindowConfig,
+  setTargetWindowAllowList,
+  setTargetWindowStrict,
+  getControlApi,
+  setControlApi,
+  regenerateControlApiToken,
+  getDiscordWebhook,
+  setDiscordWebhook,
+  testDiscordWebhook,
+  getSessionWatch,
+  setSessionWatch,
+  autoplayControl,
+  autoplayIntervals,
+  autoplaySetInterval,
+  autoplayState,
+  onAutoplayNext,
+  autoplaySetQueue,
+  onControlDecision,
+  onDiscordWebhookStatus,
+  onSessionAvailable,
+  onAutoplayStatus,
+  massPermAction,
+  massPermClear,
+  massPermConfigure,
+  massPermLoad,
+  massPermSeek,
+  massPermState,
+  onMassPermStatus,
   sendCustomToActiveWindow,
   startClipboardWatch,
   stopClipboardWatch,
+  type AutoplayIntervals,
+  type AutoplaySnapshot,
+  type MapInfoProgress,
+  type MapInfoProviderConfig,
+  type MapInfoProviderInfo,
+  type MapLintReport,
+  type MapInfoResponse,
+  type MapInfoResult,
+  type MapcodesCaptured,
+  type MassPermAction,
+  type ControlApiInfo,
+  type DiscordWebhookStatus,
+  type MassPermSnapshot,
+  type SessionWatch,
+  type TargetWindowConfig,
 } from './tauri'
 
+// Payload de uma categoria de origem (fila mista manda um por categoria).
+type SubmitPart = { category: string; payload: ExportPayloadV1 }
+
+// querySelector do modal de configurações
+type SettingsQuery = <T extends HTMLElement>(sel: string) => T
+
+// post_all para na primeira mensagem que falha; diz quantas chegaram antes
+function describeDiscordPost(st: DiscordWebhookStatus): string {
+  if (!st.error) return `session summary posted (${st.total} message(s)).`
+  if (st.posted > 0) return `${st.posted} of ${st.total} message(s) posted, then: ${st.error}`
+  return `webhook failed: ${st.error}`
+}
+
 function nowIso(): string {
   return new Date().toISOString()
 }
@@ -70,20 +138,13 @@ function createItem(mapcode: string): QueueItem {
   }
 }
 
-const DECISION_LABEL: Record<NonNullable<QueueItem['decision']>, string> = {
-  left_as_is: 'left as is',
-  p1ed: "p1'ed",
-  will_be_discussed: 'will be discussed',
-  ignored: 'ignored',
-}
-
 const REVIEW_MAX_CHARS = 2000
 
 export function initApp(root: HTMLElement): void {
   let state: AppState = loadState()
   let unlistenClipboard: (() => void) | null = null
   let clipboardDebounceTimer: number | null = null
-  let clipboardPendingText: string | null = null
+  let clipboardPendingText: MapcodesCaptured | null = null
   let clipboardLastProcessed: string | null = null
   let detailsBoundId: string | null = null
   let reviewSaveTimer: number | null = null
@@ -104,12 +165,23 @@ export function initApp(root: HTMLElement): void {
   let updaterAutoTried = false
   let runtimeVersion = APP_VERSION
 
-  // Mass perm (local UI state)
+  // Avisos do lint por item (id), refeitos quando o XML chega
+  const lintReports = new Map<string, MapLintReport>()
+
+  // Miniaturas da fila: mapcode -> data URL ('' = sem preview); renderizadas uma por vez
+  const queuePreviews = new Map<string, string>()
+  let queuePreviewsLoading = false
+
+  // Autoplay (espelho do timer no Rust, atualizado pelo evento `autoplay_status`)
+  let autoplay: AutoplaySnapshot | null = null
+  let autoplayIntervalsCache: AutoplayIntervals | null = null
+  let autoplayQueueKey: string | null = null
+
+  // Mass perm (espelho do runner no Rust, atualizado pelo evento `massperm_status`)
   let massPermMapcodes: string[] = []
   let massPermIndex = 0
   let massPermLastSentIndex: number | null = null
   let massPermRunning = false
-  let massPermTimer: number | null = null
   let massPermInFlight = false
   let massPermCategoryCode: string = 'P4'
   let massPermHotkeysEnabled = true
@@ -152,6 +224,7 @@ export function initApp(root: HTMLElement): void {
           <button id="newSession" class="btn danger">New session</button>
           <button id="exportJson" class="btn">Export JSON</button>
           <button id="openMassPerm" class="btn">Mass perm</button>
+          <button id="openSettings" class="btn">Settings</button>
         </div>
         <div class="settings">
           <label class="field">
@@ -186,6 +259,12 @@ export function initApp(root: HTMLElement): void {
             </label>
             <button id="importFromApi" class="btn">Import from Discord session</button>
           </div>
+          <div class="row">
+            <label class="field">
+              <span>Also fetch (mixed queue)</span>
+              <input id="apiExtraCategories" type="text" placeholder="e.g. P5, P9" />
+            </label>
+          </div>
           <div class="row">
             <button id="addFromClipboard" class="btn">Add from clipboard</button>
             <label class="field checkbox">
@@ -213,6 +292,11 @@ export function initApp(root: HTMLElement): void {
                 <span>Hotkeys</span>
               </label>
               <button id="reviewHotkeysConfig" class="btn">Config hotkeys</button>
+              <label class="field" title="Seconds per map in autoplay (saved per category)">
+                <span>Auto (s)</span>
+                <input id="autoplaySecs" type="number" min="5" max="900" step="5" style="width: 64px;" />
+              </label>
+              <button id="autoplayToggle" class="btn" title="Plays the next map every N seconds. Review hotkeys pause it.">Autoplay</button>
               <label class="field checkbox">
                 <input id="showIgnored" type="checkbox" />
                 <span>Show ignored</span>
@@ -240,6 +324,7 @@ export function initApp(root: HTMLElement): void {
     <div id="aboutModal" class="wizardOverlay" style="display:none"></div>
     <div id="hotkeysModal" class="wizardOverlay" style="display:none"></div>
     <div id="customCommand" class="wizardOverlay" style="display:none"></div>
+    <div id="settingsModal" class="wizardOverlay" style="display:none"></div>
   `
 
   const els = {
@@ -253,9 +338,11 @@ export function initApp(root: HTMLElement): void {
     importFile: root.querySelector<HTMLButtonElement>('#importFile')!,
     apiCategory: root.querySelector<HTMLSelectElement>('#apiCategory')!,
     importFromApi: root.querySelector<HTMLButtonElement>('#importFromApi')!,
+    apiExtraCategories: root.querySelector<HTMLInputElement>('#apiExtraCategories')!,
     newSession: root.querySelector<HTMLButtonElement>('#newSession')!,
     exportJson: root.querySelector<HTMLButtonElement>('#exportJson')!,
     openMassPerm: root.querySelector<HTMLButtonElement>('#openMassPerm')!,
+    openSettings: root.querySelector<HTMLButtonElement>('#openSettings')!,
     homeBtn: root.querySelector<HTMLButtonElement>('#homeBtn')!,
     exportSessionBtn: root.querySelector<HTMLButtonElement>('#exportSessionBtn')!,
     finishReviewBtn: root.querySelector<HTMLButtonElement>('#finishReviewBtn')!,
@@ -267,6 +354,8 @@ export function initApp(root: HTMLElement): void {
     queueCommandMode: root.querySelector<HTMLSelectElement>('#queueCommandMode')!,
     reviewHotkeys: root.querySelector<HTMLInputElement>('#reviewHotkeys')!,
     reviewHotkeysConfig: root.querySelector<HTMLButtonElement>('#reviewHotkeysConfig')!,
+    autoplaySecs: root.querySelector<HTMLInputElement>('#autoplaySecs')!,
+    autoplayToggle: root.querySelector<HTMLButtonElement>('#autoplayToggle')!,
     showIgnored: root.querySelector<HTMLInputElement>('#showIgnored')!,
     details: root.querySelector<HTMLDivElement>('#details')!,
     wizard: root.querySelector<HTMLDivElement>('#wizard')!,
@@ -278,6 +367,7 @@ export function initApp(root: HTMLElement): void {
     aboutModal: root.querySelector<HTMLDivElement>('#aboutModal')!,
     hotkeysModal: root.querySelector<HTMLDivElement>('#hotkeysModal')!,
     customCommand: root.querySelector<HTMLDivElement>('#customCommand')!,
+    settingsModal: root.querySelector<HTMLDivElement>('#settingsModal')!,
     authOverlay: root.querySelector<HTMLDivElement>('#authOverlay')!,
     confirmMassPermLeave: root.querySelector<HTMLDivElement>('#confirmMassPermLeave')!,
     settings: root.querySelector<HTMLDivElement>('.settings')!,
@@ -322,6 +412,7 @@ export function initApp(root: HTMLElement): void {
     els.newSession.style.display = active ? 'none' : 'inline-flex'
     els.exportJson.style.display = active ? 'none' : 'inline-flex'
     els.openMassPerm.style.display = active ? 'none' : 'inline-flex'
+    els.openSettings.style.display = active ? 'none' : 'inline-flex'
 
     // quando sessão está ativa, esconde settings (pedido: apenas 3 botões)
     els.settings.style.display = active ? 'none' : 'flex'
@@ -493,6 +584,407 @@ export function initApp(root: HTMLElement): void {
     closeBtn.addEventListener('click', () => close())
   }
 
+  function openSettingsModal(): void {
+    els.settingsModal.style.display = 'grid'
+    els.settingsModal.innerHTML = `
+      <div class="wizardCard">
+        <div class="wizardHeader">
+          <div>
+            <div class="wizardTitle">Settings</div>
+            <div class="wizardHint">Saved on this computer; applied right away.</div>
+          </div>
+        </div>
+        <div class="wizardBody">
+          <div class="panelTitle">Keyboard</div>
+          <div class="row">
+            <label class="field" title="Physical layout used to resolve hotkeys such as Cmd+< or Ctrl+?">
+              <span>Layout</span>
+              <select id="setKeyboardLayout">
+                <option value="us">US (QWERTY)</option>
+                <option value="abnt2">Brazilian (ABNT2)</option>
+                <option value="azerty">French (AZERTY)</option>
+              </select>
+            </label>
+          </div>
+          <div class="panelTitle">Map info source</div>
+          <div class="wizardHint" id="setSourceHint"></div>
+          <div class="row">
+            <label class="field">
+              <span>Source</span>
+              <select id="setSourceKind">
+                <option value="default">From env/.env</option>
+                <option value="cypher801">Cypher801</option>
+                <option value="localDir">Local directory</option>
+              </select>
+            </label>
+            <label class="field" style="flex: 1;" data-source="cypher801">
+              <span>Base URL (empty = env/.env or default)</span>
+              <input id="setSourceUrl" type="text" placeholder="https://cypher801.app/" />
+            </label>
+          </div>
+          <div class="row">
+            <label class="field" style="flex: 1;" data-source="cypher801" title="Stored in the app data dir; a new key replaces the old one">
+              <span>API key (empty = env/.env)</span>
+              <input id="setSourceKey" type="password" autocomplete="off" />
+            </label>
+            <label class="field" style="flex: 1;" data-source="localDir">
+              <span>Directory with &lt;id&gt;.xml files</span>
+              <input id="setSourceDir" type="text" />
+            </label>
+            <button class="btn" id="setSourceSave">Save</button>
+          </div>
+          <div class="panelTitle">Map info cache</div>
+          <div class="wizardHint" id="setCacheHint"></div>
+          <div class="row">
+            <button class="btn" id="setCacheRefresh">Refresh</button>
+            <button class="btn" id="setCacheClearExpired">Clear expired</button>
+            <button class="btn" id="setCacheClearAll" title="Every map is fetched again on the next lookup">Clear all</button>
+          </div>
+          <div class="panelTitle">Target window</div>
+          <div class="wizardHint" id="setTargetHint"></div>
+          <div class="row">
+            <label class="field" style="flex: 1;" title="Commands are only typed when the focused window title contains one of these (class:x checks WM_CLASS instead)">
+              <span>Allowed windows (one per line, class:x for WM_CLASS; empty = no check)</span>
+              <textarea id="setTargetAllow" class="textarea" rows="4"></textarea>
+            </label>
+          </div>
+          <div class="row">
+            <label class="field checkbox" title="Refuse to type when the focused window can't be read (Wayland, Windows, macOS)">
+              <input id="setTargetStrict" type="checkbox" />
+              <span>Strict</span>
+            </label>
+            <button class="btn" id="setTargetSave">Save list</button>
+            <button class="btn" id="setTargetDefaults">Defaults</button>
+          </div>
+          <div class="panelTitle">Local control API</div>
+          <div class="wizardHint" id="setApiHint"></div>
+          <div class="row">
+            <label class="field checkbox" title="HTTP API on 127.0.0.1 for stream decks, OBS and bots">
+              <input id="setApiEnabled" type="checkbox" />
+              <span>Enabled</span>
+            </label>
+            <label class="field">
+              <span>Port</span>
+              <input id="setApiPort" type="number" min="1" max="65535" style="width: 90px;" />
+            </label>
+            <button class="btn" id="setApiApply">Apply</button>
+          </div>
+          <div class="row">
+            <label class="field" style="flex: 1;">
+              <span>Token</span>
+              <input id="setApiToken" type="text" readonly />
+            </label>
+            <button class="btn" id="setApiCopy">Copy</button>
+            <button class="btn" id="setApiRegenerate" title="Clients using the old token stop working">Regenerate</button>
+          </div>
+          <div class="panelTitle">Discord webhook</div>
+          <div class="wizardHint">Posts a session summary after each accepted review. Empty = off.</div>
+          <div class="row">
+            <label class="field" style="flex: 1;">
+              <span>Webhook URL</span>
+              <input id="setDiscordUrl" type="text" placeholder="https://discord.com/api/webhooks/..." />
+            </label>
+            <button class="btn" id="setDiscordSave">Save</button>
+            <button class="btn" id="setDiscordTest" title="Posts a sample summary to the URL in the field">Test</button>
+          </div>
+          <div class="panelTitle">New session notifications</div>
+          <div class="wizardHint">Polls the Session API and notifies when a checked category gets a new batch. None checked = off.</div>
+          <div class="row" id="setWatchCategories" style="flex-wrap: wrap;">
+            ${REVIEW_CATEGORIES.map(
+              (c) => `
+                <label class="field checkbox" title="${c.description}">
+                  <input type="checkbox" value="${c.code}" />
+                  <span>${c.code}</span>
+                </label>`,
+            ).join('')}
+          </div>
+          <div class="row">
+            <label class="field" title="Between 1 and 60 minutes">
+              <span>Every (min)</span>
+              <input id="setWatchMinutes" type="number" min="1" max="60" style="width: 64px;" />
+            </label>
+            <button class="btn" id="setWatchSave">Save</button>
+          </div>
+          <div class="status" id="settingsStatus"></div>
+        </div>
+        <div class="wizardFooter">
+          <div class="wizardFooterRight">
+            <button class="btn primary" id="settingsClose">Close</button>
+          </div>
+        </div>
+      </div>
+    `
+
+    const q: SettingsQuery = <T extends HTMLElement>(sel: string) => els.settingsModal.querySelector<T>(sel)!
+    const statusEl = q<HTMLDivElement>('#settingsStatus')
+    const say = (msg: string) => {
+      statusEl.textContent = msg
+    }
+    const layoutSel = q<HTMLSelectElement>('#setKeyboardLayout')
+    layoutSel.value = state.settings.keyboardLayout
+
+    layoutSel.addEventListener('change', async () => {
+      const prev = state.settings.keyboardLayout
+      try {
+        await changeKeyboardLayout(layoutSel.value as KeyboardLayout)
+        statusEl.textContent = 'Keyboard layout saved; hotkeys re-registered.'
+      } catch (e) {
+        layoutSel.value = prev
+        statusEl.textContent = `Keyboard layout: ${String(e)}`
+      }
+    })
+
+    void wireMapInfoSourceSettings(q, say)
+    void wireMapInfoCacheSettings(q, say)
+    void wireTargetWindowSettings(q, say)
+    void wireControlApiSettings(q, say)
+    void wireDiscordWebhookSettings(q, say)
+    void wireSessionWatchSettings(q, say)
+
+    q<HTMLButtonElement>('#settingsClose').addEventListener('click', () => {
+      els.settingsModal.style.display = 'none'
+      els.settingsModal.innerHTML = ''
+    })
+  }
+
+  async function wireMapInfoSourceSettings(q: SettingsQuery, say: (msg: string) => void): Promise<void> {
+    const kindEl = q<HTMLSelectElement>('#setSourceKind')
+    const urlEl = q<HTMLInputElement>('#setSourceUrl')
+    const keyEl = q<HTMLInputElement>('#setSourceKey')
+    const dirEl = q<HTMLInputElement>('#setSourceDir')
+    const hintEl = q<HTMLDivElement>('#setSourceHint')
+    const fields = Array.from(els.settingsModal.querySelectorAll<HTMLElement>('[data-source]'))
+    const showFields = () => {
+      for (const f of fields) f.style.display = f.dataset.source === kindEl.value ? '' : 'none'
+    }
+    const fill = (info: MapInfoProviderInfo) => {
+      hintEl.textContent = `In use: ${info.kind === 'localDir' ? 'local directory' : 'Cypher801'} (${info.location}).`
+    }
+    kindEl.addEventListener('change', showFields)
+    showFields()
+    try {
+      fill(await getMapInfoProvider())
+    } catch (e) {
+      say(`Map info source: ${String(e)}`)
+      return
+    }
+
+    q<HTMLButtonElement>('#setSourceSave').addEventListener('click', async () => {
+      const kind = kindEl.value as MapInfoProviderConfig['kind']
+      const config: MapInfoProviderConfig =
+        kind === 'cypher801'
+          ? { kind, baseUrl: urlEl.value.trim() || undefined, key: keyEl.value.trim() || undefined }
+          : kind === 'localDir'
+            ? { kind, dir: dirEl.value.trim() }
+            : { kind: 'default' }
+      if (config.kind === 'localDir' && !config.dir) {
+        say('Map info source: choose a directory.')
+        return
+      }
+      try {
+        fill(await setMapInfoProvider(config))
+        // a chave fica só no arquivo do app; não volta para a tela
+        keyEl.value = ''
+        say('Map info source saved.')
+      } catch (e) {
+        say(`Map info source: ${String(e)}`)
+      }
+    })
+  }
+
+  async function wireMapInfoCacheSettings(q: SettingsQuery, say: (msg: string) => void): Promise<void> {
+    const hintEl = q<HTMLDivElement>('#setCacheHint')
+    const refresh = async () => {
+      try {
+        const st = await mapInfoCacheStats()
+        const kb = Math.round(st.bytes / 1024)
+        const hours = Math.round(st.ttlSecs / 3600)
+        hintEl.textContent = `${st.entries} map(s), ${st.expired} expired, ${kb} KB; entries expire after ${hours} h. ${st.dir}`
+      } catch (e) {
+        hintEl.textContent = ''
+        say(`Map info cache: ${String(e)}`)
+      }
+    }
+    const clear = async (expiredOnly: boolean) => {
+      try {
+        const removed = await clearMapInfoCache(expiredOnly)
+        say(`Map info cache: removed ${removed} entr${removed === 1 ? 'y' : 'ies'}.`)
+      } catch (e) {
+        say(`Map info cache: ${String(e)}`)
+      }
+      await refresh()
+    }
+    q<HTMLButtonElement>('#setCacheRefresh').addEventListener('click', () => void refresh())
+    q<HTMLButtonElement>('#setCacheClearExpired').addEventListener('click', () => void clear(true))
+    q<HTMLButtonElement>('#setCacheClearAll').addEventListener('click', () => void clear(false))
+    await refresh()
+  }
+
+  async function wireTargetWindowSettings(q: SettingsQuery, say: (msg: string) => void): Promise<void> {
+    const allowEl = q<HTMLTextAreaElement>('#setTargetAllow')
+    const strictEl = q<HTMLInputElement>('#setTargetStrict')
+    const hintEl = q<HTMLDivElement>('#setTargetHint')
+    const fill = (cfg: TargetWindowConfig) => {
+      allowEl.value = cfg.allowList.join('\n')
+      strictEl.checked = cfg.strict
+      hintEl.textContent = !cfg.allowList.length
+        ? 'Check off: commands go to whatever window is focused.'
+        : cfg.checkable
+          ? `Defaults: ${cfg.defaults.join(', ')}.`
+          : cfg.strict
+            ? "The focused window can't be read here, so strict mode refuses to type."
+            : "The focused window can't be read here; commands are typed unchecked unless Strict is on."
+    }
+    try {
+      fill(await getTargetWindowConfig())
+    } catch (e) {
+      say(`Target window: ${String(e)}`)
+      return
+    }
+
+    const save = async (patterns: string[] | null) => {
+      try {
+        fill(await setTargetWindowAllowList(patterns))
+        say('Target window list saved.')
+      } catch (e) {
+        say(`Target window: ${String(e)}`)
+      }
+    }
+    q<HTMLButtonElement>('#setTargetSave').addEventListener('click', () => {
+      void save(allowEl.value.split('\n'))
+    })
+    q<HTMLButtonElement>('#setTargetDefaults').addEventListener('click', () => {
+      void save(null)
+    })
+    strictEl.addEventListener('change', async () => {
+      try {
+        fill(await setTargetWindowStrict(strictEl.checked))
+        say(strictEl.checked ? 'Strict target window check on.' : 'Strict target window check off.')
+      } catch (e) {
+        strictEl.checked = !strictEl.checked
+        say(`Target window: ${String(e)}`)
+      }
+    })
+  }
+
+  async function wireControlApiSettings(q: SettingsQuery, say: (msg: string) => void): Promise<void> {
+    const enabledEl = q<HTMLInputElement>('#setApiEnabled')
+    const portEl = q<HTMLInputElement>('#setApiPort')
+    const tokenEl = q<HTMLInputElement>('#setApiToken')
+    const hintEl = q<HTMLDivElement>('#setApiHint')
+    const fill = (info: ControlApiInfo) => {
+      enabledEl.checked = info.enabled
+      portEl.value = String(info.port)
+      tokenEl.value = info.token
+      const base = `http://127.0.0.1:${info.port}`
+      hintEl.textContent = info.running
+        ? `Listening on ${base}. OBS overlay: ${base}/overlay?token=<token>`
+        : info.enabled
+          ? 'Enabled but not listening (port in use?).'
+          : 'Off.'
+    }
+    const run = async (label: string, call: () => Promise<ControlApiInfo>) => {
+      try {
+        fill(await call())
+        say(label)
+      } catch (e) {
+        say(`Control API: ${String(e)}`)
+      }
+    }
+    try {
+      fill(await getControlApi())
+    } catch (e) {
+      say(`Control API: ${String(e)}`)
+      return
+    }
+
+    q<HTMLButtonElement>('#setApiApply').addEventListener('click', () => {
+      const port = Number.parseInt(portEl.value, 10)
+      if (!Number.isInteger(port) || port < 1 || port > 65535) {
+        say('Control API: port must be between 1 and 65535.')
+        return
+      }
+      void run('Control API settings saved.', () => setControlApi(enabledEl.checked, port))
+    })
+    q<HTMLButtonElement>('#setApiRegenerate').addEventListener('click', () => {
+      void run('New control API token generated; update your clients.', () => regenerateControlApiToken())
+    })
+    q<HTMLButtonElement>('#setApiCopy').addEventListener('click', async () => {
+      try {
+        await writeClipboardText(tokenEl.value)
+        say('Control API token copied.')
+      } catch (e) {
+        say(`Control API: ${String(e)}`)
+      }
+    })
+  }
+
+  async function wireDiscordWebhookSettings(q: SettingsQuery, say: (msg: string) => void): Promise<void> {
+    const urlEl = q<HTMLInputElement>('#setDiscordUrl')
+    try {
+      urlEl.value = (await getDiscordWebhook()).webhookUrl ?? ''
+    } catch (e) {
+      say(`Discord webhook: ${String(e)}`)
+      return
+    }
+
+    q<HTMLButtonElement>('#setDiscordSave').addEventListener('click', async () => {
+      try {
+        const cfg = await setDiscordWebhook(urlEl.value.trim() || null)
+        urlEl.value = cfg.webhookUrl ?? ''
+        say(cfg.webhookUrl ? 'Discord webhook saved.' : 'Discord webhook off.')
+      } catch (e) {
+        say(`Discord webhook: ${String(e)}`)
+      }
+    })
+    const testBtn = q<HTMLButtonElement>('#setDiscordTest')
+    testBtn.addEventListener('click', async () => {
+      testBtn.disabled = true
+      say('Discord: posting a sample summary...')
+      try {
+        say(`Discord test: ${describeDiscordPost(await testDiscordWebhook(urlEl.value.trim() || null))}`)
+      } catch (e) {
+        say(`Discord test: ${String(e)}`)
+      } finally {
+        testBtn.disabled = false
+      }
+    })
+  }
+
+  async function wireSessionWatchSettings(q: SettingsQuery, say: (msg: string) => void): Promise<void> {
+    const boxes = Array.from(q<HTMLDivElement>('#setWatchCategories').querySelectorAll<HTMLInputElement>('input'))
+    const minutesEl = q<HTMLInputElement>('#setWatchMinutes')
+    // categorias vigiadas fora da lista de review (setadas pelo comando) não somem ao salvar
+    let others: string[] = []
+    const fill = (w: SessionWatch) => {
+      for (const box of boxes) box.checked = w.categories.includes(box.value)
+      others = w.categories.filter((c) => !boxes.some((b) => b.value === c))
+      minutesEl.value = String(Math.round(w.intervalSecs / 60))
+    }
+    try {
+      fill(await getSessionWatch())
+    } catch (e) {
+      say(`Session watch: ${String(e)}`)
+      return
+    }
+
+    q<HTMLButtonElement>('#setWatchSave').addEventListener('click', async () => {
+      const minutes = Number.parseInt(minutesEl.value, 10)
+      if (!Number.isInteger(minutes) || minutes < 1 || minutes > 60) {
+        say('Session watch: interval must be between 1 and 60 minutes.')
+        return
+      }
+      const categories = [...boxes.filter((b) => b.checked).map((b) => b.value), ...others]
+      try {
+        const w = await setSessionWatch(categories, minutes * 60)
+        fill(w)
+        say(w.categories.length ? `Watching ${w.categories.join(', ')}.` : 'Session watch off.')
+      } catch (e) {
+        say(`Session watch: ${String(e)}`)
+      }
+    })
+  }
+
   async function checkForUpdatesOnBoot(): Promise<void> {
     if (updaterAutoTried) return
     updaterAutoTried = true
@@ -893,37 +1385,21 @@ export function initApp(root: HTMLElement): void {
         close()
 
         // envia review para Discord Session API (best effort)
-        let submitOk = false
-        let submitStatus = 0
-        let submitMsg: string | null = null
+        const parts = splitPayloadBySource(payload, category)
+        const accepted = new Set<string>()
         const postAsPrivate = Boolean(frPostAsPrivate?.checked)
-        try {
-          const res = await submitSessionReview(
-            category,
-            payload,
-            state.settings.authToken,
-            isVotecrewUser(),
-            postAsPrivate,
-          )
-          submitOk = Boolean(res?.ok)
-          submitStatus = Number(res?.status ?? 0)
-          submitMsg = (res?.error ?? res?.body ?? null) as any
-        } catch (e) {
-          submitOk = false
-          submitStatus = 0
-          submitMsg = String(e)
-        }
+        const outcome = await submitParts(parts, accepted, postAsPrivate)
 
         endCurrentSession()
         openLauncher({ allowReturnToSession: false })
         setStatus(`Session saved: ${finalPath}`)
 
         openSubmitReviewResultModal({
-          ok: submitOk,
-          status: submitStatus,
-          message: submitMsg,
+          ...outcome,
           category,
           filePath: finalPath,
+          // só faz sentido repetir quando alguma categoria ficou de fora
+          retry: parts.length > 1 ? () => submitParts(parts, accepted, postAsPrivate) : undefined,
         })
       } catch (e) {
         setStatus(`Failed to finish review: ${String(e)}`)
@@ -931,18 +1407,94 @@ export function initApp(root: HTMLElement): void {
     })
   }
 
+  // Envia as partes ainda não aceitas; `accepted` guarda as aceitas entre tentativas, para o
+  // retry não postar de novo a review de uma categoria que já entrou.
+  async function submitParts(
+    parts: SubmitPart[],
+    accepted: Set<string>,
+    postAsPrivate: boolean,
+  ): Promise<{ ok: boolean; status: number; message: string | null }> {
+    const results: Array<{ category: string; ok: boolean; status: number; msg: string | null }> = []
+    for (const part of parts) {
+      if (accepted.has(part.category)) continue
+      try {
+        const res = await submitSessionReview(
+          part.category,
+          part.payload,
+          state.settings.authToken,
+          isVotecrewUser(),
+          postAsPrivate,
+          authUser?.name ?? authUser?.username ?? null,
+        )
+        const ok = Boolean(res?.ok)
+        if (ok) accepted.add(part.category)
+        results.push({
+          category: part.category,
+          ok,
+          status: Number(res?.status ?? 0),
+          msg: res?.itemErrors?.length
+            ? res.itemErrors.map((e) => e.message).join('\n')
+            : ((res?.error ?? res?.body ?? null) as any),
+        })
+      } catch (e) {
+        results.push({ category: part.category, ok: false, status: 0, msg: String(e) })
+      }
+    }
+    const failed = results.filter((r) => !r.ok)
+    const lines = failed.map((r) => `${r.category}: ${r.msg ?? `HTTP ${r.status}`}`)
+    // no retry (ou com falha parcial) vale lembrar o que já tinha entrado
+    if (accepted.size && (failed.length || results.length < parts.length)) {
+      lines.push(`Already accepted: ${Array.from(accepted).join(', ')}`)
+    }
+    return {
+      ok: failed.length === 0,
+      status: (failed[0] ?? results[0])?.status ?? 0,
+      message: parts.length === 1 ? (results[0]?.msg ?? null) : lines.join('\n') || null,
+    }
+  }
+
+  // Fila mista: um payload por categoria de origem, cada um com a thread dela.
+  function splitPayloadBySource(payload: ExportPayloadV1, category: string): SubmitPart[] {
+    const groups = new Map<string, ExportPayloadV1['items']>()
+    for (const it of payload.items) {
+      const key = it.sourceCategory || category
+      groups.set(key, [...(groups.get(key) ?? []), it])
+    }
+    return Array.from(groups, ([cat, items]) => ({
+      category: cat,
+      payload: {
+        ...payload,
+        session: payload.session
+          ? { ...payload.session, category: cat, threadId: items[0]?.sourceThreadId ?? payload.session.threadId ?? null }
+          : null,
+        // campos só do app; a Session API recebe o item como sempre
+        items: items.map((it) => {
+          const out = { ...it }
+          delete out.sourceCategory
+          delete out.sourceThreadId
+          return out
+        }),
+      },
+    }))
+  }
+
   function openSubmitReviewResultModal(args: {
     ok: boolean
     status: number
     message: string | null
     category: string
     filePath: string
+    // reenvia só as categorias que falharam
+    retry?: () => Promise<{ ok: boolean; status: number; message: string | null }>
   }): void {
     els.submitReviewResult.style.display = 'grid'
+    const canRetry = !args.ok && Boolean(args.retry)
     const title = args.ok ? 'Review submitted' : 'Failed to submit review'
     const hint = args.ok
       ? 'The review was sent to Discord successfully.'
-      : 'The review could not be sent to Discord. You can retry later using the saved JSON.'
+      : canRetry
+        ? 'Some categories were not accepted. Retry sends only those; the accepted ones are not sent again.'
+        : 'The review could not be sent to Discord. You can retry later using the saved JSON.'
 
     els.submitReviewResult.innerHTML = `
       <div class="wizardCard">
@@ -973,6 +1525,7 @@ export function initApp(root: HTMLElement): void {
         </div>
         <div class="wizardFooter">
           <div class="wizardFooterRight">
+            ${canRetry ? '<button class="btn" id="srRetry">Retry failed</button>' : ''}
             <button class="btn primary" id="srOk">OK</button>
           </div>
         </div>
@@ -985,15 +1538,49 @@ export function initApp(root: HTMLElement): void {
       els.submitReviewResult.innerHTML = ''
     }
     srOk.addEventListener('click', () => close())
+
+    const srRetry = els.submitReviewResult.querySelector<HTMLButtonElement>('#srRetry')
+    srRetry?.addEventListener('click', async () => {
+      srRetry.disabled = true
+      srOk.disabled = true
+      const outcome = await args.retry!()
+      openSubmitReviewResultModal({ ...args, ...outcome })
+    })
   }
 
   function syncNp(): void {
     const sel = getSelected()
-    void setNpContext({ mapcode: sel?.mapcode ?? null, commandMode: state.settings.commandMode }).catch(() => {
+    const idx = indexOfSelected()
+    void syncAutoplayQueue()
+    void setNpContext({
+      mapcode: sel?.mapcode ?? null,
+      commandMode: state.settings.commandMode,
+      category: sel?.sourceCategory ?? state.session?.category ?? null,
+      index: idx >= 0 ? idx : null,
+      total: getVisibleQueueItems().length,
+      author: sel?.author ?? null,
+    }).catch(() => {
       // best effort
     })
   }
 
+  // Só reenvia quando a fila visível muda (ordem, filtro, categoria de origem).
+  async function syncAutoplayQueue(): Promise<void> {
+    const queue = getVisibleQueueItems().map((it) => ({
+      mapcode: it.mapcode,
+      category: it.sourceCategory ?? state.session?.category ?? null,
+    }))
+    const key = JSON.stringify(queue)
+    if (key === autoplayQueueKey) return
+    autoplayQueueKey = key
+    try {
+      await autoplaySetQueue(queue)
+    } catch {
+      // tenta de novo no próximo sync
+      autoplayQueueKey = null
+    }
+  }
+
   function select(id: string | null): void {
     state.selectedId = id
     persist()
@@ -1031,9 +1618,11 @@ export function initApp(root: HTMLElement): void {
     syncNp()
     setStatus(`Added ${toAdd.length} mapcode(s) (${sourceLabel}).`)
 
-    void hydrateMapInfoForItems(addedItems).catch(() => {
-      // best effort
-    })
+    void hydrateMapInfoForItems(addedItems)
+      .then(() => lintQueueItems(addedItems))
+      .catch(() => {
+        // best effort
+      })
   }
 
   function normalizeCategorySelection(codeOrNumber: string): { code: string; number: number | null } {
@@ -1063,13 +1652,16 @@ export function initApp(root: HTMLElement): void {
     return parseMapcodesFromText(text)
   }
 
-  function stopMassPerm(): void {
-    massPermRunning = false
-    massPermInFlight = false
-    if (massPermTimer) {
-      window.clearInterval(massPermTimer)
-      massPermTimer = null
-    }
+  function applyMassPermSnapshot(snap: MassPermSnapshot): void {
+    massPermMapcodes = snap.mapcodes
+    massPermIndex = snap.index
+    massPermLastSentIndex = snap.lastSent
+    massPermRunning = snap.status === 'running'
+    massPermCategoryCode = `P${snap.category}`
+    massPermIntervalSec = snap.intervalMs / 1000
+    if (els.massPerm.style.display !== 'grid') return
+    renderMassPerm()
+    if (snap.message) setMassPermStatus(snap.message)
   }
 
   function stopCustomCommand(): void {
@@ -1082,13 +1674,13 @@ export function initApp(root: HTMLElement): void {
   }
 
   function resetMassPermData(): void {
-    stopMassPerm()
-    massPermMapcodes = []
-    massPermIndex = 0
-    massPermLastSentIndex = null
-    massPermCategoryCode = 'P4'
     massPermHotkeysEnabled = true
-    massPermIntervalSec = 0.3
+    void massPermClear()
+      .then(() => massPermConfigure({ categoryNumber: 4, intervalMs: 300, playFirst: false }))
+      .then(applyMassPermSnapshot)
+      .catch(() => {
+        // best effort
+      })
   }
 
   function resetCustomCommandData(): void {
@@ -1151,12 +1743,32 @@ export function initApp(root: HTMLElement): void {
     })
   }
 
-  async function massPermSendPerm(mapcode: string, categoryNumber: number, label: string): Promise<void> {
+  // O envio acontece no Rust; a resposta (e o evento) trazem o estado novo.
+  async function runMassPermAction(action: MassPermAction): Promise<void> {
+    if (massPermInFlight) return
+    massPermInFlight = true
+    let snap: MassPermSnapshot | null = null
     try {
-      const cmd = await sendPermToActiveWindow({ mapcode, categoryNumber })
-      setMassPermStatus(`${label}: sent ${cmd}`)
+      snap = await massPermAction(action)
     } catch (e) {
-      setMassPermStatus(`${label}: failed (${String(e)})`)
+      setMassPermStatus(String(e))
+    } finally {
+      massPermInFlight = false
+    }
+    if (snap) applyMassPermSnapshot(snap)
+  }
+
+  async function loadMassPermList(list: string[], source: string): Promise<void> {
+    const resolved = normalizeCategorySelection(massPermCategoryCode)
+    try {
+      const snap = await massPermLoad(uniqPreserveOrder(list), {
+        categoryNumber: resolved.number,
+        intervalMs: Math.round(massPermIntervalSec * 1000),
+      })
+      applyMassPermSnapshot(snap)
+      setMassPermStatus(`Loaded ${snap.total} map(s) from ${source}.`)
+    } catch (e) {
+      setMassPermStatus(`Failed to load list: ${String(e)}`)
     }
   }
 
@@ -1326,211 +1938,64 @@ export function initApp(root: HTMLElement): void {
       // clamp to 0.1..1.0 with step 0.1
       const clamped = Math.max(0.1, Math.min(1.0, Math.round(next * 10) / 10))
       massPermIntervalSec = clamped
-
-      // if running, restart timer with the new interval (keep progress)
-      if (massPermRunning) {
-        if (massPermTimer) {
-          window.clearInterval(massPermTimer)
-          massPermTimer = null
-        }
-
-        const intervalMs = Math.round(massPermIntervalSec * 1000)
-        massPermTimer = window.setInterval(async () => {
-          if (massPermInFlight) return
-          if (!massPermRunning) return
-          if (massPermIndex >= massPermMapcodes.length) {
-            stopMassPerm()
-            setMassPermStatus('Done.')
-            renderMassPerm()
-            return
-          }
-
-          const mc = massPermMapcodes[massPermIndex]!
-          massPermInFlight = true
-          try {
-            const resolved = normalizeCategorySelection(massPermCategoryCode)
-            if (!resolved.number) {
-              setMassPermStatus('Invalid category number.')
-              stopMassPerm()
-              renderMassPerm()
-              return
-            }
-            await massPermSendPerm(mc, resolved.number, 'auto')
-            massPermLastSentIndex = massPermIndex
-            massPermIndex += 1
-          } finally {
-            massPermInFlight = false
-            renderMassPerm()
-          }
-        }, intervalMs)
-      }
-
-      renderMassPerm()
+      // rodando, o intervalo novo vale a partir do próximo envio (mantém o progresso)
+      void massPermConfigure({ intervalMs: Math.round(clamped * 1000) })
+        .then(applyMassPermSnapshot)
+        .catch((e) => setMassPermStatus(`Failed to set interval: ${String(e)}`))
     })
 
     mpCategory.addEventListener('change', () => {
       massPermCategoryCode = mpCategory.value
-      renderMassPerm()
+      const resolved = normalizeCategorySelection(massPermCategoryCode)
+      if (resolved.number == null) {
+        renderMassPerm()
+        return
+      }
+      void massPermConfigure({ categoryNumber: resolved.number })
+        .then(applyMassPermSnapshot)
+        .catch((e) => setMassPermStatus(`Failed to set category: ${String(e)}`))
     })
 
     mpAddTextarea.addEventListener('click', () => {
-      stopMassPerm()
-      const list = extractMapcodesFromTextOrSessionJson(mpTextarea.value)
-      massPermMapcodes = uniqPreserveOrder(list)
-      massPermIndex = 0
-      massPermLastSentIndex = null
-      setMassPermStatus(`Loaded ${massPermMapcodes.length} map(s) from textarea.`)
-      renderMassPerm()
+      void loadMassPermList(extractMapcodesFromTextOrSessionJson(mpTextarea.value), 'textarea')
     })
 
     mpFromClipboard.addEventListener('click', async () => {
-      stopMassPerm()
       const txt = await readClipboardText()
       if (!txt) {
         setMassPermStatus('Clipboard is empty (or has no text).')
         return
       }
-      const list = extractMapcodesFromTextOrSessionJson(txt)
-      massPermMapcodes = uniqPreserveOrder(list)
-      massPermIndex = 0
-      massPermLastSentIndex = null
-      setMassPermStatus(`Loaded ${massPermMapcodes.length} map(s) from clipboard.`)
-      renderMassPerm()
+      await loadMassPermList(extractMapcodesFromTextOrSessionJson(txt), 'clipboard')
     })
 
     mpFromFile.addEventListener('click', async () => {
-      stopMassPerm()
       const path = await openImportFileDialog()
       if (!path) return
       const txt = await readTextFileFromPath(path)
-      const list = extractMapcodesFromTextOrSessionJson(txt)
-      massPermMapcodes = uniqPreserveOrder(list)
-      massPermIndex = 0
-      massPermLastSentIndex = null
-      setMassPermStatus(`Loaded ${massPermMapcodes.length} map(s) from file.`)
-      renderMassPerm()
+      await loadMassPermList(extractMapcodesFromTextOrSessionJson(txt), 'file')
     })
 
     mpClearList.addEventListener('click', () => {
-      stopMassPerm()
-      massPermMapcodes = []
-      massPermIndex = 0
-      massPermLastSentIndex = null
-      setMassPermStatus('Cleared.')
-      renderMassPerm()
+      void massPermClear()
+        .then(applyMassPermSnapshot)
+        .catch((e) => setMassPermStatus(`Failed to clear: ${String(e)}`))
     })
 
-    const startMassPerm = () => {
-      const resolved = normalizeCategorySelection(massPermCategoryCode)
-      if (!resolved.number) {
+    mpToggle.addEventListener('click', () => {
+      if (!massPermRunning && normalizeCategorySelection(massPermCategoryCode).number == null) {
         setMassPermStatus('Invalid category number.')
         return
       }
-      if (!massPermMapcodes.length) {
-        setMassPermStatus('No maps loaded.')
-        return
-      }
-      if (massPermIndex >= massPermMapcodes.length) {
-        setMassPermStatus('Already finished. Press Cancel to reset or load a new list.')
-        return
-      }
-      if (massPermRunning) return
-
-      massPermRunning = true
-      const intervalMs = Math.round(massPermIntervalSec * 1000)
-      massPermTimer = window.setInterval(async () => {
-        if (massPermInFlight) return
-        if (!massPermRunning) return
-        if (massPermIndex >= massPermMapcodes.length) {
-          stopMassPerm()
-          setMassPermStatus('Done.')
-          renderMassPerm()
-          return
-        }
-
-        const mc = massPermMapcodes[massPermIndex]!
-        massPermInFlight = true
-        try {
-          const resolved = normalizeCategorySelection(massPermCategoryCode)
-          if (!resolved.number) {
-            setMassPermStatus('Invalid category number.')
-            stopMassPerm()
-            renderMassPerm()
-            return
-          }
-          await massPermSendPerm(mc, resolved.number, 'auto')
-          massPermLastSentIndex = massPermIndex
-          massPermIndex += 1
-        } finally {
-          massPermInFlight = false
-          renderMassPerm()
-        }
-      }, intervalMs)
-
-      renderMassPerm()
-      setMassPermStatus('Running… focus the game window.')
-    }
-
-    const pauseMassPerm = () => {
-      stopMassPerm()
-      setMassPermStatus('Paused.')
-      renderMassPerm()
-    }
-
-    mpToggle.addEventListener('click', () => {
-      if (massPermRunning) {
-        pauseMassPerm()
-      } else {
-        startMassPerm()
-      }
+      void runMassPermAction('toggle')
     })
 
-    mpPlayCurrent.addEventListener('click', async () => {
-      if (massPermInFlight) return
-      const resolved = normalizeCategorySelection(massPermCategoryCode)
-      if (!resolved.number) {
-        setMassPermStatus('Invalid category number.')
-        return
-      }
-      const mc = massPermMapcodes[massPermIndex]
-      if (!mc) {
-        setMassPermStatus('No current map selected.')
-        return
-      }
-      massPermInFlight = true
-      await massPermSendPerm(mc, resolved.number, 'current')
-      massPermLastSentIndex = massPermIndex
-      massPermInFlight = false
-      renderMassPerm()
+    mpPlayCurrent.addEventListener('click', () => {
+      void runMassPermAction('playCurrent')
     })
 
-    mpPrev.addEventListener('click', async () => {
-      if (massPermInFlight) return
-      if (massPermRunning) {
-        setMassPermStatus('Pause first to go back.')
-        return
-      }
-      if (massPermIndex <= 0) {
-        setMassPermStatus('Already at the first map.')
-        return
-      }
-      const resolved = normalizeCategorySelection(massPermCategoryCode)
-      if (!resolved.number) {
-        setMassPermStatus('Invalid category number.')
-        return
-      }
-      const idx = massPermIndex - 1
-      const mc = massPermMapcodes[idx]
-      if (!mc) {
-        setMassPermStatus('No map to apply.')
-        return
-      }
-      massPermInFlight = true
-      await massPermSendPerm(mc, resolved.number, 'manual')
-      massPermLastSentIndex = idx
-      massPermIndex = idx
-      massPermInFlight = false
-      renderMassPerm()
+    mpPrev.addEventListener('click', () => {
+      void runMassPermAction('prev')
     })
 
     mpCancel.addEventListener('click', () => {
@@ -1544,48 +2009,17 @@ export function initApp(root: HTMLElement): void {
     if (mpQueue) {
       for (const btn of Array.from(mpQueue.querySelectorAll<HTMLButtonElement>('button.queueItem'))) {
         btn.addEventListener('click', () => {
-          if (massPermRunning || massPermInFlight) {
-            setMassPermStatus('Pause first to change the current map.')
-            return
-          }
           const idx = Number.parseInt(btn.dataset.index ?? '', 10)
           if (!Number.isFinite(idx)) return
-          massPermIndex = Math.max(0, Math.min(idx, massPermMapcodes.length - 1))
-          renderMassPerm()
+          void massPermSeek(idx)
+            .then(applyMassPermSnapshot)
+            .catch((e) => setMassPermStatus(String(e)))
         })
       }
     }
 
-    mpNext.addEventListener('click', async () => {
-      if (massPermInFlight) return
-      const resolved = normalizeCategorySelection(massPermCategoryCode)
-      if (!resolved.number) {
-        setMassPermStatus('Invalid category number.')
-        return
-      }
-      if (!massPermMapcodes.length) {
-        setMassPermStatus('No maps loaded.')
-        return
-      }
-      if (massPermIndex >= massPermMapcodes.length) {
-        setMassPermStatus('Done.')
-        return
-      }
-
-      // Next map = send the command for the next unsent map, then advance.
-      const idx = massPermIndex
-      const mc = massPermMapcodes[idx]
-      if (!mc) {
-        setMassPermStatus('No map to apply.')
-        return
-      }
-
-      massPermInFlight = true
-      await massPermSendPerm(mc, resolved.number, 'manual')
-      massPermLastSentIndex = idx
-      massPermIndex = idx + 1
-      massPermInFlight = false
-      renderMassPerm()
+    mpNext.addEventListener('click', () => {
+      void runMassPermAction('next')
     })
   }
 
@@ -2011,6 +2445,17 @@ export function initApp(root: HTMLElement): void {
     // mass perm pode rodar sem sessão; não força mostrar o shell
     renderMassPerm()
     setMassPermStatus('Load a list and choose a category.')
+    // retoma progresso salvo (o runner volta pausado)
+    void massPermState()
+      .then((snap) => {
+        applyMassPermSnapshot(snap)
+        if (snap.total > 0 && snap.status !== 'idle') {
+          setMassPermStatus(`Resumed: ${Math.min(snap.index, snap.total)}/${snap.total} done.`)
+        }
+      })
+      .catch(() => {
+        // best effort
+      })
     void applyMassPermHotkeysConfig(massPermHotkeysEnabled).catch(() => {
       // best effort
     })
@@ -2022,16 +2467,23 @@ export function initApp(root: HTMLElement): void {
       return
     }
 
-    const existing = new Set(state.items.map((i) => i.mapcode))
+    // Numa fila mista o mesmo mapcode pode vir de categorias diferentes; cada uma tem sua review.
+    const dedupeKey = (mapcode: string, sourceCategory?: string | null) => `${mapcode}|${sourceCategory ?? ''}`
+    const existing = new Set(state.items.map((i) => dedupeKey(i.mapcode, i.sourceCategory)))
     const toAdd: QueueItem[] = []
     for (const raw of items) {
       const normalized = normalizeMapcode(raw.mapcode)
       if (!normalized) continue
-      if (state.settings.dedupe && existing.has(normalized)) {
+      if (state.settings.dedupe && existing.has(dedupeKey(normalized, raw.sourceCategory))) {
         // Allow duplicates when they come from different submitters (same mapcode can be submitted by different people).
         const submitter = typeof raw.submitter === 'string' ? raw.submitter.trim() : ''
         if (!submitter) continue
-        const alreadyHasSameSubmitter = state.items.some((it) => it.mapcode === normalized && (it.submitter ?? '') === submitter)
+        const alreadyHasSameSubmitter = state.items.some(
+          (it) =>
+            it.mapcode === normalized &&
+            (it.sourceCategory ?? '') === (raw.sourceCategory ?? '') &&
+            (it.submitter ?? '') === submitter,
+        )
         if (alreadyHasSameSubmitter) continue
       }
       const base = createItem(normalized)
@@ -2049,9 +2501,11 @@ export function initApp(root: HTMLElement): void {
     syncNp()
     setStatus(`Added ${toAdd.length} mapcode(s) (${sourceLabel}).`)
 
-    void hydrateMapInfoForItems(toAdd).catch(() => {
-      // best effort
-    })
+    void hydrateMapInfoForItems(toAdd)
+      .then(() => lintQueueItems(toAdd))
+      .catch(() => {
+        // best effort
+      })
   }
 
   async function hydrateMapInfoForItems(items: QueueItem[]): Promise<void> {
@@ -2097,7 +2551,36 @@ export function initApp(root: HTMLElement): void {
     })
     if (!missing.length) return
 
-    const res = await fetchMapInfo(missing)
+    // progresso por lote na barra de status, depois da mensagem que já estava lá
+    const base = (els.inputStatus.textContent ?? '').replace(/\s*Map info: .*$/, '')
+    const showProgress = (p: MapInfoProgress) => {
+      const extra = [
+        p.cached ? `${p.cached} cached` : '',
+        p.notFound ? `${p.notFound} not found` : '',
+        p.failed ? `${p.failed} failed` : '',
+      ]
+        .filter(Boolean)
+        .join(', ')
+      const line = `Map info: ${p.done}/${p.total}${extra ? ` (${extra})` : ''}${p.done < p.total ? '…' : '.'}`
+      setStatus(base ? `${base} ${line}` : line)
+    }
+    const unlisten = await onMapInfoProgress(showProgress).catch(() => null)
+    let res: MapInfoResponse
+    try {
+      res = await fetchMapInfo(missing)
+    } finally {
+      unlisten?.()
+    }
+    // o último evento pode chegar depois da resposta: fecha com o resultado final
+    const count = (status: MapInfoResult['status']) => res.results.filter((r) => r.status === status).length
+    showProgress({
+      done: res.results.length,
+      total: res.results.length,
+      found: count('found'),
+      notFound: count('notFound'),
+      failed: count('failed'),
+      cached: res.cached,
+    })
     if (!res || res.error) return
 
     for (const entry of res.data) {
@@ -2105,8 +2588,10 @@ export function initApp(root: HTMLElement): void {
       // Apply to ALL items that match this mapcode (duplicates)
       const matches = state.items.filter((x) => String(x.mapcode).replace(/^@+/, '') === mc)
       if (!matches.length) continue
+      // XML novo: refaz a miniatura
+      if (matches.some((it) => it.xml !== (entry.xml ?? null))) queuePreviews.delete(mc)
       for (const it of matches) {
-        it.author = entry.author ?? null
+        it.author = entry.author || null
         it.xml = entry.xml ?? null
         it.p = typeof entry.p === 'number' ? entry.p : null
         it.updatedAt = nowIso()
@@ -2116,17 +2601,45 @@ export function initApp(root: HTMLElement): void {
     persist()
     // Atualiza UI se o selecionado ganhou dados
     updateDetailsValues()
+    void loadQueuePreviews()
+    // autor novo também vai para o overlay do stream
+    syncNp()
+  }
+
+  // Roda depois do fetch_map_info; itens ainda sem XML ficam para a próxima vez.
+  async function lintQueueItems(items: QueueItem[]): Promise<void> {
+    const targets = items.filter((it) => it.xml && Number.parseInt(String(it.mapcode).replace(/^@+/, ''), 10) > 0)
+    if (!targets.length) return
+    const reports = await lintMaps(
+      targets.map((it) => ({
+        mapId: Number.parseInt(String(it.mapcode).replace(/^@+/, ''), 10),
+        category: it.sourceCategory ?? state.session?.category ?? null,
+        xml: it.xml,
+      })),
+    )
+    targets.forEach((it, i) => {
+      const report = reports[i]
+      if (report) lintReports.set(it.id, report)
+    })
+    renderQueue()
+    updateDetailsValues()
+  }
+
+  function lintSummary(report: MapLintReport | undefined): string {
+    if (!report) return ''
+    if (report.error) return `Lint: ${report.error}`
+    if (!report.warnings.length) return ''
+    return report.warnings.map((w) => `${w.severity}: ${w.message}`).join('\n')
   }
 
   function basename(path: string): string {
     return path.split(/[\\/]+/).filter(Boolean).pop() ?? path
   }
 
-  function scheduleAutoClipboardAdd(text: string): void {
-    const t = text.trim()
-    if (!t) return
+  function scheduleAutoClipboardAdd(captured: MapcodesCaptured): void {
+    if (!captured.mapcodes.length) return
 
-    clipboardPendingText = t
+    clipboardPendingText = captured
     if (clipboardDebounceTimer) {
       window.clearTimeout(clipboardDebounceTimer)
       clipboardDebounceTimer = null
@@ -2137,9 +2650,9 @@ export function initApp(root: HTMLElement): void {
       const next = clipboardPendingText
       clipboardPendingText = null
       if (!next) return
-      if (clipboardLastProcessed === next) return
-      clipboardLastProcessed = next
-      addMapcodes(parseMapcodesFromText(next), 'auto-clipboard')
+      if (clipboardLastProcessed === next.snippet) return
+      clipboardLastProcessed = next.snippet
+      addMapcodes(next.mapcodes, 'auto-clipboard')
     }, 250)
   }
 
@@ -2202,15 +2715,22 @@ export function initApp(root: HTMLElement): void {
         const decisionClass = item.decision ? `dec-${item.decision}` : ''
         const dotClass = item.status === 'reviewed' ? 'ok' : 'pending'
         const dotTitle = item.status === 'reviewed' ? 'reviewed' : 'pending'
+        const lint = lintReports.get(item.id)
         const titleParts = [
           `@${item.mapcode}`,
           item.decision ? `decision: ${DECISION_LABEL[item.decision]}` : 'decision: —',
           `status: ${dotTitle}`,
         ]
+        if (lint?.warnings.length) titleParts.push(`lint: ${lint.warnings.length}`)
+        // avisos já vêm do mais grave para o menos grave
+        const lintBadge = lint?.warnings.length
+          ? `<span class="lintBadge sev-${lint.warnings[0]!.severity}">${lint.warnings.length}</span>`
+          : 
         return `
           <button class="queueItem ${active} ${decisionClass}" data-id="${item.id}" title="${titleParts.join(' | ')}">
             <span class="idx">${idx + 1}</span>
-            <span class="mc">@${String(item.mapcode).replace(/^@+/, '')}</span>
+            ${queueThumbHtml(item)}
+            <span class="mc">@${String(item.mapcode).replace(/^@+/, '')}${lintBadge}</span>
             <span class="dot ${dotClass}" aria-label="${dotTitle}"></span>
           </button>
         `
@@ -2220,6 +2740,54 @@ export function initApp(root: HTMLElement): void {
     for (const btn of Array.from(els.queue.querySelectorAll<HTMLButtonElement>('button.queueItem'))) {
       btn.addEventListener('click', () => select(btn.dataset.id ?? null))
     }
+    void loadQueuePreviews()
+  }
+
+  function queueThumbHtml(item: QueueItem): string {
+    const mc = String(item.mapcode).replace(/^@+/, '')
+    const url = queuePreviews.get(mc)
+    if (url) return `<img class="thumb" data-thumb="${mc}" src="${url}" alt="" />`
+    return `<span class="thumb" data-thumb="${mc}"></span>`
+  }
+
+  async function loadQueuePreviews(): Promise<void> {
+    if (queuePreviewsLoading) return
+    queuePreviewsLoading = true
+    try {
+      for (;;) {
+        // só os visíveis com XML; a fila pode mudar entre um render e outro
+        const next = getVisibleQueueItems().find((it) => {
+          const mc = String(it.mapcode).replace(/^@+/, '')
+          return Boolean(it.xml) && !queuePreviews.has(mc)
+        })
+        if (!next) return
+        const mc = String(next.mapcode).replace(/^@+/, '')
+        let url = ''
+        try {
+          const res = await renderMapPreview({
+            mapId: Number.parseInt(mc, 10),
+            xml: next.xml,
+            width: 96,
+            inline: true,
+          })
+          url = res.dataUrl ?? ''
+        } catch {
+          // XML que não renderiza fica sem miniatura
+        }
+        queuePreviews.set(mc, url)
+        if (!url) continue
+        for (const el of Array.from(els.queue.querySelectorAll<HTMLElement>(`[data-thumb="${mc}"]`))) {
+          const img = document.createElement('img')
+          img.className = 'thumb'
+          img.dataset.thumb = mc
+          img.src = url
+          img.alt = ''
+          el.replaceWith(img)
+        }
+      }
+    } finally {
+      queuePreviewsLoading = false
+    }
   }
 
   function renderDetails(): void {
@@ -2240,7 +2808,8 @@ export function initApp(root: HTMLElement): void {
     detailsBoundId = sel.id
     els.details.classList.remove('muted')
 
-    const sessionCategory = (state.session?.category ?? 'P3') as ReviewedCategoryCode
+    // fila mista: as decisões seguem a categoria de origem do item
+    const sessionCategory = (sel.sourceCategory ?? state.session?.category ?? 'P3') as ReviewedCategoryCode
     const catMeta = REVIEW_CATEGORIES.find((c) => c.code === sessionCategory) ?? REVIEW_CATEGORIES[0]!
     const decisionOptionsHtml = [
       `<option value="">—</option>`,
@@ -2267,6 +2836,7 @@ export function initApp(root: HTMLElement): void {
           <button class="btn" id="copyXml">Copy XML</button>
         </div>
         <div class="status" id="d_xmlStatus"></div>
+        <div class="status lintStatus" id="d_lint"></div>
       </div>
 
       <div class="kv">
@@ -2361,12 +2931,14 @@ export function initApp(root: HTMLElement): void {
     const author = els.details.querySelector<HTMLSpanElement>('#d_author')
     const copyXml = els.details.querySelector<HTMLButtonElement>('#copyXml')
     const xmlStatus = els.details.querySelector<HTMLDivElement>('#d_xmlStatus')
+    const lint = els.details.querySelector<HTMLDivElement>('#d_lint')
 
     if (mapcode) mapcode.textContent = String(sel.mapcode).replace(/^@+/, '')
     if (submitter) submitter.textContent = sel.submitter ?? '—'
     if (author) author.textContent = sel.author ?? '—'
     if (copyXml) copyXml.disabled = !sel.xml
     if (xmlStatus) xmlStatus.textContent = sel.xml ? `XML ready (p=${sel.p ?? '—'})` : 'Fetching XML…'
+    if (lint) lint.textContent = lintSummary(lintReports.get(sel.id))
     if (decisionSelect && decisionSelect.value !== (sel.decision ?? '')) {
       decisionSelect.value = sel.decision ?? ''
     }
@@ -2400,6 +2972,7 @@ export function initApp(root: HTMLElement): void {
     els.showIgnored.checked = state.settings.showIgnoredInQueue
     els.queueCommandMode.value = state.settings.commandMode
     els.reviewHotkeys.checked = state.settings.reviewHotkeysEnabled
+    renderAutoplay()
     els.commandMode.value = state.settings.commandMode
     els.dedupe.checked = state.settings.dedupe
     els.autoCapture.checked = state.settings.autoCaptureClipboard
@@ -2472,6 +3045,41 @@ function normalizeHotkeyDisplay(value: string): string {
     })
   }
 
+  // líder + 1..4: as follow-ups só ficam registradas enquanto a sequência está armada
+  async function applyChordHotkeysConfig(leader = state.settings.decisionChordLeader): Promise<void> {
+    const trimmed = leader.trim()
+    await registerChordHotkeys(
+      trimmed
+        ? [{ leader: trimmed, followUps: ['1', '2', '3', '4'].map((key, i) => ({ key, action: `decision:${i + 1}` })) }]
+        : [],
+    )
+  }
+
+  // `decision:N` = N-ésima decisão permitida na categoria do mapa selecionado
+  function applyChordAction(action: string): void {
+    const n = /^decision:(\d+)$/.exec(action)?.[1]
+    if (!n) return
+    const sel = getSelected()
+    if (!sel) {
+      setStatus('No mapcode selected.')
+      return
+    }
+    const category = sel.sourceCategory ?? state.session?.category
+    const decision = REVIEW_CATEGORIES.find((c) => c.code === category)?.decisions[Number(n) - 1]
+    if (!decision) {
+      setStatus(`Chord: no decision ${n} in ${category ?? 'this category'}.`)
+      return
+    }
+    updateSelected(
+      (item) => {
+        item.decision = decision
+        item.status = 'reviewed'
+      },
+      { rerenderQueue: true },
+    )
+    setStatus(`Chord: ${sel.mapcode} → ${DECISION_LABEL[decision]}.`)
+  }
+
   async function applyMassPermHotkeysConfig(enabled: boolean): Promise<void> {
     const hk = state.settings.massPermHotkeys
     await setMassPermHotkeysConfig({
@@ -2485,9 +3093,33 @@ function normalizeHotkeyDisplay(value: string): string {
     })
   }
 
+  // as hotkeys já registradas foram resolvidas com o layout anterior: registra de novo
+  async function changeKeyboardLayout(layout: KeyboardLayout): Promise<void> {
+    const prev = state.settings.keyboardLayout
+    await setKeyboardLayout(layout)
+    try {
+      await applyReviewHotkeysConfig()
+      await applyChordHotkeysConfig()
+      if (els.massPerm.style.display === 'grid' || els.customCommand.style.display === 'grid') {
+        await applyMassPermHotkeysConfig(massPermHotkeysEnabled)
+      }
+    } catch (e) {
+      await setKeyboardLayout(prev)
+      await applyReviewHotkeysConfig().catch(() => {
+        // best effort
+      })
+      await applyChordHotkeysConfig().catch(() => {
+        // best effort
+      })
+      throw e
+    }
+    updateSettings({ keyboardLayout: layout })
+  }
+
   function getReviewHotkeysTitle(): string {
     const hk = state.settings.reviewHotkeys
-    return `Global hotkeys: ${hk.prevMap} = prev+load | ${hk.nextMap} = next+load | ${hk.replayCurrent} = replay`
+    const chord = state.settings.decisionChordLeader ? ` | ${state.settings.decisionChordLeader}, 1–4 = decision` : ''
+    return `Global hotkeys: ${hk.prevMap} = prev+load | ${hk.nextMap} = next+load | ${hk.replayCurrent} = replay${chord}`
   }
 
   function openHotkeysModal(kind: 'review' | 'mass_perm'): void {
@@ -2502,6 +3134,8 @@ function normalizeHotkeyDisplay(value: string): string {
           { id: 'prevMap', label: 'Prev + load', value: reviewCurrent.prevMap },
           { id: 'nextMap', label: 'Next + load', value: reviewCurrent.nextMap },
           { id: 'replayCurrent', label: 'Replay current', value: reviewCurrent.replayCurrent },
+          // opcional: vazio desliga a sequência
+          { id: 'decisionChord', label: 'Decision chord (then 1–4)', value: state.settings.decisionChordLeader },
         ]
       : [
           { id: 'toggle', label: 'Play / Pause / Resume', value: massCurrent.toggle },
@@ -2633,7 +3267,7 @@ function normalizeHotkeyDisplay(value: string): string {
         next[f.id] = normalizeHotkeyValue(stateMap.get(f.id) ?? '')
       }
 
-      const values = groupFields.map((f) => next[f.id])
+      const values = groupFields.filter((f) => f.id !== 'decisionChord').map((f) => next[f.id])
       const err = validateHotkeySet(values)
       if (err) {
         statusEl.textContent = formatHotkeyError(err)
@@ -2649,6 +3283,8 @@ function normalizeHotkeyDisplay(value: string): string {
           }
           await registerHotkeys(updated)
           updateSettings({ reviewHotkeys: updated })
+          await applyChordHotkeysConfig(next.decisionChord)
+          updateSettings({ decisionChordLeader: next.decisionChord })
           const reviewLabel = els.reviewHotkeys.closest('label')
           if (reviewLabel) reviewLabel.title = getReviewHotkeysTitle()
         } else {
@@ -2698,6 +3334,30 @@ function normalizeHotkeyDisplay(value: string): string {
     els.hotkeysModal.focus?.()
   }
 
+  function autoplaySecsForSession(): number | null {
+    const iv = autoplayIntervalsCache
+    if (!iv) return null
+    const cat = state.session?.category
+    return (cat ? iv.perCategory[cat] : undefined) ?? iv.defaultSecs
+  }
+
+  function renderAutoplay(): void {
+    const secs = autoplaySecsForSession()
+    if (secs != null && document.activeElement !== els.autoplaySecs) els.autoplaySecs.value = String(secs)
+    const status = autoplay?.status ?? 'off'
+    const left = Math.ceil((autoplay?.remainingMs ?? 0) / 1000)
+    els.autoplayToggle.textContent =
+      status === 'running' ? `Pause autoplay (${left}s)` : status === 'paused' ? `Resume autoplay (${left}s)` : 'Autoplay'
+    els.autoplayToggle.classList.toggle('primary', status === 'running')
+  }
+
+  function applyAutoplaySnapshot(snap: AutoplaySnapshot): void {
+    const prevMessage = autoplay?.message ?? null
+    autoplay = snap
+    if (snap.message && snap.message !== prevMessage) setStatus(snap.message)
+    renderAutoplay()
+  }
+
   async function playSelected(sourceLabel: string): Promise<void> {
     const sel = getSelected()
     if (!sel) {
@@ -2719,6 +3379,7 @@ function normalizeHotkeyDisplay(value: string): string {
     updateSettings({ commandMode: els.commandMode.value as AppState['settings']['commandMode'] }),
   )
   els.aboutBtn.addEventListener('click', () => openAboutModal())
+  els.openSettings.addEventListener('click', () => openSettingsModal())
   els.queueCommandMode.addEventListener('change', () =>
     updateSettings({ commandMode: els.queueCommandMode.value as AppState['settings']['commandMode'] }),
   )
@@ -2733,6 +3394,28 @@ function normalizeHotkeyDisplay(value: string): string {
   els.reviewHotkeysConfig.addEventListener('click', () => {
     openHotkeysModal('review')
   })
+  els.autoplayToggle.addEventListener('click', async () => {
+    const starting = (autoplay?.status ?? 'off') === 'off'
+    try {
+      // o Rust anda sozinho por esta fila
+      if (starting) await syncAutoplayQueue()
+      applyAutoplaySnapshot(await autoplayControl('toggle'))
+      // começa assistindo o mapa selecionado
+      if (starting) void playSelected('autoplay: start')
+    } catch (e) {
+      setStatus(`Autoplay: ${String(e)}`)
+    }
+  })
+  els.autoplaySecs.addEventListener('change', async () => {
+    const secs = Number.parseInt(els.autoplaySecs.value, 10)
+    if (!Number.isFinite(secs)) return
+    try {
+      autoplayIntervalsCache = await autoplaySetInterval(state.session?.category ?? null, secs)
+      renderAutoplay()
+    } catch (e) {
+      setStatus(`Failed to set autoplay time: ${String(e)}`)
+    }
+  })
   els.showIgnored.addEventListener('change', () => {
     updateSettings({ showIgnoredInQueue: els.showIgnored.checked })
     renderQueue()
@@ -2825,6 +3508,8 @@ function normalizeHotkeyDisplay(value: string): string {
                   status: (it.status as any) ?? 'pending',
                   createdAt: typeof it.createdAt === 'string' ? it.createdAt : nowIso(),
                   updatedAt: typeof it.updatedAt === 'string' ? it.updatedAt : nowIso(),
+                  sourceCategory: typeof it.sourceCategory === 'string' ? it.sourceCategory : null,
+                  sourceThreadId: typeof it.sourceThreadId === 'string' ? it.sourceThreadId : null,
                 } satisfies Pick<QueueItem, 'mapcode'> & Partial<QueueItem>
               })
 
@@ -2880,6 +3565,7 @@ function normalizeHotkeyDisplay(value: string): string {
             persist()
 
             addItems(items, `session json: ${basename(path)}`)
+            void applySubmissionLimits().catch((e) => setStatus(`Submission limit check failed: ${String(e)}`))
             return
           }
         } catch {
@@ -2892,54 +3578,107 @@ function normalizeHotkeyDisplay(value: string): string {
     }
   })
 
+  // Depois de carregar a sessão: mapas além do limite por usuário viram ignored (com o motivo no
+  // review). Fila mista conta por categoria de origem; as extras usam o limite da categoria.
+  async function applySubmissionLimits(): Promise<void> {
+    const session = state.session
+    if (!session) return
+    const groups = new Map<string, QueueItem[]>()
+    for (const it of state.items) {
+      const key = it.sourceCategory ?? session.category
+      groups.set(key, [...(groups.get(key) ?? []), it])
+    }
+
+    let marked = 0
+    for (const [category, items] of groups) {
+      const report = await checkSubmissionLimits(
+        items.map((it) => ({
+          id: it.id,
+          mapcode: it.mapcode,
+          submitter: it.submitter ?? null,
+          importedIgnored: it.importedIgnored ?? null,
+        })),
+        category === session.category ? session.limitPerUser : null,
+        category,
+      )
+      for (const over of report.overflow) {
+        const it = items.find((x) => x.id === over.id)
+        // não mexe no que o revisor já decidiu
+        if (!it || it.decision) continue
+        it.decision = 'ignored'
+        it.status = 'reviewed'
+        if (!it.review.trim()) it.review = over.reason
+        it.updatedAt = nowIso()
+        marked += 1
+      }
+    }
+    if (!marked) return
+
+    persist()
+    render()
+    syncNp()
+    setStatus(`Marked ${marked} map(s) over the submission limit as ignored.`)
+  }
+
+  // "P5, 9" -> ['P5', 'P9']
+  function parseCategoryList(raw: string): string[] {
+    return raw
+      .split(/[\s,;]+/)
+      .map((c) => c.trim().toUpperCase())
+      .filter(Boolean)
+      .map((c) => (c.startsWith('P') ? c : `P${c}`))
+  }
+
+  function sessionApiErrorText(categoryType: string, err: string): string {
+    if (err === 'no_active_session') return `No active session for ${categoryType}.`
+    if (err === 'unauthorized') return 'Session API: unauthorized (check SESSION_API_TOKEN).'
+    if (err === 'missing_category') return 'Session API: missing category.'
+    return `Session API error (${categoryType}): ${err}`
+  }
+
   els.importFromApi.addEventListener('click', async () => {
     const categoryType = ((state.session?.category as ReviewedCategoryCode) || (els.apiCategory.value as ReviewedCategoryCode) || 'P3') as ReviewedCategoryCode
-    setStatus(`Fetching session (${categoryType})...`)
+    // categorias extras: cada item guarda a categoria/thread de onde veio
+    const extra = parseCategoryList(els.apiExtraCategories.value).filter((c) => c !== categoryType)
+    const mixed = extra.length > 0
+    const label = [categoryType, ...extra].join(', ')
+    setStatus(`Fetching session (${label})...`)
     try {
-      const res = await fetchSessionFromApi(categoryType)
-      if (!res?.ok) {
-        const err = res?.error?.error || 'unknown_error'
-        if (err === 'no_active_session') {
-          setStatus(`No active session for ${categoryType}.`)
-        } else if (err === 'unauthorized') {
-          setStatus('Session API: unauthorized (check SESSION_API_TOKEN).')
-        } else if (err === 'missing_category') {
-          setStatus('Session API: missing category.')
-        } else {
-          setStatus(`Session API error (${categoryType}): ${err}`)
+      const { results } = await fetchSessionsFromApi([categoryType, ...extra])
+      const errors: string[] = []
+      const items: Array<Pick<QueueItem, 'mapcode'> & Partial<QueueItem>> = []
+
+      for (const [code, res] of Object.entries(results)) {
+        const data = res?.ok ? res.data : undefined
+        if (!data) {
+          errors.push(sessionApiErrorText(code, res?.error?.error || 'unknown_error'))
+          continue
         }
-        return
-      }
 
-      const data = res.data
-      if (!data) {
-        setStatus(`Session API returned no data (${categoryType}).`)
-        return
-      }
-
-      // grava metadados da sessão importada
-      state.session = {
-        category: String(data.category || categoryType),
-        inputMethod: 'session_api',
-        startedAt: state.session?.startedAt ?? nowIso(),
-        threadId: String(data.threadId),
-        collectedAt: data.collectedAt,
-        limitPerUser: data.limitPerUser,
-      }
-      persist()
+        // grava metadados da sessão importada (categoria principal)
+        if (code === categoryType) {
+          state.session = {
+            category: String(data.category || categoryType),
+            inputMethod: 'session_api',
+            startedAt: state.session?.startedAt ?? nowIso(),
+            threadId: String(data.threadId),
+            collectedAt: data.collectedAt,
+            limitPerUser: data.limitPerUser,
+          }
+          persist()
+        }
 
-      if (!Array.isArray(data.maps) || data.maps.length === 0) {
-        setStatus(`No maps in session (${data.category}). threadId=${data.threadId}`)
-        return
-      }
+        if (!Array.isArray(data.maps) || data.maps.length === 0) {
+          errors.push(`No maps in session (${data.category}). threadId=${data.threadId}`)
+          continue
+        }
 
-      const items = data.maps
-        .filter((m) => typeof m?.mapCode === 'string')
-        .map((m) => {
+        for (const m of data.maps) {
+          if (typeof m?.mapCode !== 'string') continue
           const ignored = Boolean(m.ignored)
           const reason = (m.reason ?? null) as any
           const reasonText = typeof reason === 'string' ? reason.trim() : ''
-          return {
+          items.push({
             mapcode: m.mapCode,
             submitter: m.submitter ?? null,
             importedIgnored: ignored,
@@ -2947,12 +3686,18 @@ function normalizeHotkeyDisplay(value: string): string {
             review: ignored ? (reasonText ? reasonText : '') : '',
             decision: ignored ? ('ignored' as const) : null,
             status: ignored ? ('reviewed' as const) : ('pending' as const),
-          } satisfies Pick<QueueItem, 'mapcode'> & Partial<QueueItem>
-        })
+            ...(mixed ? { sourceCategory: code, sourceThreadId: String(data.threadId) } : {}),
+          })
+        }
+      }
 
-      addItems(items, `api: ${data.category}`)
+      if (items.length) addItems(items, `api: ${label}`)
+      if (errors.length) setStatus(items.length ? `Added ${items.length} map(s). ${errors.join(' ')}` : errors.join(' '))
+      if (items.length) {
+        void applySubmissionLimits().catch((e) => setStatus(`Submission limit check failed: ${String(e)}`))
+      }
     } catch (e) {
-      setStatus(`Failed to fetch session (${categoryType}): ${String(e)}`)
+      setStatus(`Failed to fetch session (${label}): ${String(e)}`)
     }
   })
 
@@ -3336,8 +4081,8 @@ function normalizeHotkeyDisplay(value: string): string {
     if (enabled) {
       try {
         if (!unlistenClipboard) {
-          unlistenClipboard = await onClipboardChanged((text) => {
-            scheduleAutoClipboardAdd(text)
+          unlistenClipboard = await onMapcodesCaptured((captured) => {
+            scheduleAutoClipboardAdd(captured)
           })
         }
         await startClipboardWatch()
@@ -3370,11 +4115,25 @@ function normalizeHotkeyDisplay(value: string): string {
   setShellVisible(false)
   renderAuth()
   void hydrateAppVersion()
-
-  // hotkeys globais (best effort)
-  void applyReviewHotkeysConfig().catch(() => {
+  // a fila salva já tem XML; os avisos do lint não são salvos
+  void lintQueueItems(state.items).catch(() => {
     // best effort
   })
+
+  // hotkeys globais (best effort); o layout vem antes porque muda a tecla resolvida
+  void setKeyboardLayout(state.settings.keyboardLayout)
+    .catch(() => {
+      // fica no padrão (US)
+    })
+    .then(async () => {
+      void applyReviewHotkeysConfig().catch(() => {
+        // best effort
+      })
+      await applyChordHotkeysConfig()
+    })
+    .catch((e) => {
+      setStatus(formatHotkeyError(`decision chord: ${String(e)}`))
+    })
   // aplica estado salvo das hotkeys de review (best effort)
   void setReviewHotkeysEnabled(state.settings.reviewHotkeysEnabled).catch(() => {
     // best effort
@@ -3384,12 +4143,69 @@ function normalizeHotkeyDisplay(value: string): string {
   void onHotkeyReplayCurrent(() => void playSelected('hotkey: replay current')).catch(() => {
     // best effort
   })
+  void onHotkeyChordArmed((leader) => setStatus(`Chord ${leader}: press 1–4 to set the decision…`)).catch(() => {
+    // best effort
+  })
+  void onHotkeyChordTimeout((leader) => setStatus(`Chord ${leader}: timed out.`)).catch(() => {
+    // best effort
+  })
+  void onHotkeyChord((action) => applyChordAction(action)).catch(() => {
+    // best effort
+  })
   void onHotkeyNavPlay((delta) => {
     selectRelative(delta)
     void playSelected(delta > 0 ? 'hotkey: next map' : 'hotkey: previous map')
   }).catch(() => {
     // best effort
   })
+  // autoplay (best effort)
+  void onAutoplayStatus((snap) => applyAutoplaySnapshot(snap)).catch(() => {
+    // best effort
+  })
+  void onAutoplayNext((next) => {
+    const item = getVisibleQueueItems()[next.index]
+    // a fila pode ter mudado durante o envio; o próximo syncNp corrige a posição no Rust
+    if (!item || item.mapcode !== next.mapcode) return
+    select(item.id)
+    setStatus(`Autoplay: playing ${next.mapcode}.`)
+  }).catch(() => {
+    // best effort
+  })
+  // poller da Session API achou lote novo
+  void onSessionAvailable((ev) => {
+    setStatus(`New ${ev.category} session available (${ev.maps} map(s)).`)
+  }).catch(() => {
+    // best effort
+  })
+  // resumo postado (ou não) no Discord depois do submit
+  void onDiscordWebhookStatus((st) => {
+    setStatus(`Discord: ${describeDiscordPost(st)}`)
+  }).catch(() => {
+    // best effort
+  })
+  // API de controle local (best effort)
+  void onControlDecision(({ mapcode, decision }) => {
+    // a seleção pode ter mudado entre o pedido e o evento
+    if (getSelected()?.mapcode !== mapcode) return
+    updateSelected(
+      (item) => {
+        item.decision = decision
+        item.status = 'reviewed'
+      },
+      { rerenderQueue: true },
+    )
+    setStatus(`Control API: @${mapcode.replace(/^@+/, '')} → ${DECISION_LABEL[decision]}.`)
+  }).catch(() => {
+    // best effort
+  })
+  void Promise.all([autoplayIntervals(), autoplayState()])
+    .then(([iv, snap]) => {
+      autoplayIntervalsCache = iv
+      applyAutoplaySnapshot(snap)
+    })
+    .catch(() => {
+      // best effort
+    })
   void onHotkeysStatus((enabled) => {
     setStatus(`Global hotkeys: ${enabled ? 'enabled' : 'disabled'}.`)
   }).catch(() => {
@@ -3397,6 +4213,9 @@ function normalizeHotkeyDisplay(value: string): string {
   })
 
   // hotkeys globais do mass perm (best effort)
+  void onMassPermStatus((snap) => applyMassPermSnapshot(snap)).catch(() => {
+    // best effort
+  })
   void onHotkeyMassPermToggle(() => {
     if (!massPermHotkeysEnabled) return
     if (els.customCommand.style.display === 'grid') {
@@ -3465,8 +4284,8 @@ function normalizeHotkeyDisplay(value: string): string {
     Promise.resolve()
       .then(async () => {
         els.autoCapture.checked = true
-        unlistenClipboard = await onClipboardChanged((text) => {
-          scheduleAutoClipboardAdd(text)
+        unlistenClipboard = await onMapcodesCaptured((captured) => {
+          scheduleAutoClipboardAdd(captured)
         })
         await startClipboardWatch()
       })
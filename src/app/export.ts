@@ -32,6 +32,8 @@ export interface ExportQueueItemV1 {
   status: QueueItem['status']
   createdAt: string
   updatedAt: string
+  sourceCategory?: string | null
+  sourceThreadId?: string | null
 }
 
 export interface ExportPayloadV1 {
@@ -86,6 +88,10 @@ export function buildExportPayloadV1(
       }
 
       if (includeXml) base.xml = it.xml ?? null
+      if (it.sourceCategory) {
+        base.sourceCategory = it.sourceCategory
+        base.sourceThreadId = it.sourceThreadId ?? null
+      }
       return base
     }),
   }
@@ -1,5 +1,7 @@
 export type CommandMode = '!np' | '/np' | '/npp'
 
+export type KeyboardLayout = 'us' | 'abnt2' | 'azerty'
+
 export type Decision = 'left_as_is' | 'p1ed' | 'will_be_discussed' | 'ignored'
 
 export type InputMethod = 'session_api' | 'session_json' | 'file_text' | 'clipboard' | 'textarea'
@@ -21,6 +23,9 @@ export interface QueueItem {
   status: ItemStatus
   createdAt: string
   updatedAt: string
+  // fila mista (várias categorias): sessão de onde o item veio
+  sourceCategory?: string | null
+  sourceThreadId?: string | null
 }
 
 export interface SessionState {
@@ -44,6 +49,10 @@ export interface Settings {
     nextMap: string
     replayCurrent: string
   }
+  // líder da sequência de decisão (líder, depois 1..4 = decisão n da categoria); '' desliga
+  decisionChordLeader: string
+  // layout físico do teclado (resolve `<`, `?` etc. das hotkeys para a tecla certa)
+  keyboardLayout: KeyboardLayout
   massPermHotkeys: {
     toggle: string
     playCurrent: string
@@ -73,6 +82,8 @@ export const DEFAULT_SETTINGS: Settings = {
     nextMap: 'PageDown',
     replayCurrent: 'Insert',
   },
+  decisionChordLeader: 'Ctrl+Alt+R',
+  keyboardLayout: 'us',
   massPermHotkeys: {
     toggle: 'Ctrl+P',
     playCurrent: 'Insert',
@@ -4,9 +4,9 @@ import { listen, type UnlistenFn } from '@tauri-apps/api/event'
 import { open, save } from '@tauri-apps/plugin-dialog'
 import { check } from '@tauri-apps/plugin-updater'
 import { relaunch } from '@tauri-apps/plugin-process'
-import type { CommandMode } from './model'
+import type { CommandMode, Decision, KeyboardLayout } from './model'
 import type { ExportPayloadV1 } from './export'
-import type { ReviewedCategoryCode } from './categories'
+import type { CategoryMeta, ReviewedCategoryCode } from './categories'
 
 export async function readClipboardText(): Promise<string | null> {
   const res = await invoke<string | null>('read_clipboard_text')
@@ -76,6 +76,27 @@ export async function readTextFileFromPath(path: string): Promise<string> {
   return await invoke<string>('read_text_file', { path })
 }
 
+export type ImportFormat = 'discordJson' | 'discordHtml' | 'chatLog'
+
+export interface ImportedRow {
+  mapcode: string
+  submitter: string | null
+  category: string | null
+  timestamp: string | null
+  message: string
+}
+
+export interface ImportResult {
+  format: ImportFormat
+  rows: ImportedRow[]
+  messages: number
+}
+
+// Sem `format`, o Rust detecta pelo conteúdo do arquivo.
+export async function importSubmissions(path: string, format?: ImportFormat): Promise<ImportResult> {
+  return await invoke<ImportResult>('import_submissions', { path, format: format ?? null })
+}
+
 export async function startClipboardWatch(): Promise<void> {
   await invoke('start_clipboard_watch')
 }
@@ -84,8 +105,14 @@ export async function stopClipboardWatch(): Promise<void> {
   await invoke('stop_clipboard_watch')
 }
 
-export async function onClipboardChanged(cb: (text: string) => void): Promise<UnlistenFn> {
-  return await listen<string>('clipboard_changed', (event) => cb(event.payload))
+export interface MapcodesCaptured {
+  mapcodes: string[]
+  snippet: string
+  source: 'xfixes' | 'wayland' | 'poll'
+}
+
+export async function onMapcodesCaptured(cb: (captured: MapcodesCaptured) => void): Promise<UnlistenFn> {
+  return await listen<MapcodesCaptured>('mapcodes_captured', (event) => cb(event.payload))
 }
 
 export async function onHotkeyReplayCurrent(cb: () => void): Promise<UnlistenFn> {
@@ -104,14 +131,215 @@ export async function onMassPermHotkeysStatus(cb: (enabled: boolean) => void): P
   return await listen<{ enabled: boolean }>('massperm_hotkeys_status', (event) => cb(Boolean(event.payload.enabled)))
 }
 
-export async function setNpContext(args: { mapcode: string | null; commandMode: CommandMode }): Promise<void> {
-  await invoke('set_np_context', { ctx: { mapcode: args.mapcode, commandMode: args.commandMode } })
+export interface ParsedMapcodes {
+  mapcodes: string[]
+  rejected: string[]
+}
+
+// strict=true separa o que não é código real do jogo (@123456) em `rejected`.
+export async function parseMapcodes(text: string, strict = false): Promise<ParsedMapcodes> {
+  return await invoke<ParsedMapcodes>('parse_mapcodes', { text, strict })
+}
+
+export interface MapXmlSummary {
+  width: number
+  height: number
+  wind: number
+  gravity: number
+  background: number | null
+  grounds: number
+  // por nome do tipo ("wood", "lava", ...)
+  groundsByType: Record<string, number>
+  dynamicGrounds: number
+  shamanObjects: number
+  decorations: number
+  joints: number
+  mouseSpawns: number
+  shamanSpawns: number
+  holes: number
+  cheeses: number
+  flags: Array<'night' | 'collision' | 'soulmate' | 'defilante'>
+  warnings: string[]
+}
+
+export interface ParsedMapXml {
+  summary: MapXmlSummary
+  // modelo completo (grounds, points, decorations, shamanObjects, joints), só com full=true
+  map?: Record<string, unknown>
+}
+
+export async function parseMapXml(xml: string, full = false): Promise<ParsedMapXml> {
+  return await invoke<ParsedMapXml>('parse_map_xml', { xml, full })
+}
+
+export interface MapFieldChange {
+  // ex.: "position", "size", "type", "width", "night"; "attribute" para atributos de <P> não modelados
+  field: string
+  before: string | null
+  after: string | null
+}
+
+export interface MapElementChange {
+  element: 'ground' | 'mouseSpawn' | 'shamanSpawn' | 'hole' | 'cheese' | 'decoration' | 'shamanObject' | 'joint'
+  change: 'added' | 'removed' | 'changed'
+  // ex.: "lava ground", "decoration 12"
+  label: string
+  beforeIndex: number | null
+  afterIndex: number | null
+  x: number
+  y: number
+  fields: MapFieldChange[]
+}
+
+export interface MapXmlDiff {
+  properties: MapFieldChange[]
+  elements: MapElementChange[]
+  // primeira linha = contagem; depois uma frase por mudança ("no changes" se igual)
+  summary: string[]
+}
+
+// Sem `before`, compara com a versão anterior do mapa no cache de mapInfo (precisa de mapId).
+export async function diffMapXml(args: { after: string; before?: string | null; mapId?: number | null }): Promise<MapXmlDiff> {
+  return await invoke<MapXmlDiff>('diff_map_xml', {
+    args: { after: args.after, before: args.before ?? null, mapId: args.mapId ?? null },
+  })
+}
+
+export type LintSeverity = 'info' | 'warning' | 'error'
+
+export interface LintWarning {
+  // ex.: "missing-hole", "shaman-objects", "missing-defilante"
+  rule: string
+  severity: LintSeverity
+  message: string
+}
+
+export interface MapLintReport {
+  mapId: number
+  category: string | null
+  // do mais grave para o menos grave
+  warnings: LintWarning[]
+  // mapa que não deu pra checar (sem XML, XML inválido, categoria inválida)
+  error?: string
+}
+
+// Sem xml usa o cache de mapInfo; sem category usa o `p` atual do mapa.
+export async function lintMaps(
+  items: Array<{ mapId: number; category?: string | null; xml?: string | null }>,
+): Promise<MapLintReport[]> {
+  return await invoke<MapLintReport[]>('lint_maps', { items })
+}
+
+export interface FingerprintMatch {
+  mapId: number
+  // mesmo conteúdo (só mudou a ordem/formatação do XML)
+  exact: boolean
+  // 0..1 (Jaccard dos elementos com posição arredondada)
+  similarity: number
+  category: string | null
+  decision: string | null
+  author: string | null
+  // unix (segundos)
+  reviewedAt: number
+}
+
+export interface ResubmissionReport {
+  mapId: number
+  // exatos primeiro, depois por similaridade
+  matches: FingerprintMatch[]
+  error?: string
+}
+
+// Compara com os mapas já revisados (gravados a cada submit). Sem xml usa o cache de mapInfo.
+export async function findResubmissions(
+  items: Array<{ mapId: number; xml?: string | null }>,
+  threshold?: number,
+): Promise<ResubmissionReport[]> {
+  return await invoke<ResubmissionReport[]>('find_resubmissions', { items, threshold: threshold ?? null })
+}
+
+// Para reviews que não passaram pelo submit (ex.: sessão importada).
+export async function recordReviewedMaps(
+  items: Array<{
+    mapId: number
+    xml?: string | null
+    category?: string | null
+    decision?: string | null
+    author?: string | null
+  }>,
+): Promise<number> {
+  return await invoke<number>('record_reviewed_maps', { items })
+}
+
+export interface MapPreview {
+  // PNG no cache do app (um arquivo por mapa/tamanho/XML)
+  path: string
+  cached: boolean
+  // só com inline=true
+  dataUrl?: string
+}
+
+export async function renderMapPreview(args: {
+  mapId: number
+  // sem xml, usa o cache de mapInfo
+  xml?: string | null
+  width?: number
+  height?: number
+  showInvisible?: boolean
+  inline?: boolean
+}): Promise<MapPreview> {
+  return await invoke<MapPreview>('render_map_preview', { args })
+}
+
+export async function setNpContext(args: {
+  mapcode: string | null
+  commandMode: CommandMode
+  category?: string | null
+  // posição na fila visível (0-based) e autor, expostos pela API de controle/overlay
+  index?: number | null
+  total?: number
+  author?: string | null
+}): Promise<void> {
+  await invoke('set_np_context', {
+    ctx: {
+      mapcode: args.mapcode,
+      commandMode: args.commandMode,
+      category: args.category ?? null,
+      index: args.index ?? null,
+      total: args.total ?? 0,
+      author: args.author ?? null,
+    },
+  })
 }
 
 export async function sendNpToActiveWindow(args: { mapcode: string; commandMode: CommandMode }): Promise<string> {
   return await invoke<string>('send_np_to_active_window', { args: { mapcode: args.mapcode, commandMode: args.commandMode } })
 }
 
+// Janelas em que o app pode digitar (trecho do título, ou do WM_CLASS com `class:`; só checado no X11).
+// Fora da lista os envios falham com "wrong_target_window: ...".
+export interface TargetWindowConfig {
+  allowList: string[]
+  defaults: string[]
+  // recusa ("unchecked_target_window: ...") quando não dá para ler a janela em foco
+  strict: boolean
+  // false = a janela em foco não pode ser lida aqui (Wayland, Windows, macOS)
+  checkable: boolean
+}
+
+export async function getTargetWindowConfig(): Promise<TargetWindowConfig> {
+  return await invoke<TargetWindowConfig>('get_target_window_config')
+}
+
+// null volta para a lista padrão; [] desliga a checagem
+export async function setTargetWindowAllowList(patterns: string[] | null): Promise<TargetWindowConfig> {
+  return await invoke<TargetWindowConfig>('set_target_window_allow_list', { patterns })
+}
+
+export async function setTargetWindowStrict(strict: boolean): Promise<TargetWindowConfig> {
+  return await invoke<TargetWindowConfig>('set_target_window_strict', { strict })
+}
+
 export async function sendPermToActiveWindow(args: { mapcode: string; categoryNumber: number }): Promise<string> {
   return await invoke<string>('send_perm_to_active_window', { args: { mapcode: args.mapcode, categoryNumber: args.categoryNumber } })
 }
@@ -122,6 +350,140 @@ export async function sendCustomToActiveWindow(args: { mapcode: string; prefix:
   })
 }
 
+export type MassPermRunStatus = 'idle' | 'running' | 'paused' | 'done'
+
+export interface MassPermSnapshot {
+  status: MassPermRunStatus
+  mapcodes: string[]
+  category: number
+  // próximo mapa a enviar
+  index: number
+  total: number
+  lastSent: number | null
+  current: string | null
+  intervalMs: number
+  // manda /np antes do /p
+  playFirst: boolean
+  lastCommand: string | null
+  message: string | null
+}
+
+export type MassPermAction = 'toggle' | 'pause' | 'playCurrent' | 'next' | 'prev'
+
+export interface MassPermConfig {
+  categoryNumber?: number | null
+  intervalMs?: number | null
+  playFirst?: boolean | null
+}
+
+// O runner roda no Rust (não para com a janela em segundo plano); as hotkeys de mass perm
+// vão direto para ele enquanto houver lista carregada.
+export async function massPermLoad(mapcodes: string[], config: MassPermConfig = {}): Promise<MassPermSnapshot> {
+  return await invoke<MassPermSnapshot>('massperm_load', { mapcodes, config })
+}
+
+export async function massPermConfigure(config: MassPermConfig): Promise<MassPermSnapshot> {
+  return await invoke<MassPermSnapshot>('massperm_configure', { config })
+}
+
+export async function massPermAction(action: MassPermAction): Promise<MassPermSnapshot> {
+  return await invoke<MassPermSnapshot>('massperm_action', { action })
+}
+
+export async function massPermSeek(index: number): Promise<MassPermSnapshot> {
+  return await invoke<MassPermSnapshot>('massperm_seek', { index })
+}
+
+export async function massPermClear(): Promise<MassPermSnapshot> {
+  return await invoke<MassPermSnapshot>('massperm_clear')
+}
+
+// Progresso salvo (retomado pausado depois de reabrir o app).
+export async function massPermState(): Promise<MassPermSnapshot> {
+  return await invoke<MassPermSnapshot>('massperm_state')
+}
+
+export async function onMassPermStatus(cb: (snap: MassPermSnapshot) => void): Promise<UnlistenFn> {
+  return await listen<MassPermSnapshot>('massperm_status', (event) => cb(event.payload))
+}
+
+export type AutoplayStatus = 'off' | 'running' | 'paused'
+
+export interface AutoplaySnapshot {
+  status: AutoplayStatus
+  category: string | null
+  mapcode: string | null
+  intervalSecs: number
+  remainingMs: number
+  message: string | null
+}
+
+export type AutoplayAction = 'start' | 'pause' | 'resume' | 'toggle' | 'stop'
+
+export interface AutoplayIntervals {
+  defaultSecs: number
+  perCategory: Record<string, number>
+}
+
+// Conta a partir do mapa do `setNpContext`; o tempo vem da categoria do item.
+export async function autoplayControl(action: AutoplayAction): Promise<AutoplaySnapshot> {
+  return await invoke<AutoplaySnapshot>('autoplay_control', { action })
+}
+
+export async function autoplayState(): Promise<AutoplaySnapshot> {
+  return await invoke<AutoplaySnapshot>('autoplay_state')
+}
+
+export async function autoplayIntervals(): Promise<AutoplayIntervals> {
+  return await invoke<AutoplayIntervals>('autoplay_intervals')
+}
+
+// category null = padrão; secs null = volta a categoria para o padrão
+export async function autoplaySetInterval(category: string | null, secs: number | null): Promise<AutoplayIntervals> {
+  return await invoke<AutoplayIntervals>('autoplay_set_interval', { category, secs })
+}
+
+export async function onAutoplayStatus(cb: (snap: AutoplaySnapshot) => void): Promise<UnlistenFn> {
+  return await listen<AutoplaySnapshot>('autoplay_status', (event) => cb(event.payload))
+}
+
+// Fila visível (na ordem do `index` do `setNpContext`); o Rust toca o próximo sozinho.
+export async function autoplaySetQueue(queue: Array<{ mapcode: string; category: string | null }>): Promise<void> {
+  await invoke('autoplay_set_queue', { queue })
+}
+
+// O Rust já mandou o `/np` deste item; a webview só move a seleção.
+export async function onAutoplayNext(cb: (next: { index: number; mapcode: string }) => void): Promise<UnlistenFn> {
+  return await listen<{ index: number; mapcode: string }>('autoplay_next', (event) => cb(event.payload))
+}
+
+// API de controle em 127.0.0.1 (desligada por padrão); clientes mandam o token.
+export interface ControlApiInfo {
+  enabled: boolean
+  port: number
+  token: string
+  running: boolean
+}
+
+export async function getControlApi(): Promise<ControlApiInfo> {
+  return await invoke<ControlApiInfo>('get_control_api')
+}
+
+export async function setControlApi(enabled: boolean, port?: number): Promise<ControlApiInfo> {
+  return await invoke<ControlApiInfo>('set_control_api', { enabled, port: port ?? null })
+}
+
+export async function regenerateControlApiToken(): Promise<ControlApiInfo> {
+  return await invoke<ControlApiInfo>('regenerate_control_api_token')
+}
+
+// Decisão vinda da API de controle para o mapa `mapcode`.
+export async function onControlDecision(
+  cb: (ev: { mapcode: string; decision: Decision }) => void,
+): Promise<UnlistenFn> {
+  return await listen<{ mapcode: string; decision: Decision }>('control_decision', (event) => cb(event.payload))
+}
+
 export async function setMassPermHotkeysConfig(args: {
   enabled: boolean
   hotkeys: { toggle: string; playCurrent: string; next: string; prev: string }
@@ -146,10 +508,41 @@ export async function registerHotkeys(args?: {
   await invoke('register_hotkeys', { args: payload })
 }
 
+// Afeta os próximos registerHotkeys/setMassPermHotkeysConfig/registerChordHotkeys.
+export async function setKeyboardLayout(layout: KeyboardLayout): Promise<KeyboardLayout> {
+  return await invoke<KeyboardLayout>('set_keyboard_layout', { layout })
+}
+
 export async function setReviewHotkeysEnabled(enabled: boolean): Promise<void> {
   await invoke('set_review_hotkeys_enabled_cmd', { enabled })
 }
 
+export interface ChordHotkeyConfig {
+  leader: string
+  followUps: Array<{ key: string; action: string }>
+  timeoutMs?: number
+}
+
+export async function registerChordHotkeys(chords: ChordHotkeyConfig[]): Promise<void> {
+  await invoke('register_chord_hotkeys', { args: { chords } })
+}
+
+export async function onHotkeyChordArmed(cb: (leader: string, timeoutMs: number) => void): Promise<UnlistenFn> {
+  return await listen<{ leader: string; timeoutMs: number }>('hotkey_chord_armed', (event) =>
+    cb(event.payload.leader, event.payload.timeoutMs),
+  )
+}
+
+export async function onHotkeyChord(cb: (action: string, leader: string, key: string) => void): Promise<UnlistenFn> {
+  return await listen<{ leader: string; key: string; action: string }>('hotkey_chord', (event) =>
+    cb(event.payload.action, event.payload.leader, event.payload.key),
+  )
+}
+
+export async function onHotkeyChordTimeout(cb: (leader: string) => void): Promise<UnlistenFn> {
+  return await listen<{ leader: string }>('hotkey_chord_timeout', (event) => cb(event.payload.leader))
+}
+
 export async function onHotkeyMassPermToggle(cb: () => void): Promise<UnlistenFn> {
   return await listen('hotkey_massperm_toggle', () => cb())
 }
@@ -198,11 +591,68 @@ export async function fetchSessionFromApi(categoryType: ReviewedCategoryCode): P
   return await invoke<SessionApiEnvelope>('fetch_session_api', { categoryType })
 }
 
+export interface SessionApiMultiEnvelope {
+  // chave = código normalizado ("P4")
+  results: Record<string, SessionApiEnvelope>
+}
+
+// Várias categorias em paralelo (fila mista); cada uma com o próprio ok/erro.
+export async function fetchSessionsFromApi(categoryTypes: string[]): Promise<SessionApiMultiEnvelope> {
+  return await invoke<SessionApiMultiEnvelope>('fetch_sessions_api', { categoryTypes })
+}
+
+// Poller da Session API: avisa (notificação + `session_available`) quando uma categoria vigiada
+// ganha um lote novo (threadId/collectedAt diferente do último visto).
+export interface SessionWatch {
+  categories: string[]
+  intervalSecs: number
+  lastSeen: Record<string, { threadId: string; collectedAt: string }>
+}
+
+export interface SessionAvailable {
+  category: string
+  threadId: string
+  collectedAt: string
+  maps: number
+}
+
+export async function getSessionWatch(): Promise<SessionWatch> {
+  return await invoke<SessionWatch>('get_session_watch')
+}
+
+// [] desliga; intervalSecs omitido mantém o atual (60..3600)
+export async function setSessionWatch(categories: string[], intervalSecs?: number): Promise<SessionWatch> {
+  return await invoke<SessionWatch>('set_session_watch', { categories, intervalSecs: intervalSecs ?? null })
+}
+
+export async function onSessionAvailable(cb: (ev: SessionAvailable) => void): Promise<UnlistenFn> {
+  return await listen<SessionAvailable>('session_available', (event) => cb(event.payload))
+}
+
+export interface ReviewItemError {
+  id: string
+  mapcode: string
+  error: 'missing_decision' | 'unknown_decision' | 'decision_not_allowed' | 'missing_review'
+  message: string
+}
+
 export interface SubmitReviewEnvelope {
   ok: boolean
   status: number
   body?: string | null
   error?: string | null
+  // só com error = "invalid_items" (validação local antes de enviar)
+  itemErrors?: ReviewItemError[]
+}
+
+// Mesma validação do submit, para avisar antes de fechar a sessão.
+export async function validateReviewPayload(categoryType: string, payload: ExportPayloadV1): Promise<ReviewItemError[]> {
+  return await invoke<ReviewItemError[]>('validate_review_payload', { categoryType, payload })
+}
+
+// Registro de categorias do lado Rust (o mesmo categories.json embutido no build).
+export async function listCategories(): Promise<CategoryMeta[]> {
+  return await invoke<CategoryMeta[]>('list_categories')
 }
 
 export async function submitSessionReview(
@@ -211,6 +661,8 @@ export async function submitSessionReview(
   token?: string | null,
   votecrew?: boolean,
   postAsPrivate?: boolean,
+  // nome no resumo do webhook do Discord
+  reviewer?: string | null,
 ): Promise<SubmitReviewEnvelope> {
   return await invoke<SubmitReviewEnvelope>('submit_session_review_api', {
     categoryType,
@@ -218,23 +670,174 @@ export async function submitSessionReview(
     token,
     votecrew,
     postAsPrivate,
+    reviewer: reviewer ?? null,
+  })
+}
+
+// Webhook do Discord: depois de um submit aceito o Rust posta o resumo da sessão.
+export interface DiscordWebhookConfig {
+  webhookUrl: string | null
+}
+
+// As `posted` primeiras de `total` mensagens chegaram; `error` = onde parou (null = todas).
+export interface DiscordWebhookStatus {
+  posted: number
+  total: number
+  error: string | null
+}
+
+export async function getDiscordWebhook(): Promise<DiscordWebhookConfig> {
+  return await invoke<DiscordWebhookConfig>('get_discord_webhook')
+}
+
+// null/vazio desliga
+export async function setDiscordWebhook(webhookUrl: string | null): Promise<DiscordWebhookConfig> {
+  return await invoke<DiscordWebhookConfig>('set_discord_webhook', { webhookUrl })
+}
+
+// Posta um resumo de exemplo (na URL salva ou em `webhookUrl`, ex.: um stub local).
+export async function testDiscordWebhook(webhookUrl?: string | null): Promise<DiscordWebhookStatus> {
+  return await invoke<DiscordWebhookStatus>('test_discord_webhook', { webhookUrl: webhookUrl ?? null })
+}
+
+export async function onDiscordWebhookStatus(cb: (status: DiscordWebhookStatus) => void): Promise<UnlistenFn> {
+  return await listen<DiscordWebhookStatus>('discord_webhook_status', (event) => cb(event.payload))
+}
+
+export interface SubmitterCount {
+  submitter: string
+  // mapas na fila (inclui ignorados no import e repetidos)
+  total: number
+  counted: number
+  overflow: number
+}
+
+export interface OverflowItem {
+  id: string
+  mapcode: string
+  submitter: string
+  // posição entre os mapas que contam desse submitter (1-based)
+  position: number
+  // texto padrão para o review ao marcar como ignored
+  reason: string
+}
+
+export interface SubmissionLimitReport {
+  // null = sessão sem limite
+  limit: number | null
+  overLimit: SubmitterCount[]
+  // na ordem da fila
+  overflow: OverflowItem[]
+  withoutSubmitter: number
+}
+
+// Conta na ordem da fila; itens com importedIgnored não contam.
+export async function checkSubmissionLimits(
+  items: Array<{ id: string; mapcode: string; submitter?: string | null; importedIgnored?: boolean | null }>,
+  limitPerUser: number | null | undefined,
+  // sem limitPerUser usa o submissionLimit da categoria
+  category?: string | null,
+): Promise<SubmissionLimitReport> {
+  return await invoke<SubmissionLimitReport>('check_submission_limits', {
+    items,
+    limitPerUser: limitPerUser ?? null,
+    category: category ?? null,
   })
 }
 
 export interface MapInfoEntry {
   id: number
+  // vazio quando a origem não tem (diretório local)
   author: string
   xml: string
-  p: number
+  p: number | null
+}
+
+export interface MapInfoResult {
+  id: number
+  status: 'found' | 'notFound' | 'failed'
+  cached: boolean
+  // cache vencido servido porque o fetch falhou
+  stale: boolean
+  error?: string
 }
 
 export interface MapInfoResponse {
+  // true só quando nenhum id pôde ser resolvido
   error: boolean
   data: MapInfoEntry[]
+  // quantos itens vieram do cache local
+  cached: number
+  results: MapInfoResult[]
+}
+
+export interface MapInfoProgress {
+  done: number
+  total: number
+  found: number
+  notFound: number
+  failed: number
+  cached: number
+}
+
+export async function onMapInfoProgress(cb: (progress: MapInfoProgress) => void): Promise<UnlistenFn> {
+  return await listen<MapInfoProgress>('map_info_progress', (event) => cb(event.payload))
+}
+
+export async function fetchMapInfo(mapIds: number[], forceRefresh = false): Promise<MapInfoResponse> {
+  return await invoke<MapInfoResponse>('fetch_map_info', { mapIds, forceRefresh })
+}
+
+export interface MapInfoCacheItem {
+  id: number
+  author: string
+  p: number
+  fetchedAt: number
+  expired: boolean
+  bytes: number
+}
+
+export interface MapInfoCacheStats {
+  dir: string
+  ttlSecs: number
+  entries: number
+  expired: number
+  bytes: number
+  oldestFetchedAt: number | null
+  newestFetchedAt: number | null
+  items: MapInfoCacheItem[]
+}
+
+export type MapInfoProviderKind = 'cypher801' | 'localDir'
+
+export interface MapInfoProviderInfo {
+  kind: MapInfoProviderKind
+  // URL base ou diretório
+  location: string
+}
+
+// Troca na hora e fica salvo no app data dir; campos vazios vêm do env/.env.
+export type MapInfoProviderConfig =
+  | { kind: 'cypher801'; baseUrl?: string; key?: string }
+  | { kind: 'localDir'; dir: string }
+  // apaga o salvo e volta para o configurado via env/.env
+  | { kind: 'default' }
+
+export async function getMapInfoProvider(): Promise<MapInfoProviderInfo> {
+  return await invoke<MapInfoProviderInfo>('get_map_info_provider')
+}
+
+export async function setMapInfoProvider(config: MapInfoProviderConfig): Promise<MapInfoProviderInfo> {
+  return await invoke<MapInfoProviderInfo>('set_map_info_provider', { args: config })
+}
+
+export async function mapInfoCacheStats(): Promise<MapInfoCacheStats> {
+  return await invoke<MapInfoCacheStats>('map_info_cache_stats')
 }
 
-export async function fetchMapInfo(mapIds: number[]): Promise<MapInfoResponse> {
-  return await invoke<MapInfoResponse>('fetch_map_info', { mapIds })
+// Retorna quantas entradas foram removidas.
+export async function clearMapInfoCache(expiredOnly = false): Promise<number> {
+  return await invoke<number>('clear_map_info_cache', { expiredOnly })
 }
 
 // -------------------------